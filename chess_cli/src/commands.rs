@@ -0,0 +1,167 @@
+use std::fs;
+use std::io::{self, Read};
+use std::sync::atomic::AtomicBool;
+
+use chess_core::pgn::read_pgn;
+use chess_core::search::{self, Line, LineScore};
+use chess_core::{Game, Move, Setup};
+use serde_json::{json, Map, Value};
+
+/// What a command prints: JSON, or a converted file
+pub enum Output {
+    Json(Value),
+    Text(String),
+}
+
+/// A position given as a FEN, or startpos
+pub fn load_position(fen: &str) -> Result<Game, String> {
+    if fen == "startpos" {
+        return Ok(Game::new());
+    }
+    let setup = Setup::from_fen(fen).map_err(|e| e.to_string())?;
+    Game::from_setup(&setup).map_err(|e| e.to_string())
+}
+
+/// A move in SAN or in coordinate notation, only checked when it is played
+pub fn parse_move(game: &Game, text: &str) -> Result<Move, String> {
+    game.parse_san(text).or_else(|_| text.parse()).map_err(|_| format!("Invalid move {}", text))
+}
+
+/// The contents of a file, or of the standard input for -
+pub fn read_input(path: &str) -> Result<String, String> {
+    let mut text = String::new();
+    if path == "-" {
+        io::stdin().read_to_string(&mut text).map_err(|e| format!("Could not read the standard input: {}", e))?;
+    } else {
+        text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    }
+    Ok(text)
+}
+
+pub fn argument<'a>(args: &'a [String], index: usize, name: &str) -> Result<&'a str, String> {
+    args.get(index).map(String::as_str).ok_or_else(|| format!("Missing argument <{}>", name))
+}
+
+/// Value given after a flag, e.g. --depth 6
+pub fn flag(args: &[String], name: &str, default: u32) -> Result<u32, String> {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => args
+            .get(index + 1)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| format!("{} needs a number", name)),
+        None => Ok(default),
+    }
+}
+
+/// Text given after a flag, e.g. --pieces assets
+pub fn text_flag<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => args.get(index + 1).map(|value| Some(value.as_str())).ok_or_else(|| format!("{} needs a value", name)),
+        None => Ok(None),
+    }
+}
+
+pub fn legal_moves(args: &[String]) -> Result<Output, String> {
+    let game = load_position(argument(args, 0, "fen")?)?;
+    let moves: Vec<Value> = game
+        .all_legal_moves()
+        .into_iter()
+        .map(|mv| json!({ "uci": mv.to_string(), "san": game.to_san(mv).unwrap_or_default() }))
+        .collect();
+    Ok(Output::Json(json!({ "fen": game.to_fen(), "turn": game.turn(), "moves": moves })))
+}
+
+pub fn play(args: &[String]) -> Result<Output, String> {
+    let mut game = load_position(argument(args, 0, "fen")?)?;
+    let mut sans = Vec::new();
+    for (index, text) in args.iter().enumerate().skip(1) {
+        if !game.endgame_status().is_ongoing() {
+            return Err(format!("Move {} ({}): the game is over", index, text));
+        }
+        let mv = parse_move(&game, text).map_err(|e| format!("Move {}: {}", index, e))?;
+        let san = game.to_san(mv).map_err(|e| format!("Move {} ({}): {}", index, text, e))?;
+        game.play(mv).map_err(|e| format!("Move {} ({}): {}", index, text, e))?;
+        game.evaluate_endgame();
+        sans.push(san);
+    }
+    Ok(Output::Json(json!({ "fen": game.to_fen(), "status": game.endgame_status(), "san": sans })))
+}
+
+pub fn validate_pgn(args: &[String]) -> Result<Output, String> {
+    let text = read_input(argument(args, 0, "file")?)?;
+    let games: Vec<Value> = read_pgn(&text)
+        .into_iter()
+        .enumerate()
+        .map(|(index, game)| match game {
+            Ok(game) => {
+                let end = game.final_position();
+                let tags: Map<String, Value> = game.tags.iter().map(|(name, value)| (name.clone(), json!(value))).collect();
+                json!({
+                    "index": index,
+                    "valid": true,
+                    "tags": tags,
+                    "moves": game.moves.len(),
+                    "result": game.result,
+                    "status": end.endgame_status(),
+                    "fen": end.to_fen(),
+                })
+            }
+            Err(e) => json!({ "index": index, "valid": false, "error": e.to_string(), "ply": e.ply, "token": e.token }),
+        })
+        .collect();
+    let valid = games.iter().all(|game| game["valid"] == json!(true));
+    Ok(Output::Json(json!({ "valid": valid, "games": games })))
+}
+
+fn count_positions(game: &mut Game, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut nodes = 0;
+    for mv in game.all_legal_moves() {
+        // The move was just generated as legal
+        game.play(mv).unwrap();
+        nodes += count_positions(game, depth - 1);
+        game.undo();
+    }
+    nodes
+}
+
+pub fn perft(args: &[String]) -> Result<Output, String> {
+    let mut game = load_position(argument(args, 0, "fen")?)?;
+    let depth: u32 = argument(args, 1, "depth")?.parse().map_err(|_| "The depth is a number".to_string())?;
+    if depth == 0 {
+        return Ok(Output::Json(json!({ "fen": game.to_fen(), "depth": 0, "nodes": 1, "divide": {} })));
+    }
+
+    let mut divide = Map::new();
+    let mut nodes = 0;
+    for mv in game.all_legal_moves() {
+        game.play(mv).unwrap();
+        let count = count_positions(&mut game, depth - 1);
+        game.undo();
+        divide.insert(mv.to_string(), json!(count));
+        nodes += count;
+    }
+    Ok(Output::Json(json!({ "fen": game.to_fen(), "depth": depth, "nodes": nodes, "divide": divide })))
+}
+
+fn line_json(line: &Line) -> Value {
+    let score = match line.score {
+        LineScore::Centipawns(centipawns) => json!({ "cp": centipawns }),
+        LineScore::Mate(moves) => json!({ "mate": moves }),
+    };
+    let uci: Vec<String> = line.moves.iter().map(Move::to_string).collect();
+    json!({ "score": score, "depth": line.depth, "uci": uci, "san": line.san })
+}
+
+pub fn analyze(args: &[String]) -> Result<Output, String> {
+    let game = load_position(argument(args, 0, "fen")?)?;
+    let depth = flag(args, "--depth", 5)?;
+    let lines = flag(args, "--lines", 1)?.max(1) as usize;
+
+    let mut best = Vec::new();
+    search::analyze(&game, depth.max(1), lines, &AtomicBool::new(false), |found| best = found.to_vec());
+    let lines: Vec<Value> = best.iter().map(line_json).collect();
+    Ok(Output::Json(json!({ "fen": game.to_fen(), "status": game.endgame_status(), "lines": lines })))
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use chess_core::pgn::{read_pgn, PgnGame};
+use chess_core::{Game, Move, Variant};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::{load_position, parse_move, read_input, Output};
+
+/// A game in the JSON format, the moves in SAN
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonGame {
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+    /// Starting position, the usual one when it is left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fen: Option<String>,
+    moves: Vec<String>,
+    /// Worked out from the moves when it is left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonGames {
+    games: Vec<JsonGame>,
+}
+
+pub fn convert(args: &[String]) -> Result<Output, String> {
+    let [from, to, path] = args else {
+        return Err("convert needs <from> <to> <file>, the formats are pgn, fen and json".to_string());
+    };
+    let text = read_input(path)?;
+    let games = match from.as_str() {
+        "pgn" => read_pgn(&text).into_iter().collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?,
+        "fen" => read_fens(&text)?,
+        "json" => read_json(&text)?,
+        _ => return Err(format!("Unknown format {}, use pgn, fen or json", from)),
+    };
+    match to.as_str() {
+        "pgn" => Ok(Output::Text(games.iter().map(PgnGame::to_pgn).collect::<Vec<_>>().join("\n"))),
+        "fen" => Ok(Output::Text(write_fens(&games))),
+        "json" => Ok(Output::Json(serde_json::to_value(write_json(&games)).map_err(|e| e.to_string())?)),
+        _ => Err(format!("Unknown format {}, use pgn, fen or json", to)),
+    }
+}
+
+/// The board, side to move, castling and en passant fields, the counters aside
+fn position_fields(fen: &str) -> Vec<&str> {
+    fen.split_whitespace().take(4).collect()
+}
+
+/// Games given as the FEN of each of their positions, a line each, with a blank line between games.
+/// The moves are found back from one position to the next
+fn read_fens(text: &str) -> Result<Vec<PgnGame>, String> {
+    let lines: Vec<(usize, &str)> = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim())).collect();
+    let mut games = Vec::new();
+    for block in lines.split(|(_, line)| line.is_empty()).filter(|block| !block.is_empty()) {
+        let (first_line, first) = block[0];
+        let mut game = load_position(first).map_err(|e| format!("Line {}: {}", first_line, e))?;
+        for &(line, fen) in &block[1..] {
+            let wanted = position_fields(fen);
+            let mv = game
+                .all_legal_moves()
+                .into_iter()
+                .find(|&mv| {
+                    let mut next = game.clone();
+                    next.play(mv).is_ok() && position_fields(&next.to_fen()) == wanted
+                })
+                .ok_or_else(|| format!("Line {}: no legal move leads to this position from the one before", line))?;
+            game.play(mv).unwrap();
+        }
+        game.evaluate_endgame();
+        games.push(PgnGame::from_game(&game));
+    }
+    Ok(games)
+}
+
+fn write_fens(games: &[PgnGame]) -> String {
+    let mut blocks = Vec::new();
+    for pgn_game in games {
+        let mut game = pgn_game.start.clone();
+        let mut block = game.to_fen() + "\n";
+        for &mv in &pgn_game.moves {
+            game.play(mv).unwrap();
+            block.push_str(&game.to_fen());
+            block.push('\n');
+        }
+        blocks.push(block);
+    }
+    blocks.join("\n")
+}
+
+fn read_json(text: &str) -> Result<Vec<PgnGame>, String> {
+    let json: JsonGames = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut games = Vec::new();
+    for (index, json_game) in json.games.into_iter().enumerate() {
+        let variant = match json_game.tags.get("Variant") {
+            Some(name) if name.eq_ignore_ascii_case("crazyhouse") => Variant::Crazyhouse,
+            _ => Variant::Standard,
+        };
+        let mut game = match &json_game.fen {
+            Some(fen) => load_position(fen).map_err(|e| format!("Game {}: {}", index + 1, e))?,
+            None => Game::with_variant(variant),
+        };
+        for text in &json_game.moves {
+            let mv: Move = parse_move(&game, text).map_err(|e| format!("Game {}: {}", index + 1, e))?;
+            game.play(mv).map_err(|e| format!("Game {}, move {}: {}", index + 1, text, e))?;
+        }
+        game.evaluate_endgame();
+
+        let mut pgn_game = PgnGame::from_game(&game);
+        pgn_game.tags = json_game.tags.into_iter().collect();
+        if let Some(result) = json_game.result {
+            pgn_game.result = result;
+        }
+        games.push(pgn_game);
+    }
+    Ok(games)
+}
+
+fn write_json(games: &[PgnGame]) -> JsonGames {
+    let games = games
+        .iter()
+        .map(|game| JsonGame {
+            tags: game.tags.iter().cloned().collect(),
+            fen: Some(game.start.to_fen()).filter(|fen| *fen != Game::with_variant(game.start.variant()).to_fen()),
+            moves: game.start.line_to_san(&game.moves),
+            result: Some(game.result.clone()),
+        })
+        .collect();
+    JsonGames { games }
+}
@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use chess_core::pgn::read_pgn;
+use chess_core::{Color, Move, Square};
+use chess_export::{save_game_gif, save_position_png, ExportOptions, PieceImages, Theme};
+use serde_json::json;
+
+use crate::commands::{argument, flag, load_position, read_input, text_flag, Output};
+
+/// The options shared by image and gif, with the piece images they draw
+fn export_options(args: &[String]) -> Result<(ExportOptions, PieceImages), String> {
+    let mut options = ExportOptions { square_size: flag(args, "--size", 64)?.clamp(8, 512), ..Default::default() };
+    if args.iter().any(|arg| arg == "--flip") {
+        options.orientation = Color::Black;
+    }
+    if let Some(name) = text_flag(args, "--theme")? {
+        options.theme = Theme::from_name(name).ok_or_else(|| format!("Unknown theme {}, use classic, brown, green or blue", name))?;
+    }
+    let images = PieceImages::load(text_flag(args, "--pieces")?.unwrap_or("assets"))?;
+    Ok((options, images))
+}
+
+/// A list separated by commas, e.g. e4,d5
+fn parse_list<T: std::str::FromStr>(text: &str, what: &str) -> Result<Vec<T>, String> {
+    text.split(',').filter(|item| !item.is_empty()).map(|item| item.parse().map_err(|_| format!("Invalid {} {}", what, item))).collect()
+}
+
+pub fn image(args: &[String]) -> Result<Output, String> {
+    let game = load_position(argument(args, 0, "fen")?)?;
+    let path = argument(args, 1, "file.png")?;
+    let (mut options, images) = export_options(args)?;
+    if let Some(squares) = text_flag(args, "--highlight")? {
+        options.highlights = parse_list::<Square>(squares, "square")?;
+    }
+    if let Some(moves) = text_flag(args, "--arrow")? {
+        options.arrows = parse_list::<Move>(moves, "arrow")?
+            .into_iter()
+            .filter_map(|mv| match mv {
+                Move::Normal { start, end, .. } => Some((start, end)),
+                Move::Drop { .. } => None,
+            })
+            .collect();
+    }
+
+    save_position_png(&game, &images, &options, Path::new(path))?;
+    Ok(Output::Json(json!({ "file": path, "fen": game.to_fen(), "size": options.square_size * 8 })))
+}
+
+pub fn gif(args: &[String]) -> Result<Output, String> {
+    let text = read_input(argument(args, 0, "pgn file")?)?;
+    let path = argument(args, 1, "file.gif")?;
+    let index = flag(args, "--game", 1)?.max(1) as usize;
+    let delay = flag(args, "--delay", 1000)?;
+    let (options, images) = export_options(args)?;
+
+    let game = read_pgn(&text)
+        .into_iter()
+        .nth(index - 1)
+        .ok_or_else(|| format!("There is no game {} in the file", index))?
+        .map_err(|e| format!("Game {}: {}", index, e))?
+        .final_position();
+    save_game_gif(&game, &images, &options, delay, Path::new(path))?;
+    Ok(Output::Json(json!({
+        "file": path,
+        "frames": game.history().len() + 1,
+        "fen": game.to_fen(),
+        "status": game.endgame_status(),
+    })))
+}
@@ -0,0 +1,81 @@
+mod commands;
+mod convert;
+mod export;
+
+use std::process::ExitCode;
+
+use serde_json::json;
+
+use crate::commands::Output;
+
+const USAGE: &str = "Usage: chess_cli <command> [arguments]
+
+Positions are given as a FEN in one argument, or startpos for the starting position.
+Moves are given in SAN (Nf3, O-O, e8=Q) or in coordinate notation (g1f3, e7e8q).
+
+Commands:
+  legal-moves <fen>                   the legal moves of the position
+  play <fen> <moves...>               plays the moves, prints the FEN reached and the end of the game
+  validate-pgn <file>                 checks every game of a PGN file
+  convert <from> <to> <file>          converts games between pgn, fen (a FEN per position, a blank line between games) and json,
+                                      - reads the standard input
+  perft <fen> <depth>                 counts the positions reached after depth moves, for each first move
+  analyze <fen> [--depth N] [--lines N]  searches the best lines
+  image <fen> <file.png> [options] [--highlight e4,d5] [--arrow e2e4,g1f3]
+                                      draws the position, its last move and check highlighted
+  gif <pgn file> <file.gif> [options] [--game N] [--delay ms]
+                                      draws every position of a game, one frame per move
+
+Options of image and gif: --size N (pixels of a square), --flip (black at the bottom),
+--theme classic|brown|green|blue, --pieces <directory of the piece images, assets by default>.
+
+The results are printed as JSON, except for conversions to pgn and fen. Errors are printed as {\"error\": ...} with a failure exit code.";
+
+/// The result of a command, None for an unknown command
+fn run(command: &str, args: &[String]) -> Option<Result<Output, String>> {
+    let result = match command {
+        "legal-moves" => commands::legal_moves(args),
+        "play" => commands::play(args),
+        "validate-pgn" => commands::validate_pgn(args),
+        "convert" => convert::convert(args),
+        "perft" => commands::perft(args),
+        "analyze" => commands::analyze(args),
+        "image" => export::image(args),
+        "gif" => export::gif(args),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// What is printed for the result of a command, and the exit code: 1 when the command failed
+fn report(result: Result<Output, String>) -> (String, u8) {
+    match result {
+        Ok(Output::Json(value)) => (format!("{}\n", value), 0),
+        Ok(Output::Text(text)) => (text, 0),
+        Err(e) => (format!("{}\n", json!({ "error": e })), 1),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let Some(result) = run(command, &args[1..]) else {
+        eprintln!("Unknown command {}\n\n{}", command, USAGE);
+        return ExitCode::from(2);
+    };
+    let (text, code) = report(result);
+    print!("{}", text);
+    ExitCode::from(code)
+}
+
+#[cfg(test)]
+#[path = "tests/test_commands.rs"]
+mod test_commands;
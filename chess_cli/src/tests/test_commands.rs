@@ -0,0 +1,82 @@
+use super::{report, run};
+
+const PAWN_ENDING: &str = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+
+/// What the command prints and its exit code
+fn output(command: &str, args: &[&str]) -> (String, u8) {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    report(run(command, &args).expect("A known command"))
+}
+
+fn assert_output(command: &str, args: &[&str], expected: &str) {
+    assert_eq!(output(command, args), (format!("{}\n", expected), 0));
+}
+
+#[test]
+fn test_legal_moves() {
+    assert_output(
+        "legal-moves",
+        &[PAWN_ENDING],
+        r#"{"fen":"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1","moves":[{"san":"Kd1","uci":"e1d1"},{"san":"Kf1","uci":"e1f1"},{"san":"Kd2","uci":"e1d2"},{"san":"Kf2","uci":"e1f2"},{"san":"e3","uci":"e2e3"},{"san":"e4","uci":"e2e4"}],"turn":"White"}"#,
+    );
+}
+
+#[test]
+fn test_play() {
+    assert_output(
+        "play",
+        &["startpos", "f3", "e7e5", "g4", "Qh4"],
+        r#"{"fen":"rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3","san":["f3","e5","g4","Qh4#"],"status":{"Win":["Black","Checkmate"]}}"#,
+    );
+}
+
+#[test]
+fn test_perft() {
+    assert_output(
+        "perft",
+        &[PAWN_ENDING, "2"],
+        r#"{"depth":2,"divide":{"e1d1":5,"e1d2":5,"e1f1":5,"e1f2":5,"e2e3":5,"e2e4":5},"fen":"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1","nodes":30}"#,
+    );
+    assert_output("perft", &["startpos", "0"], r#"{"depth":0,"divide":{},"fen":"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1","nodes":1}"#);
+}
+
+#[test]
+fn test_analyze() {
+    assert_output(
+        "analyze",
+        &["6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "--depth", "3"],
+        r#"{"fen":"6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1","lines":[{"depth":1,"san":["Ra8#"],"score":{"mate":1},"uci":["a1a8"]}],"status":"Ongoing"}"#,
+    );
+}
+
+#[test]
+fn test_convert() {
+    let path = std::env::temp_dir().join(format!("chess_cli_convert_{}.pgn", std::process::id()));
+    std::fs::write(&path, "[Event \"Casual\"]\n\n1. e4 e5 2. Nf3 *\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    assert_output("convert", &["pgn", "json", path], r#"{"games":[{"moves":["e4","e5","Nf3"],"result":"*","tags":{"Event":"Casual"}}]}"#);
+    let fens = output("convert", &["pgn", "fen", path]);
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(
+        fens,
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1
+rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2
+rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2
+"
+            .to_string(),
+            0
+        )
+    );
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(output("legal-moves", &["not a fen"]), ("{\"error\":\"A FEN has 4 or 6 fields\"}\n".to_string(), 1));
+    assert_eq!(output("play", &["startpos", "e4", "e4"]), ("{\"error\":\"Move 2: Invalid move e4\"}\n".to_string(), 1));
+    assert_eq!(output("perft", &["startpos"]), ("{\"error\":\"Missing argument <depth>\"}\n".to_string(), 1));
+    assert_eq!(output("analyze", &["startpos", "--depth"]), ("{\"error\":\"--depth needs a number\"}\n".to_string(), 1));
+    assert!(run("unknown", &[]).is_none());
+}
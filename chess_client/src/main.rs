@@ -4,91 +4,364 @@ mod utils;
 
 use std::cell::Cell;
 
-use chess_core::Game;
+use chess_core::search::Analyzer;
+use chess_core::{Color, Game, GameController, Move, Piece, PieceKind, PromotionPiece, Square};
+use chess_gui::constants::{
+    ANALYSIS_LINES, EVAL_BAR_WIDTH, POCKET_PANEL_WIDTH, SETTINGS_FILE, SIDE_PANEL_WIDTH, WINDOW_HEIGHT,
+};
+use chess_gui::animation::MoveAnimation;
+use chess_gui::review::ReviewScreen;
+use chess_gui::settings::Settings;
+use chess_gui::sound::Sounds;
+use chess_gui::{board, dialogs, input, panels};
+use chess_network::client::{Connection, NetworkEvent};
+use chess_network::{resync, ClientMessage, ServerMessage};
 use macroquad::prelude::{
-    clear_background, is_mouse_button_pressed, next_frame, Conf, MouseButton, WHITE,
+    clear_background, is_key_pressed, is_mouse_button_pressed, is_mouse_button_released, next_frame, Conf, KeyCode,
+    MouseButton, WHITE,
 };
 
-use tokio::runtime;
+use crate::constants::SERVER_ADDRESS;
 
 
+fn save_settings(settings: &Settings) {
+    if let Err(e) = settings.save() {
+        eprintln!("Could not save the settings to {}: {}", SETTINGS_FILE, e);
+    }
+}
+
 // Define the window configuration
 fn window_conf() -> Conf {
+    let pocket_width = if utils::variant_from_args().has_drops() { POCKET_PANEL_WIDTH as i32 } else { 0 };
     Conf {
         window_title: "Chess Game".to_string(),
-        window_width: 784, // Match the board size including borders
-        window_height: 784,
+        window_width: 784 + pocket_width + SIDE_PANEL_WIDTH as i32, // Match the board size including borders, the pockets in drop variants and the side panel
+        window_height: WINDOW_HEIGHT as i32, // The board and the bars of captured pieces above and below it
         fullscreen: false, // Disable fullscreen (optional)
+        window_resizable: true, // The layout is scaled to fit the window
         ..Default::default()
     }
 }
 
 #[macroquad::main(window_conf)]
 async fn main() {
-    let textures = client_gui::load_textures().await.unwrap();
+    let mut settings = Settings::load();
+    if let Some(pieces) = utils::pieces_from_args() {
+        settings.pieces = pieces;
+    }
+    let mut textures = match board::load_textures(&settings.pieces, settings.theme).await {
+        Ok(textures) => textures,
+        Err(e) => {
+            eprintln!("Could not load the pieces from {}: {}", settings.pieces, e);
+            settings.pieces = Settings::default().pieces;
+            board::load_textures(&settings.pieces, settings.theme).await.unwrap()
+        }
+    };
+    save_settings(&settings);
+    let sounds = Sounds::load(&settings.sounds).await;
 
-    let mut game = Game::new();
+    let variant = utils::variant_from_args();
+    let mut connection = Connection::connect(SERVER_ADDRESS, variant);
+    let mut game = GameController::new(Game::with_variant(variant));
+    let events = game.subscribe();
+    let mut animation = MoveAnimation::default();
+    let mut player_color: Option<Color> = None; // Known once the server has found an opponent
+    let mut message = Some("Waiting for an opponent...".to_string());
 
-    let mut previous_selected: Option<(usize, usize)> = None;
-    let mut selected: Option<(usize, usize)> = None;
+    let mut move_input = input::MoveInput::default();
+    let mut premove: Option<(Square, Square)> = None; // Played as soon as the opponent has moved
+    let mut dragged: Option<PieceKind> = None; // Piece taken from a pocket
+    let mut pending_promotion: Option<(Square, Square)> = None; // Move sent once the piece is chosen
+    let mut analyzer: Option<Analyzer> = None; // Restarted whenever the position changes, in analysis mode
+    let mut review: Option<ReviewScreen> = None; // Replaces the game until the player goes back to it
+    let mut sans: Vec<String> = Vec::new(); // Notation of the moves played, updated when the moves change
+    let mut san_moves: Vec<Move> = Vec::new(); // The moves sans was written for
+    let mut viewed: Option<(usize, Game)> = None; // Earlier position shown on the board, with the number of moves played up to it
 
     let should_quit = Cell::new(false);
+    let should_restart = Cell::new(false);
+    let should_review = Cell::new(false);
 
-    client_gui::create_skin();
+    dialogs::create_skin();
+    if settings.coordinates || utils::coordinates_from_args() {
+        input::toggle_coordinates();
+    }
 
     loop {
         clear_background(WHITE);
+        input::fit_to_window(game.game().variant());
+
+        // Turn the board around, show the files and ranks or change the board colors, on every screen
+        if is_key_pressed(KeyCode::F) {
+            input::flip_board();
+        }
+        if is_key_pressed(KeyCode::C) {
+            input::toggle_coordinates();
+            settings.coordinates = input::coordinates_shown();
+            save_settings(&settings);
+        }
+        if is_key_pressed(KeyCode::T) {
+            textures.theme = textures.theme.next();
+            settings.theme = textures.theme;
+            save_settings(&settings);
+        }
+        // Change how fast the pieces move, or turn the sound off
+        if is_key_pressed(KeyCode::A) {
+            settings.animation = settings.animation.next();
+            save_settings(&settings);
+        }
+        if is_key_pressed(KeyCode::S) {
+            settings.sound = !settings.sound;
+            save_settings(&settings);
+        }
+
+        // Apply what the server sent since the last frame
+
+        while let Some(event) = connection.try_recv() {
+            match event {
+                NetworkEvent::Message(ServerMessage::GameStarted(color, variant)) => {
+                    game.replace(Game::with_variant(variant));
+                    player_color = Some(color);
+                    input::set_orientation(color); // The player's pieces are at the bottom
+                    message = None;
+                    viewed = None;
+                    premove = None;
+                }
+                NetworkEvent::Message(ServerMessage::OpponentMove(start, end, promotion)) => {
+                    if game.play_move(start, end).is_ok() {
+                        if let Some(promotion_piece) = promotion {
+                            let _ = game.promote_pawn(promotion_piece);
+                        }
+                    }
+                }
+                NetworkEvent::Message(ServerMessage::OpponentDrop(kind, square)) => {
+                    let _ = game.drop_piece(kind, square);
+                }
+                NetworkEvent::Message(ServerMessage::PieceReceived(color, kind)) => {
+                    let _ = game.add_to_pocket(Piece::new(kind, color));
+                }
+                NetworkEvent::Message(ServerMessage::GameOver(endgame_status)) => {
+                    game.end_game(endgame_status);
+                }
+                NetworkEvent::Message(ServerMessage::MoveRejected(reason)) => {
+                    eprintln!("The server rejected the last move: {}", reason);
+                }
+                NetworkEvent::Message(ServerMessage::Resync(fen)) => {
+                    if let Some(server_game) = resync(game.game(), &fen) {
+                        game.replace(server_game);
+                    }
+                    viewed = None;
+                    premove = None;
+                    pending_promotion = None;
+                }
+                NetworkEvent::Message(_) => {}
+                NetworkEvent::Disconnected(reason) => {
+                    if game.game().endgame_status().is_ongoing() {
+                        message = Some(format!("Disconnected: {}", reason));
+                        player_color = None;
+                    }
+                }
+            }
+        }
 
-        client_gui::draw_board(&game, &textures);
+        if let Some(review_screen) = &mut review {
+            if review_screen.run_frame(&textures) {
+                review = None;
+            }
+            next_frame().await;
+            continue;
+        }
+
+        if san_moves != game.game().history() {
+            sans = game.game().history_to_san();
+            san_moves = game.game().history().to_vec();
+        }
 
-        // Handle input and show promotion menu
+        // Step through the moves played without leaving the game, reaching the last move goes back to it
+        let shown_ply = viewed.as_ref().map_or(game.game().history().len(), |(ply, _)| *ply);
+        let mut view_ply = None;
+        if is_key_pressed(KeyCode::Left) && dragged.is_none() {
+            view_ply = shown_ply.checked_sub(1);
+        }
+        if is_key_pressed(KeyCode::Right) && dragged.is_none() && shown_ply < game.game().history().len() {
+            view_ply = Some(shown_ply + 1);
+        }
+
+        // Claim a draw by threefold repetition or the fifty-move rule, the server checks it
+        if is_key_pressed(KeyCode::D) && player_color.is_some() && game.game().endgame_status().is_ongoing() {
+            connection.send(ClientMessage::ClaimDraw);
+        }
+
+        // Animate and sound the moves played since the last frame, the opponent's as well
+        let new_events: Vec<_> = events.try_iter().collect();
+        animation.update(&new_events, game.game(), settings.animation);
+        if settings.sound {
+            sounds.play(&new_events);
+        }
 
-        if let Some((column, color)) = game.is_promotion_pending() {
-            client_gui::show_promotion_menu((column, color), &textures);
+        let board = viewed.as_ref().map_or(game.game(), |(_, viewed_game)| viewed_game);
+        if viewed.is_none() {
+            let hidden: Vec<_> = move_input.dragged().into_iter().chain(animation.moving_squares()).collect();
+            board::draw_board(board, &hidden, &textures);
+            animation.draw(&textures);
+        } else {
+            board::draw_board(board, &[], &textures);
+        }
+        board::draw_captured_pieces(board, &textures);
+        if board.variant().has_drops() {
+            board::draw_pockets(board, &textures);
+        }
+
+        // The side panel shows the analysis above the moves
+        let panel_x = input::side_panel_x(game.game().variant());
+        let text_x = if utils::analysis_from_args() { panel_x + EVAL_BAR_WIDTH + 16.0 } else { panel_x + 20.0 };
+        let mut section_top = 40.0;
+        if utils::analysis_from_args() {
+            let analyzed = board.clone();
+            if analyzer.as_ref().is_none_or(|analyzer| !analyzer.is_analyzing(&analyzed)) {
+                analyzer = Some(Analyzer::start(&analyzed, ANALYSIS_LINES));
+            }
+            let lines = analyzer.as_mut().map_or(&[][..], |analyzer| analyzer.lines());
+            if analyzed.endgame_status().is_ongoing() {
+                board::draw_best_move_arrows(lines);
+            }
+            panels::draw_eval_bar(panel_x + 8.0, lines.first().map(|line| line.score));
+            let analysis_height = 40.0 + ANALYSIS_LINES as f32 * 100.0;
+            let text_width = panel_x + SIDE_PANEL_WIDTH - text_x - 8.0;
+            panels::draw_analysis_lines(text_x, section_top, section_top + analysis_height - 60.0, text_width, lines, &analyzed);
+            section_top += analysis_height;
+        }
+        if let Some(ply) = panels::draw_move_list(text_x, section_top, WINDOW_HEIGHT - 40.0, &sans, shown_ply) {
+            view_ply = Some(ply);
+        }
+        client_gui::draw_move_hints(text_x, viewed.is_some());
+
+        if let Some(ply) = view_ply {
+            viewed = (ply < game.game().history().len()).then(|| {
+                let mut viewed_game = game.game().clone();
+                while viewed_game.history().len() > ply {
+                    viewed_game.undo();
+                }
+                (ply, viewed_game)
+            });
+            move_input.clear();
+        }
+
+        // Handle input and show promotion menu, only when it's the player's turn
+
+        // A right click cancels the premove, otherwise it is played once the opponent has moved, if it is legal then.
+        // A pawn premoved to the last rank becomes a queen
+        if is_mouse_button_pressed(MouseButton::Right) {
+            premove = None;
+            move_input.clear();
+        }
+        let position = game.game();
+        if player_color == Some(position.turn()) && position.endgame_status().is_ongoing() && position.is_promotion_pending().is_none() {
+            if let Some((start, end)) = premove.take() {
+                if game.play_move(start, end).is_ok() {
+                    let promotion = game.game().is_promotion_pending().map(|_| PromotionPiece::Queen);
+                    if let Some(promotion_piece) = promotion {
+                        game.promote_pawn(promotion_piece).unwrap();
+                    }
+                    connection.send(ClientMessage::SendMove(start, end, promotion));
+                }
+            }
+        }
+
+        let can_play = player_color == Some(game.game().turn()) && game.game().endgame_status().is_ongoing() && viewed.is_none();
+
+        if let Some((column, color)) = game.game().is_promotion_pending() {
+            board::show_promotion_menu((column, color), &textures);
             if is_mouse_button_pressed(MouseButton::Left) {
-                if let Some(square) = utils::select_square() {
+                if let Some(square) = input::select_square() {
                     if let Some(promotion_piece) =
-                        utils::select_promotion_piece(square, column, color)
+                        input::select_promotion_piece(square, column, color)
                     {
                         game.promote_pawn(promotion_piece).unwrap();
+                        if let Some((start, end)) = pending_promotion.take() {
+                            connection.send(ClientMessage::SendMove(start, end, Some(promotion_piece)));
+                        }
                     }
                 }
             }
-        } else if is_mouse_button_pressed(MouseButton::Left) {
-            if let Some(square) = utils::select_square() {
-                if let Some((row, col)) = selected {
-                    if square == (row, col) {
-                        selected = None;
-                    } else {
-                        previous_selected = selected;
-                        selected = Some(square);
+        } else if let Some(kind) = dragged {
+            // The piece is dropped when the mouse button is released
+            if is_mouse_button_released(MouseButton::Left) {
+                if let Some(square) = input::select_square() {
+                    if game.drop_piece(kind, square).is_ok() {
+                        connection.send(ClientMessage::SendDrop(kind, square));
                     }
-                } else {
-                    selected = Some(square);
                 }
+                dragged = None;
             }
-
-            if let (Some(start), Some(end)) = (previous_selected, selected) {
-                if let Err(_e) = game.play_move(start, end) {
-                    //println!("{}", _e);
+        } else if let Some(kind) = input::select_pocket_piece(game.game().turn()).filter(|_| can_play && is_mouse_button_pressed(MouseButton::Left)) {
+            let position = game.game();
+            if position.variant().has_drops() && position.pocket(position.turn()).count(kind) > 0 {
+                dragged = Some(kind);
+                move_input.clear();
+            }
+        } else if let Some(color) = player_color.filter(|_| game.game().endgame_status().is_ongoing() && viewed.is_none()) {
+            // During the opponent's turn, the move is kept as a premove
+            move_input.update(&mut game, color, |game, start, end| {
+                if !can_play {
+                    premove = Some((start, end));
+                    return true;
+                }
+                if game.play_move(start, end).is_err() {
+                    return false;
+                }
+                if game.game().is_promotion_pending().is_some() {
+                    pending_promotion = Some((start, end));
                 } else {
-                    previous_selected = None;
-                    selected = None;
-
-                    game.evaluate_endgame(); // Check if the game is over, will set endgame_status
+                    connection.send(ClientMessage::SendMove(start, end, None));
                 }
-            }
+                true
+            });
         }
 
-        if game.endgame_status() != chess_core::EndgameStatus::Ongoing {
-            client_gui::draw_game_over_box(&should_quit, &mut game);
+        if viewed.is_some() {
+            // The board shows an earlier position, the box would hide it
+        } else if game.game().endgame_status() != chess_core::EndgameStatus::Ongoing {
+            dialogs::draw_game_over_box(&should_quit, &should_restart, &should_review, game.game());
+        } else if let Some(text) = &message {
+            dialogs::draw_message_box(text);
         }
 
         if should_quit.get() {
             break;
         }
 
-        client_gui::show_legal_moves(&mut game, selected, &textures);
+        if should_review.replace(false) {
+            review = Some(ReviewScreen::new(game.game()));
+            move_input.clear();
+        }
+
+        if should_restart.get() {
+            should_restart.set(false);
+            connection = Connection::connect(SERVER_ADDRESS, variant);
+            game.replace(Game::with_variant(variant));
+            player_color = None;
+            message = Some("Waiting for an opponent...".to_string());
+            viewed = None;
+            premove = None;
+        }
+
+        if can_play {
+            board::show_legal_moves(game.game(), move_input.selected(), &textures);
+        }
+        if let Some(premove) = premove {
+            board::draw_premove(premove);
+        }
+        if let Some(square) = move_input.dragged() {
+            if let Some(piece) = game.game().piece_at(square) {
+                board::draw_dragged_piece(piece, &textures);
+            }
+        }
+
+        if let Some(kind) = dragged {
+            board::show_legal_drops(game.game(), kind, &textures);
+            board::draw_dragged_piece(Piece::new(kind, game.game().turn()), &textures);
+        }
 
         next_frame().await;
     }
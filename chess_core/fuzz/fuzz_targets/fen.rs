@@ -0,0 +1,30 @@
+#![no_main]
+
+use chess_core::pgn::read_pgn;
+use chess_core::{Game, Setup};
+use libfuzzer_sys::fuzz_target;
+
+// FEN and PGN text comes from files and from users, reading it must never panic
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    for game in read_pgn(text).into_iter().flatten() {
+        let _ = game.to_pgn();
+        let _ = game.final_position();
+    }
+
+    let Ok(setup) = Setup::from_fen(text) else {
+        return;
+    };
+    let Ok(game) = Game::from_setup(&setup) else {
+        return;
+    };
+    let fen = game.to_fen();
+    assert_eq!(Setup::from_fen(&fen).map(|setup| Game::from_setup(&setup).map(|game| game.to_fen())), Ok(Ok(fen)), "The FEN written reads back");
+    for mv in game.all_legal_moves() {
+        let mut next = game.clone();
+        assert!(next.play(mv).is_ok(), "Legal moves can be played");
+        next.evaluate_endgame();
+    }
+});
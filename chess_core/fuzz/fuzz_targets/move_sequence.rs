@@ -0,0 +1,66 @@
+#![no_main]
+
+use chess_core::{Color, File, Game, Move, Piece, PieceKind, PromotionPiece, Rank, Square, Variant};
+use libfuzzer_sys::fuzz_target;
+
+fn square(byte: u8) -> Square {
+    Square::new(File::ALL[(byte & 0x07) as usize], Rank::ALL[(byte >> 4) as usize % 8])
+}
+
+fn kind(byte: u8) -> PieceKind {
+    [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen, PieceKind::King][byte as usize % 6]
+}
+
+fn promotion(byte: u8) -> PromotionPiece {
+    [PromotionPiece::Queen, PromotionPiece::Rook, PromotionPiece::Bishop, PromotionPiece::Knight][byte as usize % 4]
+}
+
+// Each action takes three bytes: what to do, then two values read as squares, pieces or an index.
+// Half of the moves are picked among the legal ones so that games go deep, the others are anything a client could send
+fuzz_target!(|data: &[u8]| {
+    let Some((&variant, actions)) = data.split_first() else {
+        return;
+    };
+    let variant = [Variant::Standard, Variant::Crazyhouse, Variant::Bughouse][variant as usize % 3];
+    let mut game = Game::with_variant(variant);
+
+    for action in actions.chunks_exact(3) {
+        let (action, a, b) = (action[0], action[1], action[2]);
+        let before = game.to_fen();
+        let result = match action % 8 {
+            0..=3 => {
+                let legal = game.all_legal_moves();
+                if legal.is_empty() {
+                    continue;
+                }
+                let mv = legal[((a as usize) << 8 | b as usize) % legal.len()];
+                let san = game.to_san(mv).expect("Legal moves have a notation");
+                assert_eq!(game.parse_san(&san), Ok(mv), "The notation reads back as the same move");
+                assert!(game.play(mv).is_ok(), "Legal moves can be played");
+                game.evaluate_endgame();
+                continue;
+            }
+            4 => game.play_move(square(a), square(b)).map(|_| ()),
+            5 => game.play(Move::Normal { start: square(a), end: square(b), promotion: (a & 1 == 1).then(|| promotion(b)) }).map(|_| ()),
+            6 => match a % 3 {
+                0 => game.drop_piece(kind(a / 3), square(b)),
+                1 => game.promote_pawn(promotion(b)),
+                _ => game.add_to_pocket(Piece::new(kind(b), if b & 0x80 == 0 { Color::White } else { Color::Black })),
+            },
+            _ => {
+                game.undo();
+                continue;
+            }
+        };
+        if result.is_err() {
+            assert_eq!(game.to_fen(), before, "A refused move leaves the game untouched");
+        }
+
+        let _ = game.piece_at(square(a));
+        let _ = game.get_legal_moves(square(b));
+        let _ = game.get_legal_drops(kind(a));
+        let _ = game.is_in_check();
+    }
+    let _ = game.history_to_san();
+    let _ = game.polyglot_key();
+});
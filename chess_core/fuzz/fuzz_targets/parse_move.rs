@@ -0,0 +1,20 @@
+#![no_main]
+
+use chess_core::{parse_move, Game, Move};
+use libfuzzer_sys::fuzz_target;
+
+// Typed moves come from players and from the network, reading them must never panic
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let _ = parse_move(text);
+    if let Ok(mv) = text.parse::<Move>() {
+        assert_eq!(mv.to_string().parse::<Move>(), Ok(mv), "Coordinate notation reads back as the same move");
+    }
+    let game = Game::new();
+    if let Ok(mv) = game.parse_san(text) {
+        let mut game = game.clone();
+        assert!(game.play(mv).is_ok(), "parse_san only returns legal moves");
+    }
+});
@@ -0,0 +1,68 @@
+use crate::core_struct::{Board, Color, Piece};
+use crate::game::GameState;
+
+use super::random64::RANDOM64;
+
+const CASTLE_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+/// Index of the piece in the Polyglot key table: black pawn, white pawn, black knight, white knight...
+fn piece_index(piece: Piece) -> usize {
+    let kind = match piece {
+        Piece::Pawn(_) => 0,
+        Piece::Knight(_) => 1,
+        Piece::Bishop(_) => 2,
+        Piece::Rook(_) => 3,
+        Piece::Queen(_) => 4,
+        Piece::King(_) => 5,
+    };
+    match piece.color() {
+        Color::Black => 2 * kind,
+        Color::White => 2 * kind + 1,
+    }
+}
+
+/// The Zobrist key of the position as defined by the Polyglot book format
+pub(crate) fn polyglot_key(board: &Board, game_state: &GameState) -> u64 {
+    let mut key = 0;
+
+    for (i, row) in board.grid.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            if let Some(piece) = cell {
+                key ^= RANDOM64[64 * piece_index(*piece) + 8 * i + j];
+            }
+        }
+    }
+
+    let castling_rights = [
+        game_state.white_castle_king_side,
+        game_state.white_castle_queen_side,
+        game_state.black_castle_king_side,
+        game_state.black_castle_queen_side,
+    ];
+    for (index, can_castle) in castling_rights.into_iter().enumerate() {
+        if can_castle {
+            key ^= RANDOM64[CASTLE_OFFSET + index];
+        }
+    }
+
+    // The en passant file only counts if a pawn of the side to move stands next to the pawn that can be taken
+    if let Some((file, _)) = game_state.en_passant {
+        let (rank, pawn) = match game_state.turn {
+            Color::White => (4, Piece::Pawn(Color::White)),
+            Color::Black => (3, Piece::Pawn(Color::Black)),
+        };
+        let can_take = (file > 0 && board.grid[rank][file - 1] == Some(pawn))
+            || (file < 7 && board.grid[rank][file + 1] == Some(pawn));
+        if can_take {
+            key ^= RANDOM64[EN_PASSANT_OFFSET + file];
+        }
+    }
+
+    if game_state.turn == Color::White {
+        key ^= RANDOM64[TURN_OFFSET];
+    }
+
+    key
+}
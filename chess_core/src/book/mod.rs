@@ -0,0 +1,6 @@
+mod key;
+mod polyglot;
+mod random64;
+
+pub(crate) use key::polyglot_key;
+pub use polyglot::{BookMove, OpeningBook};
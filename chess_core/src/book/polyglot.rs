@@ -0,0 +1,145 @@
+use std::{fs, io, path::Path};
+
+use rand::Rng;
+
+use crate::core_struct::Piece;
+use crate::game::{Game, PromotionPiece};
+use crate::square::{File, Rank, Square};
+
+const ENTRY_SIZE: usize = 16;
+
+/// One record of a Polyglot book: 16 bytes, big endian
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    key: u64,
+    raw_move: u16,
+    weight: u16,
+}
+
+/// A move found in the book for a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    pub start: Square,
+    pub end: Square,
+    pub promotion: Option<PromotionPiece>,
+    pub weight: u16,
+}
+
+/// Opening book in the Polyglot .bin format
+#[derive(Debug, Default)]
+pub struct OpeningBook {
+    entries: Vec<Entry>, // Sorted by key
+}
+
+impl OpeningBook {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<OpeningBook> {
+        let bytes = fs::read(path)?;
+        OpeningBook::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<OpeningBook> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "The book size is not a multiple of 16 bytes"));
+        }
+
+        let mut entries: Vec<Entry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| Entry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                // The last 4 bytes are the learn value, unused
+            })
+            .collect();
+        // Books written by Polyglot are already sorted, this keeps the lookup correct for the others
+        entries.sort_by_key(|entry| entry.key);
+
+        Ok(OpeningBook { entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The book moves for the current position, in the order of the book
+    pub fn moves(&self, game: &Game) -> Vec<BookMove> {
+        let key = game.polyglot_key();
+        let first = self.entries.partition_point(|entry| entry.key < key);
+
+        self.entries[first..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| decode_move(entry, game))
+            .collect()
+    }
+
+    /// The book move with the highest weight
+    pub fn best_move(&self, game: &Game) -> Option<BookMove> {
+        // max_by_key keeps the last maximum, the first one is the book's preferred move
+        self.moves(game).into_iter().rev().max_by_key(|book_move| book_move.weight)
+    }
+
+    /// A book move picked at random, more often the higher its weight
+    pub fn random_move(&self, game: &Game) -> Option<BookMove> {
+        let moves = self.moves(game);
+        let total: u32 = moves.iter().map(|book_move| book_move.weight as u32).sum();
+        if total == 0 {
+            // Every weight is 0, all moves are as good
+            return match moves.len() {
+                0 => None,
+                len => Some(moves[rand::thread_rng().gen_range(0..len)]),
+            };
+        }
+
+        let mut pick = rand::thread_rng().gen_range(0..total);
+        for book_move in moves {
+            if pick < book_move.weight as u32 {
+                return Some(book_move);
+            }
+            pick -= book_move.weight as u32;
+        }
+        None
+    }
+}
+
+/// Move bits: to file 0-2, to row 3-5, from file 6-8, from row 9-11, promotion piece 12-14
+fn decode_move(entry: &Entry, game: &Game) -> Option<BookMove> {
+    let field = |shift: u16| ((entry.raw_move >> shift) & 7) as usize;
+    let square = |rank, file| Square::new(File::ALL[field(file)], Rank::ALL[field(rank)]);
+    let start = square(9, 6);
+    let mut end = square(3, 0);
+    let promotion = match field(12) {
+        0 => None,
+        1 => Some(PromotionPiece::Knight),
+        2 => Some(PromotionPiece::Bishop),
+        3 => Some(PromotionPiece::Rook),
+        4 => Some(PromotionPiece::Queen),
+        _ => return None,
+    };
+
+    // Skip moves of the wrong side, the key could match another position
+    let piece = game.piece_at(start)?;
+    if piece.color() != game.turn() {
+        return None;
+    }
+
+    // Castling is stored as the king taking its own rook
+    let back_rank = Rank::back_rank(game.turn());
+    if piece == Piece::King(game.turn()) && start == Square::new(File::E, back_rank) && end.rank() == back_rank {
+        match end.file() {
+            File::H => end = Square::new(File::G, back_rank),
+            File::A => end = Square::new(File::C, back_rank),
+            _ => {}
+        }
+    }
+
+    Some(BookMove { start, end, promotion, weight: entry.weight })
+}
+
+#[cfg(test)]
+#[path = "../tests/test_polyglot.rs"]
+mod test_polyglot;
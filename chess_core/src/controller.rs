@@ -0,0 +1,188 @@
+use std::sync::mpsc;
+
+use crate::core_struct::{Color, Piece, PieceKind};
+use crate::errors::MoveError;
+use crate::game::{Game, PromotionPiece};
+use crate::moves::Move;
+use crate::rules::{DrawReason, EndgameStatus};
+use crate::square::{File, Rank, Square};
+
+/// What happened to a game run by a GameController, sent to every subscriber in the order it happened
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    /// A move or a drop, by that color. A pawn moved to the last rank without its piece is followed by PromotionPending, then Promotion
+    MovePlayed(Move, Color),
+    /// A piece taken off the board, beside the end square of the move on en passant
+    Capture(Piece, Square),
+    /// The rook's part of a castling, the king's part is the move played
+    Castle { color: Color, rook_start: Square, rook_end: Square },
+    /// A pawn on that square waits for its promotion piece
+    PromotionPending(Square),
+    /// The piece a pawn was promoted to, on its square
+    Promotion(Piece, Square),
+    /// The king of that color is in check
+    Check(Color),
+    GameOver(EndgameStatus),
+    /// That color offered a draw, the offer itself is kept by whoever handles it
+    DrawOffered(Color),
+    MoveTakenBack(Move),
+    /// A piece put in a pocket from outside the board, in bughouse
+    PieceReceived(Piece),
+    /// The whole game was replaced, e.g. by the server's game after a rejected move
+    GameReplaced,
+}
+
+/// A game that tells its subscribers about every change, so that they don't have to compare it from one frame to the next.
+/// The end of the game is looked for after every move, evaluate_endgame doesn't have to be called
+#[derive(Debug)]
+pub struct GameController {
+    game: Game,
+    subscribers: Vec<mpsc::Sender<GameEvent>>,
+}
+
+impl GameController {
+    pub fn new(game: Game) -> GameController {
+        GameController { game, subscribers: Vec::new() }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// A receiver of the events from now on. It stops being sent to once dropped
+    pub fn subscribe(&mut self) -> mpsc::Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// See Game::play_move
+    pub fn play_move(&mut self, start: Square, end: Square) -> Result<Option<Piece>, MoveError> {
+        let events = self.move_events(Move::new(start, end));
+        let captured = self.game.play_move(start, end)?;
+        self.after_move(events);
+        Ok(captured)
+    }
+
+    /// See Game::play
+    pub fn play(&mut self, mv: Move) -> Result<Option<Piece>, MoveError> {
+        let mut events = self.move_events(mv);
+        let captured = self.game.play(mv)?;
+        if let Move::Normal { end, promotion: Some(_), .. } = mv {
+            events.extend(self.game.piece_at(end).map(|piece| GameEvent::Promotion(piece, end)));
+        }
+        self.after_move(events);
+        Ok(captured)
+    }
+
+    /// See Game::drop_piece
+    pub fn drop_piece(&mut self, kind: PieceKind, square: Square) -> Result<(), MoveError> {
+        let events = self.move_events(Move::Drop { kind, square });
+        self.game.drop_piece(kind, square)?;
+        self.after_move(events);
+        Ok(())
+    }
+
+    /// See Game::promote_pawn
+    pub fn promote_pawn(&mut self, promotion_piece: PromotionPiece) -> Result<(), MoveError> {
+        let square = self.game.is_promotion_pending().map(|(file, color)| promotion_square(file, color));
+        self.game.promote_pawn(promotion_piece)?;
+        let promoted = square.and_then(|square| Some(GameEvent::Promotion(self.game.piece_at(square)?, square)));
+        self.after_move(promoted.into_iter().collect());
+        Ok(())
+    }
+
+    /// See Game::undo
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = self.game.undo()?;
+        self.send(GameEvent::MoveTakenBack(mv));
+        Some(mv)
+    }
+
+    /// See Game::add_to_pocket
+    pub fn add_to_pocket(&mut self, piece: Piece) -> Result<(), MoveError> {
+        self.game.add_to_pocket(piece)?;
+        self.send(GameEvent::PieceReceived(piece));
+        Ok(())
+    }
+
+    pub fn offer_draw(&mut self, color: Color) {
+        self.send(GameEvent::DrawOffered(color));
+    }
+
+    /// See Game::claim_draw
+    pub fn claim_draw(&mut self) -> Result<DrawReason, MoveError> {
+        let reason = self.game.claim_draw()?;
+        self.send(GameEvent::GameOver(EndgameStatus::Draw(reason)));
+        Ok(reason)
+    }
+
+    /// See Game::end_game
+    pub fn end_game(&mut self, endgame_status: EndgameStatus) {
+        self.game.end_game(endgame_status);
+        self.send(GameEvent::GameOver(endgame_status));
+    }
+
+    /// Puts another game in place of this one, keeping the subscribers
+    pub fn replace(&mut self, game: Game) {
+        self.game = game;
+        self.send(GameEvent::GameReplaced);
+    }
+
+    /// The events of a move, worked out before it is played since the board loses the captured piece
+    fn move_events(&self, mv: Move) -> Vec<GameEvent> {
+        let color = self.game.turn();
+        let mut events = vec![GameEvent::MovePlayed(mv, color)];
+        let Move::Normal { start, end, .. } = mv else {
+            return events;
+        };
+        let piece = self.game.piece_at(start);
+        if let Some(captured) = self.game.piece_at(end) {
+            events.push(GameEvent::Capture(captured, end));
+        } else if piece == Some(Piece::Pawn(color)) && start.file() != end.file() {
+            // En passant, the pawn taken stands beside the start square
+            events.push(GameEvent::Capture(Piece::Pawn(color.opposite()), Square::new(end.file(), start.rank())));
+        }
+        if piece == Some(Piece::King(color)) && start.file().index().abs_diff(end.file().index()) == 2 {
+            let (rook_start, rook_end) = if end.file() > start.file() { (File::H, File::F) } else { (File::A, File::D) };
+            events.push(GameEvent::Castle {
+                color,
+                rook_start: Square::new(rook_start, start.rank()),
+                rook_end: Square::new(rook_end, start.rank()),
+            });
+        }
+        events
+    }
+
+    /// Sends the events of a move that was played, then the promotion it waits for, or the check and the end of the game
+    fn after_move(&mut self, events: Vec<GameEvent>) {
+        for event in events {
+            self.send(event);
+        }
+        if let Some((file, color)) = self.game.is_promotion_pending() {
+            self.send(GameEvent::PromotionPending(promotion_square(file, color)));
+            return;
+        }
+
+        let endgame_status = self.game.evaluate_endgame();
+        if self.game.is_in_check() {
+            self.send(GameEvent::Check(self.game.turn()));
+        }
+        if !endgame_status.is_ongoing() {
+            self.send(GameEvent::GameOver(endgame_status));
+        }
+    }
+
+    fn send(&mut self, event: GameEvent) {
+        self.subscribers.retain(|subscriber| subscriber.send(event).is_ok());
+    }
+}
+
+/// Square of a pawn of that color on the last rank
+fn promotion_square(file: File, color: Color) -> Square {
+    Square::new(file, Rank::back_rank(color.opposite()))
+}
+
+#[cfg(test)]
+#[path = "tests/test_controller.rs"]
+mod test_controller;
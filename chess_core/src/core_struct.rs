@@ -28,7 +28,7 @@ impl Color {
 
  
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Piece {
     Pawn(Color),
     Knight(Color),
@@ -48,6 +48,39 @@ impl Piece {
             | Piece::Knight(color) => *color,
         }
     }
+
+    pub fn kind(&self) -> PieceKind {
+        match self {
+            Piece::Pawn(_) => PieceKind::Pawn,
+            Piece::Knight(_) => PieceKind::Knight,
+            Piece::Bishop(_) => PieceKind::Bishop,
+            Piece::Rook(_) => PieceKind::Rook,
+            Piece::Queen(_) => PieceKind::Queen,
+            Piece::King(_) => PieceKind::King,
+        }
+    }
+
+    pub fn new(kind: PieceKind, color: Color) -> Piece {
+        match kind {
+            PieceKind::Pawn => Piece::Pawn(color),
+            PieceKind::Knight => Piece::Knight(color),
+            PieceKind::Bishop => Piece::Bishop(color),
+            PieceKind::Rook => Piece::Rook(color),
+            PieceKind::Queen => Piece::Queen(color),
+            PieceKind::King => Piece::King(color),
+        }
+    }
+}
+
+/// A piece without its color, used where the color is implied (drops from a pocket for example)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PieceKind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
 }
 
 
@@ -78,12 +111,14 @@ impl fmt::Display for Board {
                 };
                 write!(f, "{}", symbol)?;
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
+pub(crate) type Grid = [[Option<Piece>; 8]; 8];
+
 impl Board {
     pub fn new() -> Board {
         Board {
@@ -156,4 +191,40 @@ impl Board {
         }
     }
 
-}
\ No newline at end of file
+    /// Board with the given pieces, the position sets are built from the grid
+    pub fn from_grid(grid: [[Option<Piece>; 8]; 8]) -> Board {
+        let mut pieces: HashMap<Piece, HashSet<(usize, usize)>> = HashMap::new();
+        for color in [Color::White, Color::Black] {
+            for kind in [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen, PieceKind::King] {
+                pieces.insert(Piece::new(kind, color), HashSet::new());
+            }
+        }
+        for (i, row) in grid.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                if let Some(piece) = cell {
+                    pieces.get_mut(piece).unwrap().insert((i, j));
+                }
+            }
+        }
+        Board { grid, pieces }
+    }
+
+    /// Puts back the pieces of an earlier grid, only the squares that changed since are updated
+    pub(crate) fn restore_grid(&mut self, grid: Grid) {
+        for (i, row) in grid.iter().enumerate() {
+            for (j, &cell) in row.iter().enumerate() {
+                let current = self.grid[i][j];
+                if current == cell {
+                    continue;
+                }
+                if let Some(piece) = current {
+                    self.pieces.entry(piece).or_default().remove(&(i, j));
+                }
+                if let Some(piece) = cell {
+                    self.pieces.entry(piece).or_default().insert((i, j));
+                }
+                self.grid[i][j] = cell;
+            }
+        }
+    }
+}
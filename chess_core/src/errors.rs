@@ -0,0 +1,140 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core_struct::{Color, PieceKind};
+use crate::rules::SetupError;
+use crate::square::Square;
+
+/// Why a move, a drop or a promotion is refused, the game is left untouched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveError {
+    NoPiece(Square),
+    /// The piece moved or dropped belongs to the side not to move
+    WrongColor,
+    NotYourTurn,
+    OwnPieceOnTarget,
+    /// The piece doesn't move that way, or its path is blocked
+    IllegalPieceMove,
+    LeavesKingInCheck,
+    /// The pawn that reached the last rank has to be promoted first
+    PromotionPending,
+    NoPromotionPending,
+    MissingPromotionPiece,
+    /// Only a pawn reaching the last rank can be promoted
+    UnexpectedPromotion,
+    GameOver,
+    /// Neither threefold repetition nor the fifty-move rule applies to the position
+    NoDrawToClaim,
+    DropsNotAllowed,
+    NotInPocket(PieceKind),
+    /// Pieces are only dropped on empty squares
+    SquareOccupied(Square),
+    PawnDropOnBackRank,
+    KingInPocket,
+    /// A pocket holds at most 255 pieces of each kind
+    PocketFull(PieceKind),
+    /// The board doesn't have exactly one king of that color, only possible with a board built by hand
+    KingNotFound(Color),
+}
+
+fn kind_name(kind: PieceKind) -> &'static str {
+    match kind {
+        PieceKind::Pawn => "pawn",
+        PieceKind::Knight => "knight",
+        PieceKind::Bishop => "bishop",
+        PieceKind::Rook => "rook",
+        PieceKind::Queen => "queen",
+        PieceKind::King => "king",
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::NoPiece(square) => write!(f, "No piece on {}", square),
+            MoveError::WrongColor => write!(f, "That piece belongs to the other side"),
+            MoveError::NotYourTurn => write!(f, "Not your turn"),
+            MoveError::OwnPieceOnTarget => write!(f, "The target square is occupied by a piece of the same color"),
+            MoveError::IllegalPieceMove => write!(f, "The piece can't move there"),
+            MoveError::LeavesKingInCheck => write!(f, "The move would leave the king in check"),
+            MoveError::PromotionPending => write!(f, "A pawn is waiting to be promoted"),
+            MoveError::NoPromotionPending => write!(f, "No pawn to promote"),
+            MoveError::MissingPromotionPiece => write!(f, "Missing promotion piece"),
+            MoveError::UnexpectedPromotion => write!(f, "Only a pawn on the last rank can be promoted"),
+            MoveError::GameOver => write!(f, "The game is over"),
+            MoveError::NoDrawToClaim => write!(f, "There is no draw to claim"),
+            MoveError::DropsNotAllowed => write!(f, "Drops are not allowed in this variant"),
+            MoveError::NotInPocket(kind) => write!(f, "No {} in the pocket", kind_name(*kind)),
+            MoveError::SquareOccupied(square) => write!(f, "The square {} is occupied", square),
+            MoveError::PawnDropOnBackRank => write!(f, "Pawns can't be dropped on the first or last rank"),
+            MoveError::KingInPocket => write!(f, "A king can't be put in a pocket"),
+            MoveError::PocketFull(kind) => write!(f, "There is no room for another {} in the pocket", kind_name(*kind)),
+            MoveError::KingNotFound(color) => write!(f, "The board doesn't have exactly one {} king", color),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Why a move, a FEN or a PGN game can't be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidSquare,
+    InvalidDropPiece,
+    InvalidPromotionPiece,
+    /// A move typed as its two squares, e.g. "e2 e4"
+    ExpectedTwoSquares,
+    /// No legal move of the position has this notation
+    UnknownMove,
+    /// A FEN has 4 or 6 fields, the move counters can be left out
+    FenFieldCount,
+    FenRankCount,
+    FenInvalidPiece,
+    /// The pockets of a drop variant, in brackets after the board, hold a king or too many pieces
+    FenPocket,
+    /// A rank of the FEN doesn't describe exactly 8 squares
+    FenRankLength,
+    FenTurn,
+    FenCastling,
+    FenEnPassant,
+    FenHalfmoveClock,
+    FenMoveNumber,
+    UnclosedComment,
+    UnclosedVariation,
+    InvalidTag,
+    /// A closing bracket, parenthesis or brace without its opening one
+    NothingToClose,
+    UnsupportedVariant,
+    UnplayablePosition(SetupError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidSquare => write!(f, "Invalid square"),
+            ParseError::InvalidDropPiece => write!(f, "Invalid piece to drop"),
+            ParseError::InvalidPromotionPiece => write!(f, "Invalid promotion piece"),
+            ParseError::ExpectedTwoSquares => write!(f, "A move is given as its two squares, e.g. e2 e4"),
+            ParseError::UnknownMove => write!(f, "Illegal or unknown move"),
+            ParseError::FenFieldCount => write!(f, "A FEN has 4 or 6 fields"),
+            ParseError::FenRankCount => write!(f, "A FEN has 8 ranks"),
+            ParseError::FenInvalidPiece => write!(f, "Invalid piece in FEN"),
+            ParseError::FenPocket => write!(f, "Invalid pocket in FEN"),
+            ParseError::FenRankLength => write!(f, "Each rank of a FEN has 8 squares"),
+            ParseError::FenTurn => write!(f, "Invalid color to move in FEN"),
+            ParseError::FenCastling => write!(f, "Invalid castling rights in FEN"),
+            ParseError::FenEnPassant => write!(f, "Invalid en passant square in FEN"),
+            ParseError::FenHalfmoveClock => write!(f, "Invalid halfmove clock in FEN"),
+            ParseError::FenMoveNumber => write!(f, "Invalid move number in FEN"),
+            ParseError::UnclosedComment => write!(f, "Comment not closed"),
+            ParseError::UnclosedVariation => write!(f, "Variation not closed"),
+            ParseError::InvalidTag => write!(f, "Invalid tag"),
+            ParseError::NothingToClose => write!(f, "Nothing to close"),
+            ParseError::UnsupportedVariant => write!(f, "Unsupported variant"),
+            ParseError::UnplayablePosition(e) => write!(f, "The position can't be played from: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,317 @@
+mod tables;
+
+use std::fmt;
+
+use crate::core_struct::{Board, Color, Piece, PieceKind};
+use crate::game::Game;
+
+/// A value for the middlegame and one for the endgame, in centipawns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Score {
+    pub middlegame: i32,
+    pub endgame: i32,
+}
+
+impl Score {
+    pub const fn new(middlegame: i32, endgame: i32) -> Score {
+        Score { middlegame, endgame }
+    }
+
+    /// Interpolates between the two values, phase goes from MAX_PHASE in the opening to 0 with only kings and pawns
+    pub fn taper(&self, phase: i32) -> i32 {
+        (self.middlegame * phase + self.endgame * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl std::ops::Add for Score {
+    type Output = Score;
+
+    fn add(self, other: Score) -> Score {
+        Score::new(self.middlegame + other.middlegame, self.endgame + other.endgame)
+    }
+}
+
+impl std::ops::AddAssign for Score {
+    fn add_assign(&mut self, other: Score) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::Mul<i32> for Score {
+    type Output = Score;
+
+    fn mul(self, factor: i32) -> Score {
+        Score::new(self.middlegame * factor, self.endgame * factor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Term {
+    Material,
+    PieceSquares,
+    Mobility,
+    KingSafety,
+    PawnStructure,
+    PassedPawns,
+    BishopPair,
+}
+
+impl Term {
+    pub const ALL: [Term; 7] = [
+        Term::Material,
+        Term::PieceSquares,
+        Term::Mobility,
+        Term::KingSafety,
+        Term::PawnStructure,
+        Term::PassedPawns,
+        Term::BishopPair,
+    ];
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Term::Material => "Material",
+            Term::PieceSquares => "Piece squares",
+            Term::Mobility => "Mobility",
+            Term::KingSafety => "King safety",
+            Term::PawnStructure => "Pawn structure",
+            Term::PassedPawns => "Passed pawns",
+            Term::BishopPair => "Bishop pair",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Phase of the starting position: 1 per knight and bishop, 2 per rook and 4 per queen
+pub const MAX_PHASE: i32 = 24;
+
+/// The terms of each side, the score of a side is what it adds for that side only
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    white: [Score; 7],
+    black: [Score; 7],
+    phase: i32,
+}
+
+impl Evaluation {
+    /// Score of one term for one side, tapered by the phase of the game
+    pub fn term(&self, term: Term, color: Color) -> i32 {
+        self.term_score(term, color).taper(self.phase)
+    }
+
+    /// Score of one term for one side, before tapering
+    pub fn term_score(&self, term: Term, color: Color) -> Score {
+        match color {
+            Color::White => self.white[term as usize],
+            Color::Black => self.black[term as usize],
+        }
+    }
+
+    /// Sum of the terms of one side, tapered
+    pub fn side(&self, color: Color) -> i32 {
+        Term::ALL.iter().map(|term| self.term(*term, color)).sum()
+    }
+
+    /// The evaluation in centipawns, positive when white is better
+    pub fn score(&self) -> i32 {
+        let total = |terms: &[Score; 7]| terms.iter().fold(Score::default(), |sum, score| sum + *score);
+        let white = total(&self.white);
+        let black = total(&self.black);
+        Score::new(white.middlegame - black.middlegame, white.endgame - black.endgame).taper(self.phase)
+    }
+
+    /// The evaluation in centipawns from the point of view of the given side
+    pub fn relative_score(&self, color: Color) -> i32 {
+        match color {
+            Color::White => self.score(),
+            Color::Black => -self.score(),
+        }
+    }
+
+    /// From MAX_PHASE with all the pieces on the board to 0 with only kings and pawns
+    pub fn phase(&self) -> i32 {
+        self.phase
+    }
+}
+
+const MATERIAL: [Score; 5] = [
+    Score::new(82, 94),    // Pawn
+    Score::new(337, 281),  // Knight
+    Score::new(365, 297),  // Bishop
+    Score::new(477, 512),  // Rook
+    Score::new(1025, 936), // Queen
+];
+const PHASE_WEIGHTS: [i32; 5] = [0, 1, 1, 2, 4];
+const MOBILITY: [Score; 5] = [
+    Score::new(0, 0), // Pawn
+    Score::new(4, 4), // Knight
+    Score::new(5, 5), // Bishop
+    Score::new(2, 4), // Rook
+    Score::new(1, 2), // Queen
+];
+const PAWN_SHIELD: [Score; 2] = [Score::new(10, 0), Score::new(5, 0)]; // Pawn right in front of the king, one square further
+const OPEN_FILE_NEAR_KING: Score = Score::new(-15, 0);
+const DOUBLED_PAWN: Score = Score::new(-10, -20);
+const ISOLATED_PAWN: Score = Score::new(-10, -15);
+const PASSED_PAWN: [Score; 8] = [ // By rank, from the side of the pawn
+    Score::new(0, 0),
+    Score::new(5, 10),
+    Score::new(10, 20),
+    Score::new(15, 35),
+    Score::new(25, 60),
+    Score::new(40, 100),
+    Score::new(60, 150),
+    Score::new(0, 0),
+];
+const BISHOP_PAIR: Score = Score::new(30, 50);
+
+/// Evaluate the position on the board, ignoring whose turn it is and the pieces in the pockets
+pub fn evaluate(game: &Game) -> Evaluation {
+    let board = &game.board;
+
+    let phase = [Color::White, Color::Black]
+        .iter()
+        .flat_map(|&color| PIECE_KINDS.iter().map(move |&kind| count(board, Piece::new(kind, color)) * PHASE_WEIGHTS[kind as usize]))
+        .sum::<i32>()
+        .min(MAX_PHASE); // More than the starting phase after promotions
+
+    Evaluation {
+        white: evaluate_side(board, Color::White),
+        black: evaluate_side(board, Color::Black),
+        phase,
+    }
+}
+
+const PIECE_KINDS: [PieceKind; 5] = [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen];
+
+fn count(board: &Board, piece: Piece) -> i32 {
+    board.pieces.get(&piece).map_or(0, |positions| positions.len() as i32)
+}
+
+fn evaluate_side(board: &Board, color: Color) -> [Score; 7] {
+    let mut terms = [Score::default(); 7];
+
+    for kind in PIECE_KINDS {
+        terms[Term::Material as usize] += MATERIAL[kind as usize] * count(board, Piece::new(kind, color));
+    }
+
+    for kind in PIECE_KINDS.into_iter().chain([PieceKind::King]) {
+        let positions = match board.pieces.get(&Piece::new(kind, color)) {
+            Some(positions) => positions,
+            None => continue,
+        };
+        for &square in positions {
+            terms[Term::PieceSquares as usize] += piece_square(kind, color, square);
+            if kind != PieceKind::King {
+                terms[Term::Mobility as usize] += MOBILITY[kind as usize] * board.mobility(square) as i32;
+            }
+        }
+    }
+
+    terms[Term::KingSafety as usize] = king_safety(board, color);
+    let (structure, passed) = pawns(board, color);
+    terms[Term::PawnStructure as usize] = structure;
+    terms[Term::PassedPawns as usize] = passed;
+
+    if count(board, Piece::Bishop(color)) >= 2 {
+        terms[Term::BishopPair as usize] = BISHOP_PAIR;
+    }
+
+    terms
+}
+
+fn piece_square(kind: PieceKind, color: Color, (i, j): (usize, usize)) -> Score {
+    // The tables start from a8, for black the board is mirrored
+    let index = match color {
+        Color::White => (7 - i) * 8 + j,
+        Color::Black => i * 8 + j,
+    };
+    match kind {
+        PieceKind::Pawn => Score::new(tables::PAWN[index], tables::PAWN_ENDGAME[index]),
+        PieceKind::Knight => Score::new(tables::KNIGHT[index], tables::KNIGHT[index]),
+        PieceKind::Bishop => Score::new(tables::BISHOP[index], tables::BISHOP[index]),
+        PieceKind::Rook => Score::new(tables::ROOK[index], tables::ROOK[index]),
+        PieceKind::Queen => Score::new(tables::QUEEN[index], tables::QUEEN[index]),
+        PieceKind::King => Score::new(tables::KING[index], tables::KING_ENDGAME[index]),
+    }
+}
+
+/// Rank of the square from the side of the given color, 0 is its back rank
+fn relative_rank(color: Color, rank: usize) -> usize {
+    match color {
+        Color::White => rank,
+        Color::Black => 7 - rank,
+    }
+}
+
+/// Pawns in front of the king and open files around it, this only matters in the middlegame
+fn king_safety(board: &Board, color: Color) -> Score {
+    let king = match board.pieces.get(&Piece::King(color)).and_then(|positions| positions.iter().next()) {
+        Some(&king) => king,
+        None => return Score::default(),
+    };
+    let own_pawns = board.pieces.get(&Piece::Pawn(color));
+
+    let mut score = Score::default();
+    for file in king.1.saturating_sub(1)..=(king.1 + 1).min(7) {
+        let mut file_has_pawn = false;
+        for (distance, shield) in PAWN_SHIELD.iter().enumerate() {
+            let rank = relative_rank(color, king.0) + distance + 1;
+            if rank < 8 && own_pawns.is_some_and(|pawns| pawns.contains(&(relative_rank(color, rank), file))) {
+                score += *shield;
+            }
+        }
+        for rank in 0..8 {
+            file_has_pawn |= own_pawns.is_some_and(|pawns| pawns.contains(&(rank, file)));
+        }
+        if !file_has_pawn {
+            score += OPEN_FILE_NEAR_KING;
+        }
+    }
+    score
+}
+
+/// Doubled and isolated pawns for the structure, and passed pawns
+fn pawns(board: &Board, color: Color) -> (Score, Score) {
+    let mut structure = Score::default();
+    let mut passed = Score::default();
+
+    let own_pawns = match board.pieces.get(&Piece::Pawn(color)) {
+        Some(pawns) => pawns,
+        None => return (structure, passed),
+    };
+    let enemy_pawns = board.pieces.get(&Piece::Pawn(color.opposite()));
+
+    let mut files = [0; 8];
+    for &(_, file) in own_pawns {
+        files[file] += 1;
+    }
+    for (file, &pawns) in files.iter().enumerate() {
+        if pawns > 1 {
+            structure += DOUBLED_PAWN * (pawns - 1);
+        }
+        let has_neighbour = (file > 0 && files[file - 1] > 0) || (file < 7 && files[file + 1] > 0);
+        if pawns > 0 && !has_neighbour {
+            structure += ISOLATED_PAWN * pawns;
+        }
+    }
+
+    for &(rank, file) in own_pawns {
+        // No enemy pawn ahead of it on its file or the neighbouring files
+        let is_passed = enemy_pawns.is_none_or(|enemies| {
+            !enemies.iter().any(|&(enemy_rank, enemy_file)| {
+                enemy_file.abs_diff(file) <= 1 && relative_rank(color, enemy_rank) > relative_rank(color, rank)
+            })
+        });
+        if is_passed {
+            passed += PASSED_PAWN[relative_rank(color, rank)];
+        }
+    }
+
+    (structure, passed)
+}
+
+#[cfg(test)]
+#[path = "../tests/test_eval.rs"]
+mod test_eval;
@@ -0,0 +1,211 @@
+use crate::core_struct::{Color, Piece, PieceKind};
+use crate::errors::ParseError;
+use crate::game::Game;
+use crate::square::Square;
+use crate::rules::Setup;
+use crate::variant::{Pocket, Variant};
+
+fn piece_char(piece: Piece) -> char {
+    let c = match piece.kind() {
+        PieceKind::Pawn => 'p',
+        PieceKind::Knight => 'n',
+        PieceKind::Bishop => 'b',
+        PieceKind::Rook => 'r',
+        PieceKind::Queen => 'q',
+        PieceKind::King => 'k',
+    };
+    match piece.color() {
+        Color::White => c.to_ascii_uppercase(),
+        Color::Black => c,
+    }
+}
+
+fn parse_piece(c: char) -> Option<Piece> {
+    let kind = match c.to_ascii_lowercase() {
+        'p' => PieceKind::Pawn,
+        'n' => PieceKind::Knight,
+        'b' => PieceKind::Bishop,
+        'r' => PieceKind::Rook,
+        'q' => PieceKind::Queen,
+        'k' => PieceKind::King,
+        _ => return None,
+    };
+    let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+    Some(Piece::new(kind, color))
+}
+
+impl Setup {
+    /// Reads a position in Forsyth-Edwards Notation. The move counters can be left out.
+    /// Pockets in brackets after the board, e.g. RNBQKBNR[Pn], and promoted pieces marked with a ~ make a crazyhouse position.
+    /// The position is only checked by Game::from_setup
+    pub fn from_fen(fen: &str) -> Result<Setup, ParseError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() != 4 && fields.len() != 6 {
+            return Err(ParseError::FenFieldCount);
+        }
+
+        let mut setup = Setup::empty();
+        let board = match fields[0].split_once('[') {
+            Some((board, pockets)) => {
+                let pockets = pockets.strip_suffix(']').ok_or(ParseError::FenPocket)?;
+                for c in pockets.chars() {
+                    let piece = parse_piece(c).ok_or(ParseError::FenPocket)?;
+                    let pocket = match piece.color() {
+                        Color::White => &mut setup.white_pocket,
+                        Color::Black => &mut setup.black_pocket,
+                    };
+                    pocket.add(piece.kind()).map_err(|_| ParseError::FenPocket)?;
+                }
+                setup.variant = Variant::Crazyhouse;
+                board
+            }
+            None => fields[0],
+        };
+        let ranks: Vec<&str> = board.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(ParseError::FenRankCount);
+        }
+        for (index, rank) in ranks.iter().enumerate() {
+            let i = 7 - index;
+            let mut j = 0;
+            for c in rank.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    j += empty as usize;
+                } else if c == '~' {
+                    // Follows a promoted piece
+                    if j == 0 || j > 8 || setup.grid[i][j - 1].is_none() {
+                        return Err(ParseError::FenInvalidPiece);
+                    }
+                    setup.promoted.push(Square::from_coords((i, j - 1)));
+                } else {
+                    let piece = parse_piece(c).ok_or(ParseError::FenInvalidPiece)?;
+                    if j >= 8 {
+                        return Err(ParseError::FenRankLength);
+                    }
+                    setup.grid[i][j] = Some(piece);
+                    j += 1;
+                }
+            }
+            if j != 8 {
+                return Err(ParseError::FenRankLength);
+            }
+        }
+
+        if setup.variant == Variant::Standard && !setup.promoted.is_empty() {
+            return Err(ParseError::FenInvalidPiece); // Promoted pieces are only marked in drop variants
+        }
+
+        setup.turn = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(ParseError::FenTurn),
+        };
+
+        if fields[2] != "-" {
+            for c in fields[2].chars() {
+                match c {
+                    'K' => setup.white_castle_king_side = true,
+                    'Q' => setup.white_castle_queen_side = true,
+                    'k' => setup.black_castle_king_side = true,
+                    'q' => setup.black_castle_queen_side = true,
+                    _ => return Err(ParseError::FenCastling),
+                }
+            }
+        }
+
+        if fields[3] != "-" {
+            let square: Square = fields[3].parse().map_err(|_| ParseError::FenEnPassant)?;
+            setup.en_passant = Some(square.file());
+        }
+
+        if fields.len() == 6 {
+            setup.halfmove_clock = fields[4].parse().map_err(|_| ParseError::FenHalfmoveClock)?;
+            setup.fullmove_number = fields[5].parse().map_err(|_| ParseError::FenMoveNumber)?;
+        }
+        Ok(setup)
+    }
+}
+
+impl Game {
+    /// Forsyth-Edwards Notation of the position. In drop variants the pockets follow the board in brackets
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        for i in (0..8).rev() {
+            let mut empty = 0;
+            for j in 0..8 {
+                match self.board.grid[i][j] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen.push(piece_char(piece));
+                        if self.game_state.variant.has_drops() && self.game_state.promoted.contains(&(i, j)) {
+                            fen.push('~');
+                        }
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if i > 0 {
+                fen.push('/');
+            }
+        }
+
+        if self.game_state.variant.has_drops() {
+            fen.push('[');
+            for color in [Color::White, Color::Black] {
+                let pocket = self.game_state.pocket(color);
+                for kind in Pocket::KINDS.iter().rev() {
+                    for _ in 0..pocket.count(*kind) {
+                        fen.push(piece_char(Piece::new(*kind, color)));
+                    }
+                }
+            }
+            fen.push(']');
+        }
+
+        fen.push_str(match self.game_state.turn {
+            Color::White => " w ",
+            Color::Black => " b ",
+        });
+
+        let castling: String = [
+            (self.game_state.white_castle_king_side, 'K'),
+            (self.game_state.white_castle_queen_side, 'Q'),
+            (self.game_state.black_castle_king_side, 'k'),
+            (self.game_state.black_castle_queen_side, 'q'),
+        ]
+        .iter()
+        .filter(|(can_castle, _)| *can_castle)
+        .map(|(_, c)| *c)
+        .collect();
+        fen.push_str(if castling.is_empty() { "-" } else { &castling });
+
+        // The square behind the pawn that just moved two squares
+        match self.game_state.en_passant {
+            Some((file, Color::White)) => fen.push_str(&format!(" {}3", (b'a' + file as u8) as char)),
+            Some((file, Color::Black)) => fen.push_str(&format!(" {}6", (b'a' + file as u8) as char)),
+            None => fen.push_str(" -"),
+        }
+
+        fen.push_str(&format!(" {} {}", self.game_state.how_many_moves, self.fullmove_number()));
+        fen
+    }
+
+    /// Number of the current move, increased after each black move
+    pub fn fullmove_number(&self) -> usize {
+        // While a promotion is pending the pawn's move is in the history, but it isn't over and the turn hasn't changed yet
+        let plies = self.history().len().saturating_sub(self.game_state.promotion_pending.is_some() as usize);
+        let started_with_black = (self.game_state.turn == Color::Black) == plies.is_multiple_of(2);
+        ((plies + started_with_black as usize) / 2).saturating_add(self.first_move_number)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_fen.rs"]
+mod test_fen;
@@ -1,9 +1,10 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashSet, hash::Hash};
 use serde::{Serialize, Deserialize};
 
-use crate::{core_struct::{self, Color, Piece}, rules::{EndgameStatus, WinReason, DrawReason}};
+use crate::snapshot::GameSnapshot;
+use crate::{book, core_struct::{self, Color, Piece, PieceKind}, errors::MoveError, moves::{self, Move}, rules::{check_and_mate, EndgameStatus, DrawReason, Setup, SetupError}, square::{File, Square}, variant::{Pocket, Variant}};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PromotionPiece {
     Queen,
     Rook,
@@ -19,9 +20,11 @@ struct Position { //For 3 fold repetition
     white_castle_queen_side: bool,
     black_castle_king_side: bool,
     black_castle_queen_side: bool,
+    white_pocket: Pocket,
+    black_pocket: Pocket,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct GameState {
     pub turn: core_struct::Color,
     pub white_castle_king_side: bool,
@@ -32,6 +35,27 @@ pub(crate) struct GameState {
     pub promotion_pending: Option<(usize, Color)>,
     pub endgame_status: EndgameStatus,
     pub how_many_moves: u32,
+    pub variant: Variant,
+    pub white_pocket: Pocket,
+    pub black_pocket: Pocket,
+    pub promoted: HashSet<(usize, usize)>, // Squares of promoted pieces, they go back to the pocket as pawns
+    pub repetition_start: usize, // Index in Game::positions of the first position since the last capture or pawn move
+}
+
+impl GameState {
+    pub(crate) fn pocket(&self, color: Color) -> &Pocket {
+        match color {
+            Color::White => &self.white_pocket,
+            Color::Black => &self.black_pocket,
+        }
+    }
+
+    pub(crate) fn pocket_mut(&mut self, color: Color) -> &mut Pocket {
+        match color {
+            Color::White => &mut self.white_pocket,
+            Color::Black => &mut self.black_pocket,
+        }
+    }
 }
 
 impl Default for GameState {
@@ -46,61 +70,137 @@ impl Default for GameState {
             promotion_pending: None,
             endgame_status: EndgameStatus::Ongoing,
             how_many_moves: 0,
+            variant: Variant::Standard,
+            white_pocket: Pocket::default(),
+            black_pocket: Pocket::default(),
+            promoted: HashSet::new(),
+            repetition_start: 0,
         }
     }
 }
 
-#[derive(Debug)]
-pub struct Game {
-    board: core_struct::Board,
+/// Everything a move changes, saved before each move to take it back. The positions seen are only ever added to, so
+/// their count is enough to find them back
+#[derive(Debug, Clone)]
+struct Snapshot {
+    grid: core_struct::Grid,
     game_state: GameState,
-    positions: HashMap<Position, u32>, //For 3 fold repetition
+    positions: usize,
+}
+
+/// Serialized as a versioned GameSnapshot: the starting position, the moves and the result, played again when it is read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "GameSnapshot", try_from = "GameSnapshot")]
+pub struct Game {
+    pub(crate) board: core_struct::Board,
+    pub(crate) game_state: GameState,
+    positions: Vec<Position>, // For 3 fold repetition, the ones that count start at game_state.repetition_start
+    moves: Vec<Move>,
+    snapshots: Vec<Snapshot>, // The position before each move
+    pub(crate) first_move_number: usize, // Number of the first move, when the game starts from a setup
+    pub(crate) received: Vec<(usize, Piece)>, // Pieces put in a pocket from the partner's board in bughouse, after how many moves
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
 }
 
 impl Game {
     pub fn new() -> Game {
-        let board = core_struct::Board::new();
-        let mut positions = HashMap::new();
-        positions.insert(Position {
-            turn: Color::White,
-            grid: board.grid,
-            white_castle_king_side: true,
-            white_castle_queen_side: true,
-            black_castle_king_side: true,
-            black_castle_queen_side: true,
-        }, 1);
-        Game {
+        Game::with_variant(Variant::Standard)
+    }
+
+    /// Starts a game from the initial position with the rules of the given variant
+    pub fn with_variant(variant: Variant) -> Game {
+        let mut game = Game {
+            board: core_struct::Board::new(),
+            game_state: GameState {
+                variant,
+                ..Default::default()
+            },
+            positions: Vec::new(),
+            moves: Vec::new(),
+            snapshots: Vec::new(),
+            first_move_number: 1,
+            received: Vec::new(),
+        };
+        game.positions.push(game.current_position());
+        game
+    }
+
+    /// Starts a game from a custom position, after checking that it can be played from
+    pub fn from_setup(setup: &Setup) -> Result<Game, SetupError> {
+        let board = core_struct::Board::from_grid(setup.grid);
+        board.validate_setup(setup)?;
+
+        let mut game = Game {
             board,
-            game_state : GameState {
-                turn: core_struct::Color::White,
-                white_castle_king_side: true,
-                white_castle_queen_side: true,
-                black_castle_king_side: true,
-                black_castle_queen_side: true,
-                en_passant: None,
-                promotion_pending: None,
-                endgame_status: EndgameStatus::Ongoing,
-                how_many_moves: 0,
+            game_state: GameState {
+                turn: setup.turn,
+                white_castle_king_side: setup.white_castle_king_side,
+                white_castle_queen_side: setup.white_castle_queen_side,
+                black_castle_king_side: setup.black_castle_king_side,
+                black_castle_queen_side: setup.black_castle_queen_side,
+                en_passant: setup.en_passant.map(|file| (file.index(), setup.turn.opposite())),
+                how_many_moves: setup.halfmove_clock,
+                variant: setup.variant,
+                ..Default::default()
             },
-            positions
+            positions: Vec::new(),
+            moves: Vec::new(),
+            snapshots: Vec::new(),
+            first_move_number: setup.fullmove_number.max(1),
+            received: Vec::new(),
+        };
+        if setup.variant.has_drops() {
+            game.game_state.white_pocket = setup.white_pocket;
+            game.game_state.black_pocket = setup.black_pocket;
+            game.game_state.promoted = setup.promoted.iter().map(|square| square.coords()).collect();
+        }
+        // A position with en passant can't be repeated, like after a two square pawn move
+        if game.game_state.en_passant.is_none() {
+            game.positions.push(game.current_position());
+        }
+        game.evaluate_endgame(); // The setup may already be checkmate or stalemate
+        Ok(game)
+    }
+
+    fn current_position(&self) -> Position {
+        Position {
+            turn: self.game_state.turn,
+            grid: self.board.grid,
+            white_castle_king_side: self.game_state.white_castle_king_side,
+            white_castle_queen_side: self.game_state.white_castle_queen_side,
+            black_castle_king_side: self.game_state.black_castle_king_side,
+            black_castle_queen_side: self.game_state.black_castle_queen_side,
+            white_pocket: self.game_state.white_pocket,
+            black_pocket: self.game_state.black_pocket,
         }
     }
 
-    /// Returns the piece at the given position
-    pub fn piece_at(&self, i: usize, j: usize) -> Option<Piece> {
+    /// Returns the piece on the given square
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        let (i, j) = square.coords();
         self.board.grid[i][j]
     }
     
-    /// Evaluate if a move is valid and execute it if it is
-    pub fn play_move(&mut self, start: (usize, usize), end: (usize, usize)) -> Result<(), &'static str> {
+    /// Evaluate if a move is valid and execute it if it is.
+    /// Returns the captured piece, as a pawn if it was a promoted piece
+    pub fn play_move(&mut self, start: Square, end: Square) -> Result<Option<Piece>, MoveError> {
         if self.game_state.promotion_pending.is_some() {
-            return Err("Promotion pending");
+            return Err(MoveError::PromotionPending);
         }
         if self.game_state.endgame_status != EndgameStatus::Ongoing {
-            return Err("Game is over");
+            return Err(MoveError::GameOver);
         }
 
+        let mv = Move::new(start, end);
+        let (start, end) = (start.coords(), end.coords());
         self.board.is_valid_move(start, end, &self.game_state)?;
+        let color = self.game_state.turn;
+        self.save(mv);
 
         // Can't castle if the king or rook has moved
         if self.board.grid[start.0][start.1] == Some(Piece::King(Color::White)) {
@@ -123,6 +223,15 @@ impl Game {
             }
         }
 
+        // Can't castle with a rook that has been taken
+        match end {
+            (0, 0) => self.game_state.white_castle_queen_side = false,
+            (0, 7) => self.game_state.white_castle_king_side = false,
+            (7, 0) => self.game_state.black_castle_queen_side = false,
+            (7, 7) => self.game_state.black_castle_king_side = false,
+            _ => {}
+        }
+
         // If a pawn moves two squares, it can be captured en passant
         if self.board.grid[start.0][start.1] == Some(Piece::Pawn(Color::White)) && start.0 == 1 && end.0 == 3 {
             self.game_state.en_passant = Some((start.1, Color::White));
@@ -145,41 +254,98 @@ impl Game {
 
         let is_take_or_pawn_move = self.board.grid[end.0][end.1].is_some() || self.board.grid[start.0][start.1] == Some(Piece::Pawn(Color::White)) || self.board.grid[start.0][start.1] == Some(Piece::Pawn(Color::Black));
 
-        self.board.execute_move(start, end);
+        let (taken_piece, _) = self.board.execute_move(start, end);
 
-        let position = Position {
-            turn: self.game_state.turn,
-            grid: self.board.grid,
-            white_castle_king_side: self.game_state.white_castle_king_side,
-            white_castle_queen_side: self.game_state.white_castle_queen_side,
-            black_castle_king_side: self.game_state.black_castle_king_side,
-            black_castle_queen_side: self.game_state.black_castle_queen_side,
-        };
+        // Promoted pieces keep their status when they move and are demoted when captured
+        let captured = taken_piece.map(|(piece, position)| {
+            if self.game_state.promoted.remove(&position) {
+                Piece::Pawn(piece.color())
+            } else {
+                piece
+            }
+        });
+        if self.game_state.promoted.remove(&start) {
+            self.game_state.promoted.insert(end);
+        }
+        if let (Some(piece), Variant::Crazyhouse) = (captured, self.game_state.variant) {
+            if let Err(e) = self.game_state.pocket_mut(color).add(piece.kind()) {
+                self.undo();
+                return Err(e);
+            }
+        }
+
+        let position = self.current_position();
 
         // Increment the number of moves if the move is not a capture or a pawn move
         // Also add a position to the positions hashmap for 3 fold repetition
         // The only move where the taken piece is not on the end square is en passant, which is already pawn move
 
         if !is_take_or_pawn_move {
-            self.game_state.how_many_moves += 1;
-            *self.positions.entry(position).or_insert(0) += 1;
+            self.game_state.how_many_moves = self.game_state.how_many_moves.saturating_add(1);
+            self.positions.push(position);
         } else {
             self.game_state.how_many_moves = 0;
-            self.positions.clear();
+            self.game_state.repetition_start = self.positions.len();
             // Should not insert the position if a promotion is pending or if it is a 2 square pawn move
-            if !self.game_state.promotion_pending.is_some() && self.game_state.en_passant.is_none() {
-                self.positions.insert(position, 1);
+            if self.game_state.promotion_pending.is_none() && self.game_state.en_passant.is_none() {
+                self.positions.push(position);
             }   
         }
 
+        Ok(captured)
+    }
+
+    /// Drop a piece of the color to play from its pocket, in the variants that allow it
+    pub fn drop_piece(&mut self, kind: PieceKind, square: Square) -> Result<(), MoveError> {
+        if !self.game_state.variant.has_drops() {
+            return Err(MoveError::DropsNotAllowed);
+        }
+        if self.game_state.promotion_pending.is_some() {
+            return Err(MoveError::PromotionPending);
+        }
+        if self.game_state.endgame_status != EndgameStatus::Ongoing {
+            return Err(MoveError::GameOver);
+        }
+
+        let color = self.game_state.turn;
+        let piece = Piece::new(kind, color);
+        self.board.is_valid_drop(piece, square.coords(), &self.game_state)?;
+        self.save(Move::Drop { kind, square });
+
+        self.board.execute_drop(piece, square.coords());
+        self.game_state.pocket_mut(color).remove(kind);
+        self.game_state.en_passant = None;
+        self.game_state.turn = color.opposite();
+
+        // A pawn drop can't be undone, like a pawn move
+        let position = self.current_position();
+        if kind == PieceKind::Pawn {
+            self.game_state.how_many_moves = 0;
+            self.game_state.repetition_start = self.positions.len();
+            self.positions.push(position);
+        } else {
+            self.game_state.how_many_moves = self.game_state.how_many_moves.saturating_add(1);
+            self.positions.push(position);
+        }
+
+        Ok(())
+    }
+
+    /// Put a piece in a pocket, used in bughouse to receive the pieces captured on the partner's board
+    pub fn add_to_pocket(&mut self, piece: Piece) -> Result<(), MoveError> {
+        if !self.game_state.variant.has_drops() {
+            return Err(MoveError::DropsNotAllowed);
+        }
+        self.game_state.pocket_mut(piece.color()).add(piece.kind())?;
+        self.received.push((self.moves.len(), piece));
         Ok(())
     }
 
     /// Promote a pawn to a piece
-    pub fn promote_pawn(&mut self, promotion_piece: PromotionPiece) -> Result<(), &'static str> {
-        let (y, color) = self.game_state.promotion_pending.ok_or("No promotion pending")?;
+    pub fn promote_pawn(&mut self, promotion_piece: PromotionPiece) -> Result<(), MoveError> {
+        let (y, color) = self.game_state.promotion_pending.ok_or(MoveError::NoPromotionPending)?;
         if color != self.game_state.turn {
-            return Err("Wrong turn for promotion");
+            return Err(MoveError::WrongColor);
         } 
         let piece = match promotion_piece {
             PromotionPiece::Queen => Piece::Queen(color),
@@ -194,58 +360,276 @@ impl Game {
         };
 
         self.board.grid[x][y] = Some(piece);
-        self.board.pieces.get_mut(&Piece::Pawn(color)).unwrap().remove(&(x, y));
-        self.board.pieces.get_mut(&piece).unwrap().insert((x, y));
+        if let Some(pawns) = self.board.pieces.get_mut(&Piece::Pawn(color)) {
+            pawns.remove(&(x, y));
+        }
+        self.board.pieces.entry(piece).or_default().insert((x, y));
+        self.game_state.promoted.insert((x, y));
         self.game_state.promotion_pending = None;
+        if let Some(Move::Normal { promotion, .. }) = self.moves.last_mut() {
+            *promotion = Some(promotion_piece);
+        }
         self.game_state.turn = self.game_state.turn.opposite();
 
+        // The pawn move started the positions again
+        let position = self.current_position();
+        self.positions.push(position);
 
-        let position = Position {
-            turn: self.game_state.turn,
+        Ok(())
+    }
+
+
+    /// Play a complete move, including the promotion piece when a pawn reaches the last rank
+    pub fn play(&mut self, mv: Move) -> Result<Option<Piece>, MoveError> {
+        match mv {
+            Move::Normal { start, end, promotion } => {
+                let piece = self.piece_at(start).ok_or(MoveError::NoPiece(start))?;
+                // Checked before the move so that a wrong move leaves the game untouched
+                match (moves::is_promotion(piece, end), promotion) {
+                    (true, None) => return Err(MoveError::MissingPromotionPiece),
+                    (false, Some(_)) => return Err(MoveError::UnexpectedPromotion),
+                    _ => {}
+                }
+                let captured = self.play_move(start, end)?;
+                if let Some(promotion_piece) = promotion {
+                    self.promote_pawn(promotion_piece)?;
+                }
+                Ok(captured)
+            }
+            Move::Drop { kind, square } => {
+                self.drop_piece(kind, square)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn save(&mut self, mv: Move) {
+        self.snapshots.push(Snapshot {
             grid: self.board.grid,
-            white_castle_king_side: self.game_state.white_castle_king_side,
-            white_castle_queen_side: self.game_state.white_castle_queen_side,
-            black_castle_king_side: self.game_state.black_castle_king_side,
-            black_castle_queen_side: self.game_state.black_castle_queen_side,
-        };
+            game_state: self.game_state.clone(),
+            positions: self.positions.len(),
+        });
+        self.moves.push(mv);
+    }
 
-        self.positions.insert(position, 1);
+    /// Take back the last move, or the pawn move of a pending promotion. Returns the move taken back
+    pub fn undo(&mut self) -> Option<Move> {
+        let snapshot = self.snapshots.pop()?;
+        self.board.restore_grid(snapshot.grid);
+        self.game_state = snapshot.game_state;
+        self.positions.truncate(snapshot.positions);
+        let mv = self.moves.pop();
+        // The pieces received after the move go with it, they were not in the pockets before
+        self.received.retain(|&(ply, _)| ply <= self.moves.len());
+        mv
+    }
 
-        Ok(())
+    /// The current position without the moves that led to it, to try moves on without copying the whole game
+    pub(crate) fn without_history(&self) -> Game {
+        Game {
+            board: self.board.clone(),
+            game_state: GameState { repetition_start: 0, ..self.game_state.clone() },
+            positions: Vec::new(),
+            moves: Vec::new(),
+            snapshots: Vec::new(),
+            first_move_number: self.fullmove_number(),
+            received: Vec::new(),
+        }
     }
 
+    /// The moves played since the start of the game
+    pub fn history(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Every legal move of the color to play, with one move per promotion piece and the drops from its pocket
+    pub fn all_legal_moves(&self) -> Vec<Move> {
+        let mut legal_moves = Vec::new();
+        if self.game_state.promotion_pending.is_some() || self.game_state.endgame_status != EndgameStatus::Ongoing {
+            return legal_moves;
+        }
+
+        let color = self.game_state.turn;
+        for start in Square::all() {
+            let piece = match self.piece_at(start) {
+                Some(piece) if piece.color() == color => piece,
+                _ => continue,
+            };
+            for end in self.get_legal_moves(start) {
+                if moves::is_promotion(piece, end) {
+                    for promotion in moves::PROMOTION_PIECES {
+                        legal_moves.push(Move::Normal { start, end, promotion: Some(promotion) });
+                    }
+                } else {
+                    legal_moves.push(Move::new(start, end));
+                }
+            }
+        }
+
+        if self.game_state.variant.has_drops() {
+            for kind in Pocket::KINDS {
+                if self.game_state.pocket(color).count(kind) > 0 {
+                    for square in self.get_legal_drops(kind) {
+                        legal_moves.push(Move::Drop { kind, square });
+                    }
+                }
+            }
+        }
+
+        legal_moves
+    }
+
+    /// Whether the king of the color to play is attacked
+    pub fn is_in_check(&self) -> bool {
+        self.board.is_king_in_check(self.game_state.turn).unwrap_or(false)
+    }
+
+    /// Squares of the pieces of that color attacking a square, whatever stands on it
+    pub fn attackers_of(&self, square: Square, color: Color) -> Vec<Square> {
+        check_and_mate::attackers(&self.board.grid, square.coords(), color).into_iter().map(Square::from_coords).collect()
+    }
+
+    /// Squares of the pieces giving check to the king of the color to play
+    pub fn checkers(&self) -> Vec<Square> {
+        let color = self.game_state.turn;
+        match self.board.pieces.get(&Piece::King(color)).and_then(|squares| squares.iter().next()) {
+            Some(&king) => self.attackers_of(Square::from_coords(king), color.opposite()),
+            None => Vec::new(),
+        }
+    }
 
-    /// Returns the endgame status of the game: ongoing, checkmate or stalemate
+    /// Squares of the pieces of that color pinned to their king by a rook, bishop or queen
+    pub fn pinned_pieces(&self, color: Color) -> Vec<Square> {
+        self.board.pinned_pieces(color).into_iter().map(Square::from_coords).collect()
+    }
+
+    /// Whether the color to play is checkmated, without recording the end of the game like evaluate_endgame
+    pub fn is_checkmate(&self) -> bool {
+        self.game_state.promotion_pending.is_none() && self.is_in_check() && !self.board.has_legal_moves(&self.game_state)
+    }
+
+    /// Whether the color to play has no legal move without being in check, without recording the end of the game
+    pub fn is_stalemate(&self) -> bool {
+        self.game_state.promotion_pending.is_none() && !self.is_in_check() && !self.board.has_legal_moves(&self.game_state)
+    }
+
+    /// A draw either player could claim, by the fifty-move rule or threefold repetition, without looking for mate
+    pub(crate) fn is_draw_by_rule(&self) -> bool {
+        self.game_state.how_many_moves >= 100 || self.repetitions() >= 3
+    }
+
+    /// How many times the current position has occurred, itself included
+    fn repetitions(&self) -> u32 {
+        let current = self.current_position();
+        let seen = self.positions[self.game_state.repetition_start..].iter().filter(|&position| *position == current).count();
+        (seen as u32).max(1)
+    }
+
+    /// The draw a player could claim now, by threefold repetition or the fifty-move rule
+    pub fn can_claim_draw(&self) -> Option<DrawReason> {
+        if !self.game_state.endgame_status.is_ongoing() {
+            None
+        } else if self.repetitions() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        } else if self.game_state.how_many_moves >= 100 {
+            Some(DrawReason::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the game in a draw by threefold repetition or the fifty-move rule, when the position allows the claim
+    pub fn claim_draw(&mut self) -> Result<DrawReason, MoveError> {
+        if !self.game_state.endgame_status.is_ongoing() {
+            return Err(MoveError::GameOver);
+        }
+        let reason = self.can_claim_draw().ok_or(MoveError::NoDrawToClaim)?;
+        self.game_state.endgame_status = EndgameStatus::Draw(reason);
+        Ok(reason)
+    }
+
+    /// Returns the endgame status of the game: ongoing, checkmate, stalemate or one of the automatic draws.
+    /// Threefold repetition and the fifty-move rule only end the game when claimed.
+    /// A game already over, e.g. by a claimed draw, a resignation or a timeout, keeps its result
     pub fn evaluate_endgame(&mut self) -> EndgameStatus {
-        if self.game_state.how_many_moves >= 100 {
-            self.game_state.endgame_status = EndgameStatus::Draw(DrawReason::FiftyMoveRule);
-            return EndgameStatus::Draw(DrawReason::FiftyMoveRule);
+        if !self.game_state.endgame_status.is_ongoing() {
+            return self.game_state.endgame_status;
+        }
+        let endgame_status = self.board.evaluate_endgame(&self.game_state);
+        if !endgame_status.is_ongoing() {
+            // A mate on the seventy-fifth move still counts
+            self.game_state.endgame_status = endgame_status;
+            return endgame_status;
+        }
+
+        if self.repetitions() >= 5 {
+            self.game_state.endgame_status = EndgameStatus::Draw(DrawReason::FivefoldRepetition);
+            return EndgameStatus::Draw(DrawReason::FivefoldRepetition);
         }
-        if self.positions.values().any(|&v| v >= 3) {
-            self.game_state.endgame_status = EndgameStatus::Draw(DrawReason::ThreefoldRepetition);
-            return EndgameStatus::Draw(DrawReason::ThreefoldRepetition);
+        if self.game_state.how_many_moves >= 150 {
+            self.game_state.endgame_status = EndgameStatus::Draw(DrawReason::SeventyFiveMoveRule);
+            return EndgameStatus::Draw(DrawReason::SeventyFiveMoveRule);
         }
 
-        if self.board.is_insufficient_material() {
+        // Captured pieces come back in drop variants, so material is only insufficient when nothing is in hand.
+        // In bughouse pieces can still arrive from the other board
+        let can_receive_pieces = match self.game_state.variant {
+            Variant::Standard => false,
+            Variant::Crazyhouse => !self.game_state.white_pocket.is_empty() || !self.game_state.black_pocket.is_empty(),
+            Variant::Bughouse => true,
+        };
+        if !can_receive_pieces && self.board.is_insufficient_material() {
             self.game_state.endgame_status = EndgameStatus::Draw(DrawReason::InsufficientMaterial);
             return EndgameStatus::Draw(DrawReason::InsufficientMaterial);
         }
 
-        let endgame_status = self.board.evaluate_endgame(&self.game_state);
+        self.game_state.endgame_status = EndgameStatus::Ongoing;
+        EndgameStatus::Ongoing
+    }
+
+    /// The squares the piece at start can go to, empty when there is no piece of the color to play there
+    pub fn get_legal_moves(&self, start: Square) -> Vec<Square> {
+        self.board.get_legal_moves(start.coords(), &self.game_state).into_iter().map(Square::from_coords).collect()
+    }
+
+    /// Returns the squares where the color to play can drop a piece of that kind
+    pub fn get_legal_drops(&self, kind: PieceKind) -> Vec<Square> {
+        let piece = Piece::new(kind, self.game_state.turn);
+        self.board.get_legal_drops(piece, &self.game_state).into_iter().map(Square::from_coords).collect()
+    }
+
+    /// Ends the game with a result decided outside of the board: resignation, agreement, timeout or the partner's board in bughouse
+    pub fn end_game(&mut self, endgame_status: EndgameStatus) {
         self.game_state.endgame_status = endgame_status;
-        endgame_status
     }
 
-    /// Panics if there is no piece at start
-    pub fn get_legal_moves(&mut self, start: (usize, usize)) -> Vec<(usize, usize)> {
-        self.board.get_legal_moves(start, &self.game_state)
+    pub fn turn(&self) -> Color {
+        self.game_state.turn
     }
 
-    pub fn is_promotion_pending(&self) -> Option<(usize, Color)> {
-        self.game_state.promotion_pending
+    pub fn variant(&self) -> Variant {
+        self.game_state.variant
+    }
+
+    pub fn pocket(&self, color: Color) -> Pocket {
+        *self.game_state.pocket(color)
+    }
+
+    /// File of the pawn waiting on the last rank to be promoted, and its color
+    pub fn is_promotion_pending(&self) -> Option<(File, Color)> {
+        self.game_state.promotion_pending.map(|(file, color)| (File::ALL[file], color))
     }
 
     pub fn endgame_status(&self) -> EndgameStatus {
         self.game_state.endgame_status
     }
+
+    /// Hash of the position used to look it up in Polyglot opening books
+    pub fn polyglot_key(&self) -> u64 {
+        book::polyglot_key(&self.board, &self.game_state)
+    }
 }
+
+#[cfg(test)]
+#[path = "tests/test_game.rs"]
+mod test_game;
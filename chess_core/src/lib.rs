@@ -1,12 +1,36 @@
 mod rules;
+mod book;
+mod controller;
+pub mod eval;
 mod core_struct;
+mod errors;
+mod fen;
 mod game;
 mod move_execution;
 mod move_generation;
+mod moves;
+pub mod pgn;
+pub mod puzzle;
+mod replay;
+pub mod review;
+pub mod search;
+mod snapshot;
+mod square;
+mod tree;
 mod utils;
+mod variant;
 
-pub use core_struct::{Color, Piece};
+pub use book::{BookMove, OpeningBook};
+pub use controller::{GameController, GameEvent};
+pub use core_struct::{Color, Piece, PieceKind};
+pub use errors::{MoveError, ParseError};
 pub use game::{Game, PromotionPiece};
-pub use rules::{EndgameStatus, DrawReason, WinReason};
+pub use moves::Move;
+pub use replay::Replay;
+pub use snapshot::SNAPSHOT_VERSION;
+pub use square::{File, Rank, Square};
+pub use tree::{GameTree, Node, NodeId, TreeError, TreeToken};
+pub use rules::{EndgameStatus, DrawReason, WinReason, Setup, SetupError};
 pub use utils::parse_move;
+pub use variant::{Pocket, Variant};
 
@@ -12,8 +12,7 @@ impl Board {
         let piece = self.grid[start.0][start.1].unwrap();
         let taken_piece = self.grid[end.0][end.1];
 
-        if taken_piece.is_some() {
-            let taken_piece = taken_piece.unwrap();
+        if let Some(taken_piece) = taken_piece {
             self.pieces.get_mut(&taken_piece).unwrap().remove(&end);
             result.0 = Some((taken_piece, end));
         }
@@ -74,64 +73,9 @@ impl Board {
         result
     }
 
-    pub(crate) fn undo_move(
-        &mut self,
-        start: (usize, usize),
-        end: (usize, usize),
-        taken_piece: Option<(Piece, (usize, usize))>,
-        promotion: bool,
-    ) {
-        // taken_piece is the piece that was taken in the move (we add position for en passant)
-
-        let piece = if promotion {
-            Piece::Pawn(self.grid[end.0][end.1].unwrap().color())
-        } else {
-            self.grid[end.0][end.1].unwrap()
-        };
-
-        self.grid[start.0][start.1] = Some(piece);
-        self.grid[end.0][end.1] = None;
-        self.pieces.get_mut(&piece).unwrap().remove(&end);
-        self.pieces.get_mut(&piece).unwrap().insert(start);
-
-        if taken_piece.is_some() {
-            let (taken_piece, taken_position) = taken_piece.unwrap();
-            self.grid[taken_position.0][taken_position.1] = Some(taken_piece);
-            self.pieces
-                .get_mut(&taken_piece)
-                .unwrap()
-                .insert(taken_position);
-        }
-
-        // move the rook back in case of castling
-        if piece == Piece::King(Color::White) && start == (0, 4) {
-            let rook = Piece::Rook(Color::White);
-            if end == (0, 6) {
-                self.grid[0][7] = Some(rook);
-                self.grid[0][5] = None;
-                self.pieces.get_mut(&rook).unwrap().insert((0, 7));
-                self.pieces.get_mut(&rook).unwrap().remove(&(0, 5));
-            }
-            if end == (0, 2) {
-                self.grid[0][0] = Some(rook);
-                self.grid[0][3] = None;
-                self.pieces.get_mut(&rook).unwrap().insert((0, 0));
-                self.pieces.get_mut(&rook).unwrap().remove(&(0, 3));
-            }
-        } else if piece == Piece::King(Color::Black) && start == (7, 4) {
-            let rook = Piece::Rook(Color::Black);
-            if end == (7, 6) {
-                self.grid[7][7] = Some(rook);
-                self.grid[7][5] = None;
-                self.pieces.get_mut(&rook).unwrap().insert((7, 7));
-                self.pieces.get_mut(&rook).unwrap().remove(&(7, 5));
-            }
-            if end == (7, 2) {
-                self.grid[7][0] = Some(rook);
-                self.grid[7][3] = None;
-                self.pieces.get_mut(&rook).unwrap().insert((7, 0));
-                self.pieces.get_mut(&rook).unwrap().remove(&(7, 3));
-            }
-        }
+    /// Should only be used if we know the square is empty
+    pub(crate) fn execute_drop(&mut self, piece: Piece, square: (usize, usize)) {
+        self.grid[square.0][square.1] = Some(piece);
+        self.pieces.entry(piece).or_default().insert(square);
     }
 }
@@ -1,5 +1,6 @@
 use crate::core_struct::{Color, Piece, Board};
 use crate::game::GameState;
+use crate::variant::Pocket;
 
 impl Board {
     fn generate_pawn_moves(&self, start: (usize, usize), color: Color) -> Vec<(usize, usize)> {
@@ -14,7 +15,7 @@ impl Board {
         ];
 
         potential_moves.into_iter()
-            .filter_map(|pos| is_valid_position(pos))
+            .filter_map(is_valid_position)
             .collect()
     }
 
@@ -31,7 +32,7 @@ impl Board {
         ];
 
         potential_moves.into_iter()
-            .filter_map(|pos| is_valid_position(pos))
+            .filter_map(is_valid_position)
             .collect()
     }
 
@@ -101,12 +102,34 @@ impl Board {
         ];
 
         potential_moves.into_iter()
-            .filter_map(|pos| is_valid_position(pos))
+            .filter_map(is_valid_position)
             .collect()
     }
-    /// Only if we know there is a piece at start
-    pub(crate) fn get_legal_moves(&mut self, start: (usize, usize), game_state: &GameState) -> Vec<(usize, usize)> {
-        let piece = self.grid[start.0][start.1].unwrap();
+    /// Number of squares a knight, bishop, rook or queen attacks that don't hold a piece of its color, checks are ignored
+    pub(crate) fn mobility(&self, start: (usize, usize)) -> usize {
+        let piece = match self.grid[start.0][start.1] {
+            Some(piece) => piece,
+            None => return 0,
+        };
+
+        let moves = match piece {
+            Piece::Knight(_) => self.generate_knight_moves(start),
+            Piece::Bishop(_) => self.generate_bishop_moves(start),
+            Piece::Rook(_) => self.generate_rook_moves(start),
+            Piece::Queen(_) => self.generate_queen_moves(start),
+            Piece::Pawn(_) | Piece::King(_) => return 0,
+        };
+
+        moves.into_iter()
+            .filter(|&(i, j)| self.grid[i][j].is_none_or(|other| other.color() != piece.color()))
+            .count()
+    }
+
+    /// Empty when there is no piece at start
+    pub(crate) fn get_legal_moves(&self, start: (usize, usize), game_state: &GameState) -> Vec<(usize, usize)> {
+        let Some(piece) = self.grid.get(start.0).and_then(|rank| rank.get(start.1)).copied().flatten() else {
+            return Vec::new();
+        };
         let color = piece.color();
 
         let potential_moves = match piece {
@@ -119,11 +142,23 @@ impl Board {
         };
 
         potential_moves.into_iter()
-            .filter(|&end| self.is_valid_move(start, end, &game_state).is_ok())
+            .filter(|&end| self.is_valid_move(start, end, game_state).is_ok())
             .collect()
     }
 
-    pub(crate) fn has_legal_moves(&mut self, game_state: &GameState) -> bool {
+    pub(crate) fn get_legal_drops(&self, piece: Piece, game_state: &GameState) -> Vec<(usize, usize)> {
+        let mut drops = Vec::new();
+        for i in 0..8 {
+            for j in 0..8 {
+                if self.is_valid_drop(piece, (i, j), game_state).is_ok() {
+                    drops.push((i, j));
+                }
+            }
+        }
+        drops
+    }
+
+    pub(crate) fn has_legal_moves(&self, game_state: &GameState) -> bool {
         let color = game_state.turn;
         for i in 0..8 {
             for j in 0..8 {
@@ -135,6 +170,11 @@ impl Board {
                 }
             }
         }
+        for kind in Pocket::KINDS {
+            if game_state.pocket(color).count(kind) > 0 && !self.get_legal_drops(Piece::new(kind, color), game_state).is_empty() {
+                return true;
+            }
+        }
         false
     }
 
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::core_struct::{Color, Piece, PieceKind};
+use crate::errors::{MoveError, ParseError};
+use crate::game::{Game, PromotionPiece};
+use crate::replay::Replay;
+use crate::square::{File, Rank, Square};
+
+/// A move as it is played in a game: a piece moving on the board, or a piece dropped from a pocket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Move {
+    Normal {
+        start: Square,
+        end: Square,
+        promotion: Option<PromotionPiece>,
+    },
+    Drop {
+        kind: PieceKind,
+        square: Square,
+    },
+}
+
+impl Move {
+    /// A move that is not a promotion
+    pub fn new(start: Square, end: Square) -> Move {
+        Move::Normal { start, end, promotion: None }
+    }
+
+    /// The square the piece lands on
+    pub fn destination(&self) -> Square {
+        match *self {
+            Move::Normal { end, .. } => end,
+            Move::Drop { square, .. } => square,
+        }
+    }
+}
+
+fn promotion_letter(promotion: PromotionPiece) -> char {
+    match promotion {
+        PromotionPiece::Queen => 'Q',
+        PromotionPiece::Rook => 'R',
+        PromotionPiece::Bishop => 'B',
+        PromotionPiece::Knight => 'N',
+    }
+}
+
+fn piece_letter(kind: PieceKind) -> &'static str {
+    match kind {
+        PieceKind::Pawn => "",
+        PieceKind::Knight => "N",
+        PieceKind::Bishop => "B",
+        PieceKind::Rook => "R",
+        PieceKind::Queen => "Q",
+        PieceKind::King => "K",
+    }
+}
+
+/// Pawns have a letter when they are dropped, P@e4
+fn drop_letter(kind: PieceKind) -> &'static str {
+    match kind {
+        PieceKind::Pawn => "P",
+        kind => piece_letter(kind),
+    }
+}
+
+/// Coordinate notation: e2e4, e7e8q, N@f3 for a drop
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Move::Normal { start, end, promotion } => {
+                write!(f, "{}{}", start, end)?;
+                if let Some(promotion) = promotion {
+                    write!(f, "{}", promotion_letter(promotion).to_ascii_lowercase())?;
+                }
+                Ok(())
+            }
+            Move::Drop { kind, square } => write!(f, "{}@{}", drop_letter(kind), square),
+        }
+    }
+}
+
+/// Reads the coordinate notation written by Display, as used by UCI engines and the Lichess puzzles
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Move, ParseError> {
+        if let Some((piece, square)) = s.split_once('@') {
+            let kind = match piece {
+                "P" => PieceKind::Pawn,
+                "N" => PieceKind::Knight,
+                "B" => PieceKind::Bishop,
+                "R" => PieceKind::Rook,
+                "Q" => PieceKind::Queen,
+                _ => return Err(ParseError::InvalidDropPiece),
+            };
+            let square = square.parse()?;
+            return Ok(Move::Drop { kind, square });
+        }
+
+        let start = s.get(0..2).ok_or(ParseError::InvalidSquare)?.parse()?;
+        let end = s.get(2..4).ok_or(ParseError::InvalidSquare)?.parse()?;
+        let promotion = match s.get(4..) {
+            Some("") => None,
+            Some("q") => Some(PromotionPiece::Queen),
+            Some("r") => Some(PromotionPiece::Rook),
+            Some("b") => Some(PromotionPiece::Bishop),
+            Some("n") => Some(PromotionPiece::Knight),
+            _ => return Err(ParseError::InvalidPromotionPiece),
+        };
+        Ok(Move::Normal { start, end, promotion })
+    }
+}
+
+impl Game {
+    /// Standard algebraic notation of a legal move in the current position, e.g. Nbd7, exd6, O-O, e8=Q+ or N@f3
+    pub fn to_san(&self, mv: Move) -> Result<String, MoveError> {
+        self.without_history().san(mv)
+    }
+
+    /// The notation of a move, found by playing it and taking it back
+    fn san(&mut self, mv: Move) -> Result<String, MoveError> {
+        let mut san = match mv {
+            Move::Drop { kind, square } => format!("{}@{}", drop_letter(kind), square),
+            Move::Normal { start, end, promotion } => {
+                let piece = self.piece_at(start).ok_or(MoveError::NoPiece(start))?;
+                let is_capture = self.piece_at(end).is_some() || (piece.kind() == PieceKind::Pawn && start.file() != end.file());
+
+                if piece.kind() == PieceKind::King && start.file().index().abs_diff(end.file().index()) == 2 {
+                    if end.file() == File::G { "O-O".to_string() } else { "O-O-O".to_string() }
+                } else if piece.kind() == PieceKind::Pawn {
+                    let mut san = String::new();
+                    if is_capture {
+                        san.push(start.file().to_char());
+                        san.push('x');
+                    }
+                    san.push_str(&end.to_string());
+                    if let Some(promotion) = promotion {
+                        san.push('=');
+                        san.push(promotion_letter(promotion));
+                    }
+                    san
+                } else {
+                    // Other pieces of the same kind that could go to the same square
+                    let rivals: Vec<Square> = self.board.pieces[&piece]
+                        .iter()
+                        .map(|&coords| Square::from_coords(coords))
+                        .filter(|&square| square != start && self.get_legal_moves(square).contains(&end))
+                        .collect();
+
+                    let mut san = piece_letter(piece.kind()).to_string();
+                    if !rivals.is_empty() {
+                        if rivals.iter().all(|rival| rival.file() != start.file()) {
+                            san.push(start.file().to_char());
+                        } else if rivals.iter().all(|rival| rival.rank() != start.rank()) {
+                            san.push(start.rank().to_char());
+                        } else {
+                            san.push_str(&start.to_string());
+                        }
+                    }
+                    if is_capture {
+                        san.push('x');
+                    }
+                    san.push_str(&end.to_string());
+                    san
+                }
+            }
+        };
+
+        // Playing the move checks it and tells why it is refused
+        self.play(mv)?;
+        if self.is_in_check() {
+            let is_mate = self.all_legal_moves().is_empty();
+            san.push(if is_mate { '#' } else { '+' });
+        }
+        self.undo();
+        Ok(san)
+    }
+
+    /// Legal move from its standard algebraic notation, the check marks and annotations are optional and 0-0 is read as O-O
+    pub fn parse_san(&self, san: &str) -> Result<Move, ParseError> {
+        let bare = |san: &str| san.trim_end_matches(['+', '#', '!', '?']).replace('0', "O");
+        let wanted = bare(san.trim());
+        let mut game = self.without_history();
+        self.all_legal_moves()
+            .into_iter()
+            .find(|&mv| game.san(mv).is_ok_and(|legal| bare(&legal) == wanted))
+            .ok_or(ParseError::UnknownMove)
+    }
+
+    /// Notation of every move played since the start of the game
+    pub fn history_to_san(&self) -> Vec<String> {
+        self.replay().map(Replay::into_san).unwrap_or_default()
+    }
+
+    /// Moves of a line played from the current position with their numbers, 12.Nf3 Nc6 or 12...Nc6 when black moves first
+    pub fn numbered_san(&self, san: &[String]) -> Vec<String> {
+        let mut number = self.fullmove_number();
+        let mut color = self.turn();
+        let mut words = Vec::new();
+        for (index, san) in san.iter().enumerate() {
+            match color {
+                Color::White => words.push(format!("{}.{}", number, san)),
+                Color::Black if index == 0 => words.push(format!("{}...{}", number, san)),
+                Color::Black => words.push(san.clone()),
+            }
+            if color == Color::Black {
+                number = number.saturating_add(1);
+            }
+            color = color.opposite();
+        }
+        words
+    }
+
+    /// Notation of a sequence of moves played from the current position, stops at the first illegal move
+    pub fn line_to_san(&self, moves: &[Move]) -> Vec<String> {
+        let mut game = self.without_history();
+        let mut line = Vec::new();
+        for &mv in moves {
+            match game.san(mv) {
+                Ok(san) => line.push(san),
+                Err(_) => break,
+            }
+            // to_san only accepts legal moves
+            game.play(mv).unwrap();
+        }
+        line
+    }
+}
+
+/// Pieces that can be given for a promotion, from the strongest
+pub(crate) const PROMOTION_PIECES: [PromotionPiece; 4] = [
+    PromotionPiece::Queen,
+    PromotionPiece::Rook,
+    PromotionPiece::Bishop,
+    PromotionPiece::Knight,
+];
+
+/// Whether the pawn on start reaches the last rank
+pub(crate) fn is_promotion(piece: Piece, end: Square) -> bool {
+    match piece {
+        Piece::Pawn(color) => end.rank() == Rank::back_rank(color.opposite()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_moves.rs"]
+mod test_moves;
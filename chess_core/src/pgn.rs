@@ -0,0 +1,338 @@
+use std::fmt;
+
+use crate::core_struct::{Color, Piece};
+use crate::errors::{MoveError, ParseError};
+use crate::game::Game;
+use crate::moves::Move;
+use crate::replay::Replay;
+use crate::rules::{EndgameStatus, Setup};
+use crate::variant::Variant;
+
+/// Tags every game starts with, in this order
+const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// A game read from Portable Game Notation, the variations, comments and annotations are left out
+#[derive(Debug, Clone)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>, // In the order they were read
+    pub start: Game,
+    pub moves: Vec<Move>,
+    pub received: Vec<(usize, Piece)>, // Pieces received in bughouse after how many moves, PGN has no notation for them
+    pub result: String, // 1-0, 0-1, 1/2-1/2, or * when the game goes on
+}
+
+/// Why a game of a PGN file can't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnError {
+    pub game: usize, // Index of the game in the file, from 0
+    pub ply: usize, // Moves read before the error
+    pub token: String,
+    pub reason: ParseError,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "game {}, after {} moves: {} ({})", self.game + 1, self.ply, self.reason, self.token)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Tag(String, String),
+    Word(String),
+    Result(String),
+    Invalid(String, ParseError),
+}
+
+/// Splits PGN text into tags, moves and results. Move numbers, comments, variations, annotations and escaped lines are skipped
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+    while let Some(c) = chars.next() {
+        let was_line_start = line_start;
+        line_start = c == '\n';
+        match c {
+            c if c.is_whitespace() => {}
+            '%' if was_line_start => {
+                chars.by_ref().find(|&c| c == '\n');
+                line_start = true;
+            }
+            ';' => {
+                chars.by_ref().find(|&c| c == '\n');
+                line_start = true;
+            }
+            '{' => {
+                if chars.by_ref().all(|c| c != '}') {
+                    tokens.push(Token::Invalid("{".to_string(), ParseError::UnclosedComment));
+                }
+            }
+            '(' => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => depth -= 1,
+                        Some('{') => {
+                            chars.by_ref().find(|&c| c == '}');
+                        }
+                        Some(_) => {}
+                        None => {
+                            tokens.push(Token::Invalid("(".to_string(), ParseError::UnclosedVariation));
+                            break;
+                        }
+                    }
+                }
+            }
+            '[' => {
+                // A bracket in the quoted value doesn't close the tag, e.g. the pockets of a crazyhouse FEN
+                let mut tag = String::new();
+                let (mut quoted, mut escaped) = (false, false);
+                for c in chars.by_ref() {
+                    match c {
+                        ']' if !quoted => break,
+                        '"' if !escaped => quoted = !quoted,
+                        _ => {}
+                    }
+                    escaped = c == '\\' && !escaped;
+                    tag.push(c);
+                }
+                let parsed = tag.trim().split_once(char::is_whitespace).and_then(|(name, value)| {
+                    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+                    Some(Token::Tag(name.to_string(), value.replace("\\\"", "\"").replace("\\\\", "\\")))
+                });
+                tokens.push(parsed.unwrap_or(Token::Invalid(format!("[{}]", tag), ParseError::InvalidTag)));
+            }
+            ')' | ']' | '}' => tokens.push(Token::Invalid(c.to_string(), ParseError::NothingToClose)),
+            c => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || "{}()[];".contains(next) {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "1-0" | "0-1" | "1/2-1/2" | "*" => tokens.push(Token::Result(word)),
+                    _ if word.starts_with('$') => {}
+                    _ => {
+                        // A move number, possibly stuck to its move: 12. 12... 12.Nf3
+                        let san = match word.rfind('.') {
+                            Some(dot) if word.starts_with(|c: char| c.is_ascii_digit()) => &word[dot + 1..],
+                            _ => &word,
+                        };
+                        if !san.is_empty() {
+                            tokens.push(Token::Word(san.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    tokens
+}
+
+/// Reads every game of a PGN file, a game that can't be read doesn't stop the next ones
+pub fn read_pgn(text: &str) -> Vec<Result<PgnGame, PgnError>> {
+    // A game ends with its result, or when the tags of the next one start
+    let mut games: Vec<Vec<Token>> = Vec::new();
+    let mut current = Vec::new();
+    let mut in_movetext = false;
+    for token in tokenize(text) {
+        match token {
+            Token::Tag(..) if in_movetext => {
+                games.push(std::mem::take(&mut current));
+                current.push(token);
+                in_movetext = false;
+            }
+            Token::Result(_) => {
+                current.push(token);
+                games.push(std::mem::take(&mut current));
+                in_movetext = false;
+            }
+            Token::Tag(..) => current.push(token),
+            token => {
+                current.push(token);
+                in_movetext = true;
+            }
+        }
+    }
+    if !current.is_empty() {
+        games.push(current);
+    }
+
+    games.into_iter().enumerate().map(|(index, tokens)| read_game(index, tokens)).collect()
+}
+
+fn read_game(index: usize, tokens: Vec<Token>) -> Result<PgnGame, PgnError> {
+    let error = |ply: usize, token: &str, reason: ParseError| PgnError { game: index, ply, token: token.to_string(), reason };
+
+    let tags: Vec<(String, String)> = tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Tag(name, value) => Some((name.clone(), value.clone())),
+            _ => None,
+        })
+        .collect();
+    let tag = |name: &str| tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str());
+
+    let variant = match tag("Variant") {
+        Some(name) if name.eq_ignore_ascii_case("crazyhouse") => Variant::Crazyhouse,
+        Some(name) if !name.eq_ignore_ascii_case("standard") => return Err(error(0, name, ParseError::UnsupportedVariant)),
+        _ => Variant::Standard,
+    };
+    let start = match tag("FEN") {
+        Some(fen) => {
+            let mut setup = Setup::from_fen(fen).map_err(|reason| error(0, fen, reason))?;
+            // A FEN with pockets is read as crazyhouse, the Variant tag says it otherwise
+            if variant != Variant::Standard {
+                setup.variant = variant;
+            }
+            Game::from_setup(&setup).map_err(|e| error(0, fen, ParseError::UnplayablePosition(e)))?
+        }
+        None => Game::with_variant(variant),
+    };
+
+    let mut game = start.clone();
+    let mut moves = Vec::new();
+    let mut result = tag("Result").unwrap_or("*").to_string();
+    for token in &tokens {
+        match token {
+            Token::Tag(..) => {}
+            Token::Word(san) => {
+                let mv = game.parse_san(san).map_err(|reason| error(moves.len(), san, reason))?;
+                // parse_san only returns legal moves
+                game.play(mv).unwrap();
+                moves.push(mv);
+            }
+            Token::Result(text) => result = text.clone(),
+            Token::Invalid(text, reason) => return Err(error(moves.len(), text, *reason)),
+        }
+    }
+
+    Ok(PgnGame { tags, start, moves, received: Vec::new(), result })
+}
+
+impl PgnGame {
+    /// A game played with chess_core, without tags
+    pub fn from_game(game: &Game) -> PgnGame {
+        let mut start = game.clone();
+        while start.undo().is_some() {}
+        PgnGame {
+            tags: Vec::new(),
+            start,
+            moves: game.history().to_vec(),
+            // The pieces received before the first move are in the pockets of the start
+            received: game.received.iter().filter(|(ply, _)| *ply > 0).copied().collect(),
+            result: result_text(game.endgame_status()).to_string(),
+        }
+    }
+
+    /// Value of a tag, if the game has it
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    /// The game once all its moves are played, with its end detected. Stops at the first move that can't be played
+    pub fn final_position(&self) -> Game {
+        let mut game = match self.replay() {
+            Ok(mut replay) => {
+                while let Ok(Some(_)) = replay.play_next() {}
+                replay.into_game()
+            }
+            Err(_) => self.start.clone(),
+        };
+        game.evaluate_endgame();
+        game
+    }
+
+    fn replay(&self) -> Result<Replay, MoveError> {
+        Replay::new(self.start.clone(), self.moves.clone(), self.received.clone())
+    }
+
+    /// The game in PGN, the seven tags first and lines of at most 80 characters
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = write_tags(&self.start, &self.result, &self.tags);
+        pgn.push('\n');
+
+        let mut tokens = Vec::new();
+        let mut number = self.start.fullmove_number();
+        let mut color = self.start.turn();
+        for (index, san) in self.replay().map(Replay::into_san).unwrap_or_default().into_iter().enumerate() {
+            match color {
+                Color::White => tokens.push(format!("{}. {}", number, san)),
+                Color::Black if index == 0 => tokens.push(format!("{}... {}", number, san)),
+                Color::Black => tokens.push(san),
+            }
+            if color == Color::Black {
+                number = number.saturating_add(1);
+            }
+            color = color.opposite();
+        }
+        tokens.push(self.result.clone());
+        pgn.push_str(&wrap_movetext(tokens));
+        pgn
+    }
+}
+
+/// The result written at the end of a game
+pub(crate) fn result_text(status: EndgameStatus) -> &'static str {
+    match status {
+        EndgameStatus::Ongoing => "*",
+        EndgameStatus::Win(Color::White, _) => "1-0",
+        EndgameStatus::Win(Color::Black, _) => "0-1",
+        EndgameStatus::Draw(_) => "1/2-1/2",
+    }
+}
+
+/// The seven tags, unknown ones as ?, then the other tags, the variant and the starting position when it isn't the usual one
+pub(crate) fn write_tags(start: &Game, result: &str, tags: &[(String, String)]) -> String {
+    let mut pgn = String::new();
+    let tag = |name: &str| tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str());
+    for name in SEVEN_TAG_ROSTER {
+        let value = match name {
+            "Result" => result,
+            "Date" => tag(name).unwrap_or("????.??.??"),
+            _ => tag(name).unwrap_or("?"),
+        };
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    for (name, value) in tags {
+        if !SEVEN_TAG_ROSTER.contains(&name.as_str()) && !["Variant", "SetUp", "FEN"].contains(&name.as_str()) {
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\"")));
+        }
+    }
+    if start.variant() == Variant::Crazyhouse {
+        pgn.push_str("[Variant \"Crazyhouse\"]\n");
+    }
+    let fen = start.to_fen();
+    if fen != Game::with_variant(start.variant()).to_fen() {
+        pgn.push_str(&format!("[SetUp \"1\"]\n[FEN \"{}\"]\n", fen));
+    }
+    pgn
+}
+
+/// Joins the tokens of the move text in lines of at most 80 characters
+pub(crate) fn wrap_movetext(tokens: Vec<String>) -> String {
+    let mut text = String::new();
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > 80 {
+            text.push_str(&line);
+            text.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    text.push_str(&line);
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+#[path = "tests/test_pgn.rs"]
+mod test_pgn;
@@ -0,0 +1,252 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::errors::{MoveError, ParseError};
+use crate::game::Game;
+use crate::moves::Move;
+use crate::core_struct::Color;
+use crate::rules::{EndgameStatus, Setup, SetupError, WinReason};
+use crate::search::{self, LineScore};
+
+/// Rating of a player who has not solved any puzzle yet
+pub const DEFAULT_RATING: i32 = 1500;
+/// How much a rating moves after one puzzle, at most
+const RATING_FACTOR: f32 = 32.0;
+
+/// Why a line of the puzzle database can't be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A puzzle needs an id, a FEN, moves and a rating
+    MissingColumns,
+    /// A puzzle needs the opponent's move and at least one answer
+    TooFewMoves,
+    InvalidRating,
+    /// The FEN or a move can't be read
+    Parse(ParseError),
+    Setup(SetupError),
+    /// A move of the solution can't be played
+    Move(MoveError),
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleError::MissingColumns => write!(f, "A puzzle needs an id, a FEN, moves and a rating"),
+            PuzzleError::TooFewMoves => write!(f, "A puzzle needs the opponent's move and at least one answer"),
+            PuzzleError::InvalidRating => write!(f, "Invalid puzzle rating"),
+            PuzzleError::Parse(e) => e.fmt(f),
+            PuzzleError::Setup(e) => write!(f, "The puzzle position can't be played: {}", e),
+            PuzzleError::Move(e) => write!(f, "The solution can't be played: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {}
+
+impl From<ParseError> for PuzzleError {
+    fn from(e: ParseError) -> PuzzleError {
+        PuzzleError::Parse(e)
+    }
+}
+
+impl From<MoveError> for PuzzleError {
+    fn from(e: MoveError) -> PuzzleError {
+        PuzzleError::Move(e)
+    }
+}
+
+/// A tactics puzzle: the opponent plays the first move of the solution, then the player has to find the others
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub id: String,
+    pub fen: String,
+    pub moves: Vec<Move>,
+    pub rating: i32,
+    pub themes: Vec<String>,
+}
+
+impl Puzzle {
+    /// A line of the Lichess puzzle database: PuzzleId,FEN,Moves,Rating followed by optional columns.
+    /// The moves are in coordinate notation separated by spaces, the themes are separated by spaces in the eighth column
+    pub fn from_csv_line(line: &str) -> Result<Puzzle, PuzzleError> {
+        let columns: Vec<&str> = line.trim().split(',').collect();
+        if columns.len() < 4 {
+            return Err(PuzzleError::MissingColumns);
+        }
+
+        let moves = columns[2]
+            .split_whitespace()
+            .map(|mv| mv.parse())
+            .collect::<Result<Vec<Move>, _>>()?;
+        if moves.len() < 2 {
+            return Err(PuzzleError::TooFewMoves);
+        }
+
+        let puzzle = Puzzle {
+            id: columns[0].to_string(),
+            fen: columns[1].to_string(),
+            moves,
+            rating: columns[3].parse().map_err(|_| PuzzleError::InvalidRating)?,
+            themes: columns.get(7).map_or(Vec::new(), |themes| themes.split_whitespace().map(String::from).collect()),
+        };
+        // Checks that the position and the whole solution can be played
+        let mut attempt = puzzle.start()?;
+        for &mv in &puzzle.moves[1..] {
+            attempt.game.play(mv)?;
+        }
+        Ok(puzzle)
+    }
+
+    /// Whether the solution ends with a checkmate, any other move that still mates in time then solves the puzzle too
+    pub fn is_mate(&self) -> bool {
+        self.themes.iter().any(|theme| theme.starts_with("mate"))
+    }
+
+    /// The puzzle with the opponent's first move played
+    pub fn start(&self) -> Result<PuzzleAttempt, PuzzleError> {
+        let setup = Setup::from_fen(&self.fen)?;
+        let mut game = Game::from_setup(&setup).map_err(PuzzleError::Setup)?;
+        game.play(*self.moves.first().ok_or(PuzzleError::TooFewMoves)?)?;
+        Ok(PuzzleAttempt { puzzle: self.clone(), game, line: self.moves[1..].to_vec(), mistakes: 0 })
+    }
+}
+
+/// Reads every puzzle of a CSV file, the header line of the Lichess database is skipped
+pub fn load_puzzles<P: AsRef<Path>>(path: P) -> io::Result<Vec<Puzzle>> {
+    let content = fs::read_to_string(path)?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with("PuzzleId"))
+        .map(|(index, line)| {
+            Puzzle::from_csv_line(line).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("Line {}: {}", index + 1, e))
+            })
+        })
+        .collect()
+}
+
+/// Answer of the puzzle to a move of the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// The move is right and the opponent answered with the next move of the solution
+    Correct(Move),
+    Solved,
+    /// The move is not played
+    Wrong,
+}
+
+/// A puzzle being solved
+#[derive(Debug, Clone)]
+pub struct PuzzleAttempt {
+    puzzle: Puzzle,
+    game: Game,
+    line: Vec<Move>, // The rest of the solution, from the player's next move. It follows the search after an alternative mate
+    mistakes: usize,
+}
+
+impl PuzzleAttempt {
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    /// The position the player has to find a move in
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.line.is_empty()
+    }
+
+    /// Number of wrong moves played so far
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    /// The next move of the solution
+    pub fn hint(&self) -> Option<Move> {
+        self.line.first().copied()
+    }
+
+    /// Plays a move of the player, then the opponent's answer from the solution.
+    /// A move that checkmates is always right. In a mate puzzle, another move is right too if it still mates
+    /// within the moves left, the opponent then answers with the best defence found by the search
+    pub fn play(&mut self, mv: Move) -> Answer {
+        let expected = match self.hint() {
+            Some(expected) => expected,
+            None => return Answer::Wrong,
+        };
+
+        let mut game = self.game.clone();
+        if game.play(mv).is_err() {
+            return Answer::Wrong;
+        }
+        let is_mate = matches!(game.evaluate_endgame(), EndgameStatus::Win(_, WinReason::Checkmate));
+        if is_mate {
+            self.game = game;
+            self.line.clear();
+            return Answer::Solved;
+        }
+
+        if mv == expected {
+            self.line.remove(0);
+        } else {
+            match self.mating_line(&game) {
+                Some(line) => self.line = line,
+                None => {
+                    self.mistakes += 1;
+                    return Answer::Wrong;
+                }
+            }
+        }
+        self.game = game;
+        if self.is_solved() {
+            return Answer::Solved;
+        }
+
+        let reply = self.line.remove(0);
+        // The solution is checked when the puzzle is read, one built by hand ends where it stops being legal
+        if self.game.play(reply).is_err() {
+            self.line.clear();
+            return Answer::Solved;
+        }
+        self.game.evaluate_endgame();
+        if self.is_solved() {
+            return Answer::Solved;
+        }
+        Answer::Correct(reply)
+    }
+
+    /// After a move of the player that is not the solution's, the opponent's best defence and the rest of a mate
+    /// the player can still give in no more moves than the solution has left, if there is one
+    fn mating_line(&self, after: &Game) -> Option<Vec<Move>> {
+        if !self.puzzle.is_mate() {
+            return None;
+        }
+        // The line holds the player's move just played, the opponent's replies and the player's next moves
+        let moves_left = self.line.len().div_ceil(2) - 1;
+        if moves_left == 0 {
+            return None;
+        }
+        let best = search::best_line(after, 2 * moves_left as u32)?;
+        let player = self.game.turn();
+        match best.score {
+            LineScore::Mate(moves) if (moves > 0) == (player == Color::White) && moves.unsigned_abs() as usize <= moves_left => Some(best.moves),
+            _ => None,
+        }
+    }
+}
+
+/// New rating of a player after a puzzle, with the Elo formula
+pub fn updated_rating(rating: i32, puzzle_rating: i32, solved: bool) -> i32 {
+    let expected = 1.0 / (1.0 + 10f32.powf((puzzle_rating as f32 - rating as f32) / 400.0));
+    let score = if solved { 1.0 } else { 0.0 };
+    rating.saturating_add((RATING_FACTOR * (score - expected)).round() as i32)
+}
+
+#[cfg(test)]
+#[path = "tests/test_puzzle.rs"]
+mod test_puzzle;
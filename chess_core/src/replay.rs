@@ -0,0 +1,98 @@
+use crate::core_struct::Piece;
+use crate::errors::MoveError;
+use crate::game::Game;
+use crate::moves::{self, Move};
+
+/// A game played again from its start one move at a time. The pieces received from the partner's board in bughouse are
+/// put back in the pockets after as many moves as in the game, which taking the moves back and playing them again loses
+#[derive(Debug, Clone)]
+pub struct Replay {
+    game: Game,
+    moves: Vec<Move>,
+    received: Vec<(usize, Piece)>, // After how many moves, past the start
+}
+
+impl Replay {
+    /// Starts before the first move of the start, which must have no history
+    pub(crate) fn new(start: Game, moves: Vec<Move>, received: Vec<(usize, Piece)>) -> Result<Replay, MoveError> {
+        let mut replay = Replay { game: start, moves, received };
+        replay.receive()?;
+        Ok(replay)
+    }
+
+    /// The position reached so far
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn into_game(self) -> Game {
+        self.game
+    }
+
+    /// Number of moves played again
+    pub fn ply(&self) -> usize {
+        self.game.history().len()
+    }
+
+    /// The move to play next, None once every move is played
+    pub fn next_move(&self) -> Option<Move> {
+        self.moves.get(self.ply()).copied()
+    }
+
+    /// Plays the next move and receives the pieces that came after it. Returns None once every move is played
+    pub fn play_next(&mut self) -> Result<Option<Move>, MoveError> {
+        let Some(mv) = self.next_move() else {
+            return Ok(None);
+        };
+        match mv {
+            // The promotion still pending at the end of the game
+            Move::Normal { start, end, promotion: None }
+                if self.ply() + 1 == self.moves.len()
+                    && self.game.piece_at(start).is_some_and(|piece| moves::is_promotion(piece, end)) =>
+            {
+                self.game.play_move(start, end)?
+            }
+            _ => self.game.play(mv)?,
+        };
+        self.receive()?;
+        Ok(Some(mv))
+    }
+
+    /// Notation of the moves left to play, stops at the first one that can't be written like line_to_san
+    pub fn into_san(mut self) -> Vec<String> {
+        let mut line = Vec::new();
+        while let Some(mv) = self.next_move() {
+            match self.game.to_san(mv) {
+                Ok(san) => line.push(san),
+                Err(_) => break,
+            }
+            if self.play_next().is_err() {
+                break;
+            }
+        }
+        line
+    }
+
+    fn receive(&mut self) -> Result<(), MoveError> {
+        let ply = self.ply();
+        for &(_, piece) in self.received.iter().filter(|(after, _)| *after == ply) {
+            self.game.add_to_pocket(piece)?;
+        }
+        Ok(())
+    }
+}
+
+impl Game {
+    /// The game played again from its start, see Replay
+    pub fn replay(&self) -> Result<Replay, MoveError> {
+        let mut start = self.clone();
+        while start.undo().is_some() {}
+        // The pieces received before the first move are still in the pockets of the start
+        let received = self.received.iter().filter(|(ply, _)| *ply > 0).copied().collect();
+        Replay::new(start, self.history().to_vec(), received)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_replay.rs"]
+mod test_replay;
@@ -0,0 +1,276 @@
+mod reviewer;
+
+use std::fmt;
+use std::sync::atomic::AtomicBool;
+
+use crate::core_struct::Color;
+use crate::errors::MoveError;
+use crate::game::Game;
+use crate::moves::Move;
+use crate::pgn;
+use crate::replay::Replay;
+use crate::rules::EndgameStatus;
+use crate::search::{self, LineScore};
+
+pub use reviewer::Reviewer;
+
+/// Scores are capped so that a mate counts as a won position instead of an endless loss
+const MAX_CENTIPAWNS: i32 = 1000;
+
+/// Centipawn loss from which a move is an inaccuracy, a mistake and a blunder
+const INACCURACY: i32 = 50;
+const MISTAKE: i32 = 100;
+const BLUNDER: i32 = 300;
+
+/// How bad a move is compared to the best move of the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    fn from_loss(loss: i32) -> Option<Judgement> {
+        match loss {
+            loss if loss >= BLUNDER => Some(Judgement::Blunder),
+            loss if loss >= MISTAKE => Some(Judgement::Mistake),
+            loss if loss >= INACCURACY => Some(Judgement::Inaccuracy),
+            _ => None,
+        }
+    }
+
+    /// Numeric annotation glyph of the judgement in PGN: $6 is ?!, $2 is ? and $4 is ??
+    pub fn nag(&self) -> u8 {
+        match self {
+            Judgement::Inaccuracy => 6,
+            Judgement::Mistake => 2,
+            Judgement::Blunder => 4,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Judgement::Inaccuracy => "?!",
+            Judgement::Mistake => "?",
+            Judgement::Blunder => "??",
+        }
+    }
+}
+
+impl fmt::Display for Judgement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Judgement::Inaccuracy => write!(f, "Inaccuracy"),
+            Judgement::Mistake => write!(f, "Mistake"),
+            Judgement::Blunder => write!(f, "Blunder"),
+        }
+    }
+}
+
+/// A move of the game, with the evaluations before and after it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveReview {
+    pub mv: Move,
+    pub san: String,
+    pub color: Color,
+    /// Centipawns from the point of view of white, capped for mates
+    pub eval_before: i32,
+    pub eval_after: i32,
+    /// Centipawns the move gave away compared to the best move
+    pub loss: i32,
+    pub judgement: Option<Judgement>,
+    /// The best line of the position, when the move played is not its first move
+    pub best_line: Option<search::Line>,
+    /// Between 0 and 100
+    pub accuracy: f32,
+}
+
+/// Why a game was not reviewed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewError {
+    /// The search was stopped before every move was reviewed
+    Stopped,
+    /// A move of the game could not be played again from its start
+    Move(MoveError),
+}
+
+impl fmt::Display for ReviewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewError::Stopped => write!(f, "The review was stopped"),
+            ReviewError::Move(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ReviewError {}
+
+impl From<MoveError> for ReviewError {
+    fn from(e: MoveError) -> ReviewError {
+        ReviewError::Move(e)
+    }
+}
+
+/// Every move of a game, judged by the search
+#[derive(Debug, Clone)]
+pub struct Review {
+    start: Replay, // Before the first move
+    result: EndgameStatus,
+    pub moves: Vec<MoveReview>,
+}
+
+/// Reviews every move of the game, searching each position to the given depth
+pub fn review_game(game: &Game, depth: u32) -> Result<Review, ReviewError> {
+    review_game_with_progress(game, depth, &AtomicBool::new(false), |_, _| ())
+}
+
+/// Same as review_game, calling on_progress with the number of moves reviewed and to review.
+/// Fails with ReviewError::Stopped as soon as stop is set
+pub fn review_game_with_progress(
+    game: &Game,
+    depth: u32,
+    stop: &AtomicBool,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Review, ReviewError> {
+    let depth = depth.max(1);
+    let start = game.replay()?;
+
+    let total = game.history().len();
+    let mut replay = start.clone();
+    let mut moves = Vec::with_capacity(total);
+    while let Some(mv) = replay.next_move() {
+        on_progress(replay.ply(), total);
+        let position = replay.game();
+        let color = position.turn();
+        let san = position.to_san(mv)?;
+        let (eval_before, best_line) = evaluate(position, depth, stop).ok_or(ReviewError::Stopped)?;
+        replay.play_next()?;
+        // One ply less, so that the move is scored as the search of the position before scored it
+        let (eval_after, _) =
+            evaluate(replay.game(), depth.saturating_sub(1).max(1), stop).ok_or(ReviewError::Stopped)?;
+
+        let best_line = best_line.filter(|line| line.moves.first() != Some(&mv));
+        let eval_after = if best_line.is_some() { eval_after } else { eval_before };
+        let sign = if color == Color::White { 1 } else { -1 };
+        let loss = (sign * (eval_before - eval_after)).max(0);
+        moves.push(MoveReview {
+            mv,
+            san,
+            color,
+            eval_before,
+            eval_after,
+            loss,
+            judgement: Judgement::from_loss(loss),
+            best_line,
+            accuracy: move_accuracy(sign * eval_before, sign * eval_after),
+        });
+    }
+    on_progress(total, total);
+
+    Ok(Review { start, result: game.endgame_status(), moves })
+}
+
+/// Capped evaluation of the position from the point of view of white, and its best line
+fn evaluate(game: &Game, depth: u32, stop: &AtomicBool) -> Option<(i32, Option<search::Line>)> {
+    let mut best = None;
+    search::analyze(game, depth, 1, stop, |lines| best = lines.first().cloned());
+
+    match best {
+        Some(line) => Some((centipawns(line.score), Some(line))),
+        // No legal move: checkmate or stalemate
+        None if game.all_legal_moves().is_empty() => {
+            let score = match (game.is_in_check(), game.turn()) {
+                (false, _) => 0,
+                (true, Color::White) => -MAX_CENTIPAWNS,
+                (true, Color::Black) => MAX_CENTIPAWNS,
+            };
+            Some((score, None))
+        }
+        None => None, // Stopped
+    }
+}
+
+fn centipawns(score: LineScore) -> i32 {
+    match score {
+        LineScore::Centipawns(score) => score.clamp(-MAX_CENTIPAWNS, MAX_CENTIPAWNS),
+        LineScore::Mate(moves) if moves >= 0 => MAX_CENTIPAWNS,
+        LineScore::Mate(_) => -MAX_CENTIPAWNS,
+    }
+}
+
+/// Chances to win between 0 and 100 for a score of the player
+fn winning_chances(centipawns: i32) -> f32 {
+    50.0 + 50.0 * (2.0 / (1.0 + (-0.00368208 * centipawns as f32).exp()) - 1.0)
+}
+
+/// Accuracy of a move from the winning chances it gave away, scores from the point of view of the player
+fn move_accuracy(before: i32, after: i32) -> f32 {
+    let lost = (winning_chances(before) - winning_chances(after)).max(0.0);
+    (103.1668 * (-0.04354 * lost).exp() - 3.1669).clamp(0.0, 100.0)
+}
+
+impl Review {
+    /// Mean accuracy of the moves of a player between 0 and 100, None if they did not play
+    pub fn accuracy(&self, color: Color) -> Option<f32> {
+        let accuracies: Vec<f32> = self.moves.iter().filter(|mv| mv.color == color).map(|mv| mv.accuracy).collect();
+        if accuracies.is_empty() {
+            return None;
+        }
+        Some(accuracies.iter().sum::<f32>() / accuracies.len() as f32)
+    }
+
+    /// Number of moves of the player with the judgement
+    pub fn count(&self, color: Color, judgement: Judgement) -> usize {
+        self.moves.iter().filter(|mv| mv.color == color && mv.judgement == Some(judgement)).count()
+    }
+
+    /// The game after the given number of moves
+    pub fn position(&self, ply: usize) -> Result<Game, MoveError> {
+        let mut replay = self.start.clone();
+        while replay.ply() < ply && replay.play_next()?.is_some() {}
+        Ok(replay.into_game())
+    }
+
+    /// The game in PGN with the judgements of the moves and the best alternatives in comments
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = pgn::write_tags(self.start.game(), pgn::result_text(self.result), &[]);
+        for (tag, color) in [("WhiteAccuracy", Color::White), ("BlackAccuracy", Color::Black)] {
+            if let Some(accuracy) = self.accuracy(color) {
+                pgn.push_str(&format!("[{} \"{:.1}\"]\n", tag, accuracy));
+            }
+        }
+        pgn.push('\n');
+
+        let mut tokens = Vec::new();
+        let mut number = self.start.game().fullmove_number();
+        let mut after_comment = true;
+        for review in &self.moves {
+            // The number stays on the line of its move
+            match review.color {
+                Color::White => tokens.push(format!("{}. {}", number, review.san)),
+                Color::Black if after_comment => tokens.push(format!("{}... {}", number, review.san)),
+                Color::Black => tokens.push(review.san.clone()),
+            }
+            after_comment = false;
+
+            if let Some(judgement) = review.judgement {
+                tokens.push(format!("${}", judgement.nag()));
+                if let Some(best) = review.best_line.as_ref().and_then(|line| line.san.first()) {
+                    tokens.push(format!("{{{}. {} was best.}}", judgement, best));
+                    after_comment = true;
+                }
+            }
+            if review.color == Color::Black {
+                number = number.saturating_add(1);
+            }
+        }
+        tokens.push(pgn::result_text(self.result).to_string());
+        pgn.push_str(&pgn::wrap_movetext(tokens));
+        pgn
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/test_review.rs"]
+mod test_review;
@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use crate::game::Game;
+
+use super::{Review, ReviewError};
+
+enum Message {
+    Progress(usize, usize),
+    Done(Result<Box<Review>, ReviewError>),
+}
+
+/// Reviews a game on a background thread, the search stops when it is dropped
+pub struct Reviewer {
+    stop: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<Message>,
+    progress: (usize, usize),
+    review: Option<Result<Review, ReviewError>>,
+}
+
+impl Reviewer {
+    /// Starts the review of a copy of the game, searching each position to the given depth
+    pub fn start(game: &Game, depth: u32) -> Reviewer {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let game = game.clone();
+        let thread_stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            let review = super::review_game_with_progress(&game, depth, &thread_stop, |done, total| {
+                let _ = sender.send(Message::Progress(done, total));
+            });
+            // Nobody waits for a stopped review
+            if !matches!(review, Err(ReviewError::Stopped)) {
+                let _ = sender.send(Message::Done(review.map(Box::new)));
+            }
+        });
+
+        Reviewer { stop, receiver, progress: (0, 1), review: None }
+    }
+
+    /// Moves reviewed and to review so far, never blocks
+    pub fn progress(&mut self) -> (usize, usize) {
+        self.receive();
+        self.progress
+    }
+
+    /// The review once every position is searched, or why the game could not be reviewed, never blocks
+    pub fn review(&mut self) -> Option<Result<&Review, ReviewError>> {
+        self.receive();
+        self.review.as_ref().map(|review| review.as_ref().map_err(|&e| e))
+    }
+
+    fn receive(&mut self) {
+        while let Ok(message) = self.receiver.try_recv() {
+            match message {
+                Message::Progress(done, total) => self.progress = (done, total),
+                Message::Done(review) => self.review = Some(review.map(|review| *review)),
+            }
+        }
+    }
+}
+
+impl Drop for Reviewer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -1,91 +1,107 @@
 use serde::{Deserialize, Serialize};
-use crate::core_struct::{Board, Color, Piece};
+use crate::core_struct::{Board, Color, Grid, Piece, PieceKind};
+use crate::errors::MoveError;
 use crate::game::GameState;
 
-impl Board {
-    fn can_pawn_take_king(
-        &self,
-        start: (usize, usize),
-        end: (usize, usize),
-        color: Color,
-    ) -> bool {
-        // We only have to check normal take, a pawn can't en passant the king
-        if color == Color::White {
-            return start.0 + 1 == end.0 && (start.1 == end.1 + 1 || start.1 + 1 == end.1);
-        } else {
-            return start.0 == end.0 + 1 && (start.1 == end.1 + 1 || start.1 + 1 == end.1);
+const KNIGHT_JUMPS: [(isize, isize); 8] = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+const KING_STEPS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+const ROOK_DIRECTIONS: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP_DIRECTIONS: [(isize, isize); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// The square one step away in a direction, if it is on the board
+fn step((i, j): (usize, usize), (di, dj): (isize, isize)) -> Option<(usize, usize)> {
+    let i = i.checked_add_signed(di).filter(|&i| i < 8)?;
+    let j = j.checked_add_signed(dj).filter(|&j| j < 8)?;
+    Some((i, j))
+}
+
+/// The pieces that slide along a direction: rooks and queens along ranks and files, bishops and queens along diagonals
+fn slides_along(piece: Piece, (di, dj): (isize, isize)) -> bool {
+    match piece.kind() {
+        PieceKind::Queen => true,
+        PieceKind::Rook => di == 0 || dj == 0,
+        PieceKind::Bishop => di != 0 && dj != 0,
+        _ => false,
+    }
+}
+
+/// Squares of the pieces of that color attacking a square of the grid, whatever stands on it
+pub(crate) fn attackers(grid: &Grid, square: (usize, usize), color: Color) -> Vec<(usize, usize)> {
+    let mut attackers = Vec::new();
+    let holds = |(i, j): (usize, usize), kind: PieceKind| grid[i][j] == Some(Piece::new(kind, color));
+
+    // A pawn takes diagonally forward, so it stands diagonally behind the square from its side
+    let behind = if color == Color::White { -1 } else { 1 };
+    for side in [-1, 1] {
+        attackers.extend(step(square, (behind, side)).filter(|&from| holds(from, PieceKind::Pawn)));
+    }
+    for jump in KNIGHT_JUMPS {
+        attackers.extend(step(square, jump).filter(|&from| holds(from, PieceKind::Knight)));
+    }
+    for direction in KING_STEPS {
+        attackers.extend(step(square, direction).filter(|&from| holds(from, PieceKind::King)));
+    }
+    for direction in ROOK_DIRECTIONS.into_iter().chain(BISHOP_DIRECTIONS) {
+        let mut current = square;
+        while let Some(next) = step(current, direction) {
+            current = next;
+            if let Some(piece) = grid[next.0][next.1] {
+                if piece.color() == color && slides_along(piece, direction) {
+                    attackers.push(next);
+                }
+                break;
+            }
         }
     }
+    attackers
+}
 
-    fn can_king_take_king(&self, start: (usize, usize), end: (usize, usize)) -> bool {
-        // Check if the move is only one square away, we don't need to check for castling because you can't take by castling
-        let x_diff = (start.0 as i8 - end.0 as i8).abs();
-        let y_diff = (start.1 as i8 - end.1 as i8).abs();
-        return x_diff <= 1 && y_diff <= 1;
+/// Whether the king of that color is attacked on the grid, an error unless there is exactly one
+pub(crate) fn is_check(grid: &Grid, color: Color) -> Result<bool, MoveError> {
+    let mut kings = (0..8).flat_map(|i| (0..8).map(move |j| (i, j))).filter(|&(i, j)| grid[i][j] == Some(Piece::King(color)));
+    match (kings.next(), kings.next()) {
+        (Some(king), None) => Ok(!attackers(grid, king, color.opposite()).is_empty()),
+        _ => Err(MoveError::KingNotFound(color)),
     }
+}
 
-    pub(super) fn is_king_in_check(&self, color: Color) -> Result<bool, &'static str> {
-        let king_positions = self
-            .pieces
-            .get(&(Piece::King(color)))
-            .ok_or("King not found")?;
-        if king_positions.len() != 1 {
-            return Err("Multiple or no kings found");
-        }
-        let king_pos = *king_positions.iter().next().unwrap(); // We know there's only one king so this is safe
-
-        for (piece, positions) in &self.pieces {
-            for pos in positions {
-                if piece.color() != color {
-                    match piece {
-                        Piece::Pawn(_) => {
-                            if self.can_pawn_take_king(*pos, king_pos, piece.color()) {
-                                return Ok(true);
-                            }
-                        }
-
-                        Piece::Knight(_) => {
-                            if self.is_valid_knight_move(*pos, king_pos) {
-                                return Ok(true);
-                            }
-                        }
-
-                        Piece::Bishop(_) => {
-                            if self.is_valid_bishop_move(*pos, king_pos) {
-                                return Ok(true);
-                            }
-                        }
-
-                        Piece::Rook(_) => {
-                            if self.is_valid_rook_move(*pos, king_pos) {
-                                return Ok(true);
-                            }
-                        }
-
-                        Piece::Queen(_) => {
-                            if self.is_valid_queen_move(*pos, king_pos) {
-                                return Ok(true);
-                            }
-                        }
-
-                        Piece::King(_) => {
-                            if self.can_king_take_king(*pos, king_pos) {
-                                return Ok(true);
-                            }
-                        }
+impl Board {
+    pub(crate) fn is_king_in_check(&self, color: Color) -> Result<bool, MoveError> {
+        is_check(&self.grid, color)
+    }
+
+    /// The pieces of that color that can't leave the line between their king and an enemy rook, bishop or queen
+    pub(crate) fn pinned_pieces(&self, color: Color) -> Vec<(usize, usize)> {
+        let Some(&king) = self.pieces.get(&Piece::King(color)).and_then(|squares| squares.iter().next()) else {
+            return Vec::new();
+        };
+        let mut pinned = Vec::new();
+        for direction in ROOK_DIRECTIONS.into_iter().chain(BISHOP_DIRECTIONS) {
+            let mut shield = None;
+            let mut current = king;
+            while let Some(next) = step(current, direction) {
+                current = next;
+                let Some(piece) = self.grid[next.0][next.1] else {
+                    continue;
+                };
+                match shield {
+                    None if piece.color() == color => shield = Some(next),
+                    Some(square) if piece.color() != color && slides_along(piece, direction) => {
+                        pinned.push(square);
+                        break;
                     }
+                    _ => break,
                 }
             }
         }
-
-        return Ok(false);
+        pinned
     }
 
     /// See if the color king is in checkmate or
-    pub(crate) fn evaluate_endgame(&mut self, game_state: &GameState) -> EndgameStatus {
+    pub(crate) fn evaluate_endgame(&self, game_state: &GameState) -> EndgameStatus {
         let color = game_state.turn;
         if !self.has_legal_moves(game_state) {
-            if self.is_king_in_check(color).unwrap() {
+            if self.is_king_in_check(color) == Ok(true) {
                 return EndgameStatus::Win(color.opposite(), WinReason::Checkmate); // Checkmate
             }
             return EndgameStatus::Draw(DrawReason::Stalemate); // Stalemate
@@ -93,32 +109,31 @@ impl Board {
         EndgameStatus::Ongoing // The game can continue
     }
 
+    /// Neither side can ever checkmate: bare kings, a single minor piece, or only bishops all on squares of one color
     pub(crate) fn is_insufficient_material(&self) -> bool {
-        // It's a draw if each side has only a king, or a king and a knight, or a king and a bishop (i.e a king and a minor piece)
-        let mut white_minor_pieces = 0;
-        let mut black_minor_pieces = 0;
+        let mut minor_pieces = 0;
+        let mut knights = 0;
+        let mut bishop_square_colors = [false; 2];
 
         for i in 0..8 {
             for j in 0..8 {
-                if let Some(piece) = self.grid[i][j] {
-                    match piece {
-                        Piece::Knight(Color::White) | Piece::Bishop(Color::White) => {white_minor_pieces += 1},
-                        Piece::Knight(Color::Black) | Piece::Bishop(Color::Black) => {black_minor_pieces += 1},
-                        Piece::King(_) => continue,
-                        _ => return false,
+                match self.grid[i][j] {
+                    Some(Piece::King(_)) | None => {}
+                    Some(Piece::Knight(_)) => {
+                        minor_pieces += 1;
+                        knights += 1;
                     }
+                    Some(Piece::Bishop(_)) => {
+                        minor_pieces += 1;
+                        bishop_square_colors[(i + j) % 2] = true;
+                    }
+                    Some(_) => return false,
                 }
             }
         }
 
-        if white_minor_pieces <= 1 && black_minor_pieces <= 1 {
-            return true;
-        }
-        false
+        minor_pieces <= 1 || (knights == 0 && !(bishop_square_colors[0] && bishop_square_colors[1]))
     }
-
-
-
 }
 
 /// The status of the endgame. The color in the checkmate variant is the color that is checkmated and lost.
@@ -131,10 +146,7 @@ pub enum EndgameStatus {
 
 impl EndgameStatus {
     pub fn is_ongoing(&self) -> bool {
-        match self {
-            EndgameStatus::Ongoing => true,
-            _ => false,
-        }
+        matches!(self, EndgameStatus::Ongoing)
     }
 }
 
@@ -143,8 +155,12 @@ pub enum DrawReason {
     Agreement,
     Stalemate,
     InsufficientMaterial,
+    /// Claimed by a player, see Game::claim_draw
     ThreefoldRepetition,
+    /// Claimed by a player, see Game::claim_draw
     FiftyMoveRule,
+    FivefoldRepetition,
+    SeventyFiveMoveRule,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
@@ -1,6 +1,8 @@
-mod check_and_mate;
+pub(crate) mod check_and_mate;
 mod move_validation;
+mod setup_validation;
 
 // Re-export only the functions (or types) you want publicly accessible.
 pub use check_and_mate::EndgameStatus;
-pub use check_and_mate::{DrawReason, WinReason};
\ No newline at end of file
+pub use check_and_mate::{DrawReason, WinReason};
+pub use setup_validation::{Setup, SetupError};
@@ -1,6 +1,9 @@
 use crate::{
-    core_struct::{Color, Piece, Board},
+    core_struct::{Color, Piece, PieceKind, Board},
+    errors::MoveError,
     game::GameState,
+    rules::check_and_mate,
+    square::Square,
     utils::BiRange
 };
 
@@ -115,7 +118,7 @@ impl Board {
             
 
         for (x, y) in x_range.zip(y_range) {
-            if self.grid[x as usize][y as usize].is_some() {
+            if self.grid[x][y].is_some() {
                 return false;
             }
         }
@@ -155,7 +158,7 @@ impl Board {
         
     }
 
-    fn is_valid_king_move(&mut self, start: (usize, usize), end: (usize, usize), game_state: &GameState) -> bool {
+    fn is_valid_king_move(&self, start: (usize, usize), end: (usize, usize), game_state: &GameState) -> bool {
         // Check if the move is only one square away
         let x_diff = (start.0 as i8 - end.0 as i8).abs();
         let y_diff = (start.1 as i8 - end.1 as i8).abs();
@@ -168,65 +171,74 @@ impl Board {
         // As well as if the king doesn't move through check 
 
         let color = self.grid[start.0][start.1].unwrap().color();
-        if self.is_king_in_check(color).unwrap() {return false;}
+        if self.is_king_in_check(color) != Ok(false) {return false;}
         if color == Color::White && start == (0, 4) {
             if  end == (0, 6) && game_state.white_castle_king_side {
                 if self.grid[0][5].is_none() && self.grid[0][6].is_none() {
-                    let (taken_piece, promotion) = self.execute_move(start, (0,5));
-                    let is_check = self.is_king_in_check(color).unwrap();
-                    self.undo_move(start, (0, 5), taken_piece, promotion);
-                    return !is_check;
-                }
-            } else if end == (0, 2) && game_state.white_castle_queen_side {
-                if self.grid[0][3].is_none() && self.grid[0][2].is_none() && self.grid[0][1].is_none() { 
-                    let (taken_piece, promotion) = self.execute_move(start, (0,3));
-                    let is_check = self.is_king_in_check(color).unwrap();
-                    self.undo_move(start, (0, 3), taken_piece, promotion);
+                    let is_check = self.leaves_king_in_check(start, (0, 5)) != Ok(false);
                     return !is_check;
                 }
+            } else if end == (0, 2)
+                && game_state.white_castle_queen_side
+                && self.grid[0][3].is_none()
+                && self.grid[0][2].is_none()
+                && self.grid[0][1].is_none()
+            {
+                let is_check = self.leaves_king_in_check(start, (0, 3)) != Ok(false);
+                return !is_check;
             }
         }
         else if color == Color::Black && start == (7, 4) {
             if  end == (7, 6) && game_state.black_castle_king_side {
                 if self.grid[7][5].is_none() && self.grid[7][6].is_none() {
-                    let (taken_piece, promotion) = self.execute_move(start, (7,5));
-                    let is_check = self.is_king_in_check(color).unwrap();
-                    self.undo_move(start, (7, 5), taken_piece, promotion);
-                    return !is_check;
-                }
-            } else if end == (7, 2) && game_state.black_castle_queen_side {
-                if self.grid[7][3].is_none() && self.grid[7][2].is_none() && self.grid[7][1].is_none() { 
-                    let (taken_piece, promotion) = self.execute_move(start, (7,3));
-                    let is_check = self.is_king_in_check(color).unwrap();
-                    self.undo_move(start, (7, 3), taken_piece, promotion);
+                    let is_check = self.leaves_king_in_check(start, (7, 5)) != Ok(false);
                     return !is_check;
                 }
+            } else if end == (7, 2)
+                && game_state.black_castle_queen_side
+                && self.grid[7][3].is_none()
+                && self.grid[7][2].is_none()
+                && self.grid[7][1].is_none()
+            {
+                let is_check = self.leaves_king_in_check(start, (7, 3)) != Ok(false);
+                return !is_check;
             }
             
         }
         return false;
     }
 
-    pub(crate) fn is_valid_move(&mut self, start: (usize, usize), end: (usize, usize), game_state: &GameState) -> Result<(), &'static str> {
-        if start.0 > 7 || start.1 > 7  {
-            return Err("Start square out of bounds");
+    /// Whether the king of the moving piece is attacked once the move is played, tried on a copy of the grid
+    fn leaves_king_in_check(&self, start: (usize, usize), end: (usize, usize)) -> Result<bool, MoveError> {
+        let mut grid = self.grid;
+        let Some(piece) = grid[start.0][start.1].take() else {
+            return Err(MoveError::NoPiece(Square::from_coords(start)));
+        };
+        // A pawn going diagonally to an empty square takes en passant, a king going two squares castles
+        if piece.kind() == PieceKind::Pawn && start.1 != end.1 && grid[end.0][end.1].is_none() {
+            grid[start.0][end.1] = None;
         }
-        if end.0 > 7 || end.1 > 7 {
-            return Err("End square out of bounds");
+        if piece.kind() == PieceKind::King && start.1.abs_diff(end.1) == 2 {
+            let (rook_start, rook_end) = if end.1 == 6 { (7, 5) } else { (0, 3) };
+            grid[start.0][rook_end] = grid[start.0][rook_start].take();
         }
+        grid[end.0][end.1] = Some(piece);
+        check_and_mate::is_check(&grid, piece.color())
+    }
 
-        let piece = self.grid[start.0][start.1].ok_or("No piece at start square")?;
+    pub(crate) fn is_valid_move(&self, start: (usize, usize), end: (usize, usize), game_state: &GameState) -> Result<(), MoveError> {
+        let piece = self.grid[start.0][start.1].ok_or(MoveError::NoPiece(Square::from_coords(start)))?;
 
 
         // Check if the start square is occupied by a piece of the correct color
         if piece.color() != game_state.turn {
-            return Err("Start square occupied by piece of wrong color");
+            return Err(MoveError::WrongColor);
         }
 
         // Check if the end square is occupied by a piece of the same color
         if let Some(end_piece) = self.grid[end.0][end.1] {
             if end_piece.color() == piece.color() {
-                return Err("End square occupied by piece of same color");
+                return Err(MoveError::OwnPieceOnTarget);
             }
         }
 
@@ -258,20 +270,44 @@ impl Board {
         };
 
         if !is_valid_move {
-            return Err("Invalid move");
+            return Err(MoveError::IllegalPieceMove);
         }
 
-        let (taken_piece, promotion) =  self.execute_move(start, end); 
-        let is_check = self.is_king_in_check(game_state.turn);
-        self.undo_move(start, end, taken_piece, promotion);
-
-        if is_check? {
-            return Err("Move would put own king in check");
+        if self.leaves_king_in_check(start, end)? {
+            return Err(MoveError::LeavesKingInCheck);
         }
 
         Ok(())
         
     }
+
+    pub(crate) fn is_valid_drop(&self, piece: Piece, square: (usize, usize), game_state: &GameState) -> Result<(), MoveError> {
+        if piece.color() != game_state.turn {
+            return Err(MoveError::WrongColor);
+        }
+
+        if game_state.pocket(piece.color()).count(piece.kind()) == 0 {
+            return Err(MoveError::NotInPocket(piece.kind()));
+        }
+
+        if self.grid[square.0][square.1].is_some() {
+            return Err(MoveError::SquareOccupied(Square::from_coords(square)));
+        }
+
+        // Pawns can't be dropped on the first or last rank
+        if piece.kind() == PieceKind::Pawn && (square.0 == 0 || square.0 == 7) {
+            return Err(MoveError::PawnDropOnBackRank);
+        }
+
+        // A drop can't uncover a check, but it has to block one if the king is in check
+        let mut grid = self.grid;
+        grid[square.0][square.1] = Some(piece);
+        if check_and_mate::is_check(&grid, piece.color())? {
+            return Err(MoveError::LeavesKingInCheck);
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,166 @@
+use std::fmt;
+
+use crate::core_struct::{Board, Color, Piece};
+use crate::square::{File, Square};
+use crate::variant::{Pocket, Variant};
+
+/// A position to start a game from, e.g. built in a board editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setup {
+    pub grid: [[Option<Piece>; 8]; 8],
+    pub turn: Color,
+    pub white_castle_king_side: bool,
+    pub white_castle_queen_side: bool,
+    pub black_castle_king_side: bool,
+    pub black_castle_queen_side: bool,
+    pub en_passant: Option<File>, // File of the pawn that just moved two squares
+    pub halfmove_clock: u32, // Moves since the last capture or pawn move, for the fifty-move rule
+    pub fullmove_number: usize,
+    /// Only the drop variants read the pockets and the promoted pieces
+    pub variant: Variant,
+    pub white_pocket: Pocket,
+    pub black_pocket: Pocket,
+    pub promoted: Vec<Square>, // Squares of promoted pieces, which go back to a pocket as pawns
+}
+
+impl Default for Setup {
+    /// The starting position
+    fn default() -> Self {
+        Setup {
+            grid: Board::new().grid,
+            turn: Color::White,
+            white_castle_king_side: true,
+            white_castle_queen_side: true,
+            black_castle_king_side: true,
+            black_castle_queen_side: true,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            variant: Variant::Standard,
+            white_pocket: Pocket::default(),
+            black_pocket: Pocket::default(),
+            promoted: Vec::new(),
+        }
+    }
+}
+
+impl Setup {
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        let (i, j) = square.coords();
+        self.grid[i][j]
+    }
+
+    /// Puts a piece on a square, or empties it with None
+    pub fn set_piece(&mut self, square: Square, piece: Option<Piece>) {
+        let (i, j) = square.coords();
+        self.grid[i][j] = piece;
+    }
+
+    /// An empty board, white to move
+    pub fn empty() -> Setup {
+        Setup {
+            grid: [[None; 8]; 8],
+            turn: Color::White,
+            white_castle_king_side: false,
+            white_castle_queen_side: false,
+            black_castle_king_side: false,
+            black_castle_queen_side: false,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            variant: Variant::Standard,
+            white_pocket: Pocket::default(),
+            black_pocket: Pocket::default(),
+            promoted: Vec::new(),
+        }
+    }
+}
+
+/// Why a setup can't be played from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupError {
+    /// Each side needs exactly one king, the count found is given
+    KingCount(Color, usize),
+    PawnOnBackRank(Square),
+    /// The side that is not to move can't be in check, its king could be taken
+    OpponentInCheck,
+    /// Castling rights need the king and the rook on their starting squares
+    InvalidCastlingRights(Color),
+    /// En passant needs a pawn that just moved two squares on that file
+    InvalidEnPassant(File),
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetupError::KingCount(color, count) => write!(f, "{} has {} kings instead of one", color, count),
+            SetupError::PawnOnBackRank(square) => write!(f, "Pawn on the back rank at {}", square),
+            SetupError::OpponentInCheck => write!(f, "The side not to move is in check"),
+            SetupError::InvalidCastlingRights(color) => write!(f, "{} can't castle, the king or rook is not on its starting square", color),
+            SetupError::InvalidEnPassant(file) => write!(f, "No pawn can be taken en passant on the {} file", file),
+        }
+    }
+}
+
+impl std::error::Error for SetupError {}
+
+impl Board {
+    /// Check that the setup describes a position that can be reached in a game, the board must be built from its grid
+    pub(crate) fn validate_setup(&self, setup: &Setup) -> Result<(), SetupError> {
+        for color in [Color::White, Color::Black] {
+            let count = self.pieces.get(&Piece::King(color)).map_or(0, |positions| positions.len());
+            if count != 1 {
+                return Err(SetupError::KingCount(color, count));
+            }
+        }
+
+        for i in [0, 7] {
+            for j in 0..8 {
+                if let Some(Piece::Pawn(_)) = self.grid[i][j] {
+                    return Err(SetupError::PawnOnBackRank(Square::from_coords((i, j))));
+                }
+            }
+        }
+
+        // There is exactly one king of each color so this can't fail
+        if self.is_king_in_check(setup.turn.opposite()).unwrap() {
+            return Err(SetupError::OpponentInCheck);
+        }
+
+        let castling_rights = [
+            (Color::White, 0, setup.white_castle_king_side, 7),
+            (Color::White, 0, setup.white_castle_queen_side, 0),
+            (Color::Black, 7, setup.black_castle_king_side, 7),
+            (Color::Black, 7, setup.black_castle_queen_side, 0),
+        ];
+        for (color, rank, can_castle, rook_file) in castling_rights {
+            if can_castle
+                && (self.grid[rank][4] != Some(Piece::King(color))
+                    || self.grid[rank][rook_file] != Some(Piece::Rook(color)))
+            {
+                return Err(SetupError::InvalidCastlingRights(color));
+            }
+        }
+
+        if let Some(en_passant) = setup.en_passant {
+            let file = en_passant.index();
+            // The pawn of the side that just played moved from its second rank to its fourth rank
+            let (pawn_rank, skipped_rank, start_rank) = match setup.turn {
+                Color::White => (4, 5, 6),
+                Color::Black => (3, 2, 1),
+            };
+            if self.grid[pawn_rank][file] != Some(Piece::Pawn(setup.turn.opposite()))
+                || self.grid[skipped_rank][file].is_some()
+                || self.grid[start_rank][file].is_some()
+            {
+                return Err(SetupError::InvalidEnPassant(en_passant));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/test_setup_validation.rs"]
+mod test_setup_validation;
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use crate::core_struct::Color;
+use crate::game::Game;
+use crate::variant::Pocket;
+
+use super::{Line, MAX_DEPTH};
+
+/// Analyzes a position on a background thread until it is dropped.
+/// Create a new one whenever the position changes
+pub struct Analyzer {
+    position: PositionId,
+    stop: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<Vec<Line>>,
+    lines: Vec<Line>,
+}
+
+impl Analyzer {
+    /// Starts the analysis of a copy of the game, looking for the multi_pv best lines
+    pub fn start(game: &Game, multi_pv: usize) -> Analyzer {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let game = game.clone();
+        let position = position_id(&game);
+        let thread_stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            super::analyze(&game, MAX_DEPTH, multi_pv, &thread_stop, |lines| {
+                let _ = sender.send(lines.to_vec());
+            });
+        });
+
+        Analyzer { position, stop, receiver, lines: Vec::new() }
+    }
+
+    /// Whether the analysis is still about the current position of the game
+    pub fn is_analyzing(&self, game: &Game) -> bool {
+        self.position == position_id(game)
+    }
+
+    /// The lines of the deepest search finished so far, never blocks
+    pub fn lines(&mut self) -> &[Line] {
+        while let Ok(lines) = self.receiver.try_recv() {
+            self.lines = lines;
+        }
+        &self.lines
+    }
+}
+
+/// Number of moves played and the position, with the pockets that the Polyglot key leaves out
+type PositionId = (usize, u64, Pocket, Pocket);
+
+fn position_id(game: &Game) -> PositionId {
+    (game.history().len(), game.polyglot_key(), game.pocket(Color::White), game.pocket(Color::Black))
+}
+
+impl Drop for Analyzer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,246 @@
+mod analyzer;
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::core_struct::{Color, PieceKind};
+use crate::eval;
+use crate::game::Game;
+use crate::moves::Move;
+
+pub use analyzer::Analyzer;
+
+const MATE_SCORE: i32 = 100_000;
+const INFINITY: i32 = 1_000_000;
+const MAX_DEPTH: u32 = 64;
+
+/// Score of a line, from the point of view of white
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineScore {
+    Centipawns(i32),
+    /// Moves until mate, negative when black mates
+    Mate(i32),
+}
+
+impl LineScore {
+    /// Converts a score of the search, seen from the color to play
+    fn from_search(score: i32, turn: Color) -> LineScore {
+        let score = if turn == Color::White { score } else { -score };
+        if score.abs() > MATE_SCORE - MAX_DEPTH as i32 * 2 {
+            let plies = MATE_SCORE - score.abs();
+            LineScore::Mate(score.signum() * (plies + 1) / 2)
+        } else {
+            LineScore::Centipawns(score)
+        }
+    }
+}
+
+impl fmt::Display for LineScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineScore::Centipawns(score) => write!(f, "{:+.2}", *score as f32 / 100.0),
+            LineScore::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+/// One of the best lines found by the search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub moves: Vec<Move>,
+    pub san: Vec<String>,
+    pub score: LineScore,
+    pub depth: u32,
+}
+
+/// Searches the position with increasing depth, calling on_depth with the multi_pv best lines after each depth.
+/// Stops at max_depth, when a mate is found or as soon as stop is set
+pub fn analyze(game: &Game, max_depth: u32, multi_pv: usize, stop: &AtomicBool, mut on_depth: impl FnMut(&[Line])) {
+    let mut search = Search { game: game.clone(), stop, nodes: 0 };
+    let mut lines: Vec<Line> = Vec::new();
+
+    for depth in 1..=max_depth.min(MAX_DEPTH) {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        let previous: Vec<Move> = lines.iter().map(|line| line.moves[0]).collect();
+        let results = match search.root(depth, multi_pv.max(1), &previous) {
+            Some(results) => results,
+            None => return, // Stopped in the middle of this depth
+        };
+        if results.is_empty() {
+            return; // Checkmate, stalemate or game over
+        }
+
+        lines = results
+            .into_iter()
+            .map(|(score, moves)| Line {
+                san: game.line_to_san(&moves),
+                score: LineScore::from_search(score, game.turn()),
+                moves,
+                depth,
+            })
+            .collect();
+        on_depth(&lines);
+
+        if matches!(lines[0].score, LineScore::Mate(_)) {
+            return;
+        }
+    }
+}
+
+/// The best line of the position, searched to the given depth
+pub fn best_line(game: &Game, depth: u32) -> Option<Line> {
+    let mut best = None;
+    analyze(game, depth, 1, &AtomicBool::new(false), |lines| best = lines.first().cloned());
+    best
+}
+
+struct Search<'a> {
+    game: Game,
+    stop: &'a AtomicBool,
+    nodes: u64,
+}
+
+impl Search<'_> {
+    /// Score and line of each of the best moves at the root, None if the search was stopped
+    fn root(&mut self, depth: u32, multi_pv: usize, previous: &[Move]) -> Option<Vec<(i32, Vec<Move>)>> {
+        let mut moves = self.ordered_moves();
+        // The best moves of the previous depth are searched first
+        moves.sort_by_key(|mv| previous.iter().position(|best| best == mv).unwrap_or(previous.len()));
+
+        let mut best: Vec<(i32, Vec<Move>)> = Vec::new();
+        for mv in moves {
+            // A move only has to be searched precisely if it can enter the best lines
+            let threshold = if best.len() < multi_pv { -INFINITY } else { best[best.len() - 1].0 };
+
+            self.game.play(mv).ok()?;
+            let result = self.negamax(depth - 1, 1, -INFINITY, -threshold);
+            self.game.undo();
+            let (score, mut line) = result?;
+            let score = -score;
+
+            if best.len() < multi_pv || score > threshold {
+                line.insert(0, mv);
+                let index = best.partition_point(|(other, _)| *other >= score);
+                best.insert(index, (score, line));
+                best.truncate(multi_pv);
+            }
+        }
+        Some(best)
+    }
+
+    fn negamax(&mut self, depth: u32, ply: i32, mut alpha: i32, beta: i32) -> Option<(i32, Vec<Move>)> {
+        self.count_node()?;
+        if self.game.is_draw_by_rule() {
+            return Some((0, Vec::new()));
+        }
+
+        let moves = self.ordered_moves();
+        if moves.is_empty() {
+            let score = if self.game.is_in_check() { -MATE_SCORE + ply } else { 0 };
+            return Some((score, Vec::new()));
+        }
+        if depth == 0 {
+            return Some((self.quiescence(alpha, beta)?, Vec::new()));
+        }
+
+        let mut best_line = Vec::new();
+        for mv in moves {
+            self.game.play(mv).ok()?;
+            let result = self.negamax(depth - 1, ply + 1, -beta, -alpha);
+            self.game.undo();
+            let (score, mut line) = result?;
+            let score = -score;
+
+            if score >= beta {
+                return Some((beta, Vec::new()));
+            }
+            if score > alpha {
+                alpha = score;
+                line.insert(0, mv);
+                best_line = line;
+            }
+        }
+        Some((alpha, best_line))
+    }
+
+    /// Only captures and promotions are searched, so that the evaluation is not done in the middle of an exchange
+    fn quiescence(&mut self, mut alpha: i32, beta: i32) -> Option<i32> {
+        self.count_node()?;
+
+        let stand_pat = eval::evaluate(&self.game).relative_score(self.game.turn());
+        if stand_pat >= beta {
+            return Some(beta);
+        }
+        alpha = alpha.max(stand_pat);
+
+        let captures: Vec<Move> = self.ordered_moves().into_iter().filter(|mv| self.capture_value(*mv).is_some()).collect();
+        for mv in captures {
+            self.game.play(mv).ok()?;
+            let result = self.quiescence(-beta, -alpha);
+            self.game.undo();
+            let score = -result?;
+
+            if score >= beta {
+                return Some(beta);
+            }
+            alpha = alpha.max(score);
+        }
+        Some(alpha)
+    }
+
+    /// Returns None once the search has to stop
+    fn count_node(&mut self) -> Option<()> {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(1024) && self.stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(())
+    }
+
+    /// Legal moves with the most promising captures and the promotions first
+    fn ordered_moves(&mut self) -> Vec<Move> {
+        let mut moves = self.game.all_legal_moves();
+        moves.sort_by_key(|mv| -self.capture_value(*mv).unwrap_or(-INFINITY));
+        moves
+    }
+
+    /// Most valuable victim first, then least valuable attacker. None for quiet moves
+    fn capture_value(&self, mv: Move) -> Option<i32> {
+        let (start, end, promotion) = match mv {
+            Move::Normal { start, end, promotion } => (start, end, promotion),
+            Move::Drop { .. } => return None,
+        };
+        let attacker = self.game.piece_at(start)?.kind();
+        let victim = match self.game.piece_at(end) {
+            Some(piece) => Some(piece.kind()),
+            None if attacker == PieceKind::Pawn && start.file() != end.file() => Some(PieceKind::Pawn), // En passant
+            None => None,
+        };
+
+        let promotion_value = match promotion {
+            Some(crate::PromotionPiece::Queen) => 50,
+            Some(_) => return None, // Under promotions are quiet moves
+            None => 0,
+        };
+        if victim.is_none() && promotion.is_none() {
+            return None;
+        }
+        Some(promotion_value + victim.map_or(0, |kind| 10 * piece_value(kind)) - piece_value(attacker))
+    }
+}
+
+fn piece_value(kind: PieceKind) -> i32 {
+    match kind {
+        PieceKind::Pawn => 1,
+        PieceKind::Knight | PieceKind::Bishop => 3,
+        PieceKind::Rook => 5,
+        PieceKind::Queen => 9,
+        PieceKind::King => 10,
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/test_search.rs"]
+mod test_search;
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core_struct::Piece;
+use crate::game::Game;
+use crate::moves::Move;
+use crate::replay::Replay;
+use crate::rules::{EndgameStatus, Setup};
+use crate::variant::Variant;
+
+/// Version of the serialized form of a game, written in every snapshot and increased whenever that form changes
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// How a game is serialized. Only what was played is kept, and the game is played again from it when it is read,
+/// which rebuilds the repetitions, the pockets and the moves to take back. In JSON:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "variant": "Crazyhouse",
+///   "fen": "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+///   "moves": ["e2e4", "d7d5", "e4d5", "d8d5", "P@e4"],
+///   "received": [],
+///   "result": "Ongoing"
+/// }
+/// ```
+///
+/// - `fen`: the position the game started from, with its pockets in the drop variants
+/// - `moves`: the moves in coordinate notation. While a promotion is pending, the last one has no promotion piece yet
+/// - `received`: the pieces put in a pocket from the partner's board in bughouse, after how many moves, e.g. `[[3, {"Knight": "Black"}]]`
+/// - `result`: the end of the game, which also keeps the endings decided off the board like a resignation
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GameSnapshot {
+    version: u32,
+    variant: Variant,
+    fen: String,
+    moves: Vec<String>,
+    #[serde(default)]
+    received: Vec<(usize, Piece)>,
+    result: EndgameStatus,
+}
+
+impl From<Game> for GameSnapshot {
+    fn from(mut game: Game) -> GameSnapshot {
+        let moves = game.history().iter().map(Move::to_string).collect();
+        // The pieces received before the first move are already in the pockets of the starting position
+        let received = game.received.iter().filter(|(ply, _)| *ply > 0).copied().collect();
+        let (variant, result) = (game.variant(), game.endgame_status());
+        while game.undo().is_some() {}
+        GameSnapshot { version: SNAPSHOT_VERSION, variant, fen: game.to_fen(), moves, received, result }
+    }
+}
+
+impl TryFrom<GameSnapshot> for Game {
+    type Error = String;
+
+    /// Plays the game again, so that a snapshot received from elsewhere goes through the same checks as the moves of a player
+    fn try_from(snapshot: GameSnapshot) -> Result<Game, String> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(format!("Unsupported snapshot version {}", snapshot.version));
+        }
+        let moves = snapshot
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(index, text)| text.parse().map_err(|e| format!("Move {} ({}) of the snapshot: {}", index + 1, text, e)))
+            .collect::<Result<Vec<Move>, String>>()?;
+        if let Some((ply, _)) = snapshot.received.iter().find(|(ply, _)| *ply > moves.len()) {
+            return Err(format!("A piece is received after move {}, past the end of the game", ply));
+        }
+
+        let mut setup = Setup::from_fen(&snapshot.fen).map_err(|e| format!("Invalid snapshot FEN: {}", e))?;
+        setup.variant = snapshot.variant;
+        let game = Game::from_setup(&setup).map_err(|e| format!("Invalid snapshot position: {}", e))?;
+        let mut replay =
+            Replay::new(game, moves, snapshot.received).map_err(|e| format!("Piece received before the first move: {}", e))?;
+        while let Some(mv) = replay.next_move() {
+            let ply = replay.ply();
+            replay.play_next().map_err(|e| format!("Move {} ({}) of the snapshot: {}", ply + 1, mv, e))?;
+        }
+
+        let mut game = replay.into_game();
+        if !snapshot.result.is_ongoing() {
+            game.end_game(snapshot.result);
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_snapshot.rs"]
+mod test_snapshot;
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core_struct::Color;
+use crate::errors::ParseError;
+
+/// A column of the board, from a on the queen side to h on the king side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum File {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+}
+
+/// A row of the board, from white's side to black's side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Rank {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+    Eighth,
+}
+
+impl File {
+    pub const ALL: [File; 8] = [File::A, File::B, File::C, File::D, File::E, File::F, File::G, File::H];
+
+    /// File from its index, 0 for a, None past h
+    pub fn new(index: usize) -> Option<File> {
+        File::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// File from its letter, a to h
+    pub fn from_char(c: char) -> Option<File> {
+        match c {
+            'a'..='h' => File::new(c as usize - 'a' as usize),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        (b'a' + self as u8) as char
+    }
+
+    /// The file that many files to the right, None off the board
+    pub fn offset(self, delta: i32) -> Option<File> {
+        File::new(self.index().checked_add_signed(delta as isize)?)
+    }
+}
+
+impl Rank {
+    pub const ALL: [Rank; 8] = [Rank::First, Rank::Second, Rank::Third, Rank::Fourth, Rank::Fifth, Rank::Sixth, Rank::Seventh, Rank::Eighth];
+
+    /// Rank from its index, 0 for the first rank, None past the eighth
+    pub fn new(index: usize) -> Option<Rank> {
+        Rank::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Rank from its digit, 1 to 8
+    pub fn from_char(c: char) -> Option<Rank> {
+        match c {
+            '1'..='8' => Rank::new(c as usize - '1' as usize),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        (b'1' + self as u8) as char
+    }
+
+    /// The rank that many ranks up towards black's side, None off the board
+    pub fn offset(self, delta: i32) -> Option<Rank> {
+        Rank::new(self.index().checked_add_signed(delta as isize)?)
+    }
+
+    /// The first rank of that color, where its pieces start
+    pub fn back_rank(color: Color) -> Rank {
+        match color {
+            Color::White => Rank::First,
+            Color::Black => Rank::Eighth,
+        }
+    }
+}
+
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// A square of the board, written in algebraic form like e4, also in the serialized games and network messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Square {
+    // Ranks first so that squares are ordered a1, b1, ... h8
+    rank: Rank,
+    file: File,
+}
+
+macro_rules! squares {
+    ($($name:ident = $file:ident $rank:ident),* $(,)?) => {
+        impl Square {
+            $(pub const $name: Square = Square { rank: Rank::$rank, file: File::$file };)*
+        }
+    };
+}
+
+squares! {
+    A1 = A First, B1 = B First, C1 = C First, D1 = D First, E1 = E First, F1 = F First, G1 = G First, H1 = H First,
+    A2 = A Second, B2 = B Second, C2 = C Second, D2 = D Second, E2 = E Second, F2 = F Second, G2 = G Second, H2 = H Second,
+    A3 = A Third, B3 = B Third, C3 = C Third, D3 = D Third, E3 = E Third, F3 = F Third, G3 = G Third, H3 = H Third,
+    A4 = A Fourth, B4 = B Fourth, C4 = C Fourth, D4 = D Fourth, E4 = E Fourth, F4 = F Fourth, G4 = G Fourth, H4 = H Fourth,
+    A5 = A Fifth, B5 = B Fifth, C5 = C Fifth, D5 = D Fifth, E5 = E Fifth, F5 = F Fifth, G5 = G Fifth, H5 = H Fifth,
+    A6 = A Sixth, B6 = B Sixth, C6 = C Sixth, D6 = D Sixth, E6 = E Sixth, F6 = F Sixth, G6 = G Sixth, H6 = H Sixth,
+    A7 = A Seventh, B7 = B Seventh, C7 = C Seventh, D7 = D Seventh, E7 = E Seventh, F7 = F Seventh, G7 = G Seventh, H7 = H Seventh,
+    A8 = A Eighth, B8 = B Eighth, C8 = C Eighth, D8 = D Eighth, E8 = E Eighth, F8 = F Eighth, G8 = G Eighth, H8 = H Eighth,
+}
+
+impl Square {
+    pub fn new(file: File, rank: Rank) -> Square {
+        Square { rank, file }
+    }
+
+    pub fn file(self) -> File {
+        self.file
+    }
+
+    pub fn rank(self) -> Rank {
+        self.rank
+    }
+
+    /// Every square, a1 to h1 then up to h8
+    pub fn all() -> impl Iterator<Item = Square> {
+        Rank::ALL.into_iter().flat_map(|rank| File::ALL.into_iter().map(move |file| Square::new(file, rank)))
+    }
+
+    /// The square that many files to the right and ranks up, None off the board
+    pub fn offset(self, file_delta: i32, rank_delta: i32) -> Option<Square> {
+        Some(Square::new(self.file.offset(file_delta)?, self.rank.offset(rank_delta)?))
+    }
+
+    /// Number of king moves between two squares
+    pub fn distance(self, other: Square) -> usize {
+        self.file.index().abs_diff(other.file.index()).max(self.rank.index().abs_diff(other.rank.index()))
+    }
+
+    /// Color of the square on the board, a1 is dark
+    pub fn color(self) -> Color {
+        if (self.file.index() + self.rank.index()).is_multiple_of(2) {
+            Color::Black
+        } else {
+            Color::White
+        }
+    }
+
+    /// Index of the square in the grid of the board, which is stored rank first
+    pub(crate) fn coords(self) -> (usize, usize) {
+        (self.rank.index(), self.file.index())
+    }
+
+    /// Square at an index of the grid, which the board always keeps on the board
+    pub(crate) fn from_coords((rank, file): (usize, usize)) -> Square {
+        Square::new(File::ALL[file], Rank::ALL[rank])
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file, self.rank)
+    }
+}
+
+/// Reads a square in algebraic form, e.g. e4
+impl FromStr for Square {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Square, ParseError> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(File::from_char), chars.next().and_then(Rank::from_char), chars.next()) {
+            (Some(file), Some(rank), None) => Ok(Square::new(file, rank)),
+            _ => Err(ParseError::InvalidSquare),
+        }
+    }
+}
+
+impl From<Square> for String {
+    fn from(square: Square) -> String {
+        square.to_string()
+    }
+}
+
+impl TryFrom<String> for Square {
+    type Error = ParseError;
+
+    fn try_from(name: String) -> Result<Square, ParseError> {
+        name.parse()
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_square.rs"]
+mod test_square;
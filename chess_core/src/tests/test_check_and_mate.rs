@@ -190,7 +190,7 @@ fn test_evaluate_endgame() {
         (Piece::King(Color::White), HashSet::from([(5, 3)])),    
     ]);
 
-    let mut board = Board { grid, pieces };
+    let board = Board { grid, pieces };
 
     assert_eq!(
         board.evaluate_endgame(&game_state),
@@ -209,7 +209,7 @@ fn test_evaluate_endgame() {
         (Piece::King(Color::White), HashSet::from([(5, 3)])),    
     ]);
 
-    let mut board = Board { grid, pieces };
+    let board = Board { grid, pieces };
 
     assert_eq!(
         board.evaluate_endgame(&game_state),
@@ -230,7 +230,7 @@ fn test_evaluate_endgame() {
         (Piece::King(Color::White), HashSet::from([(6, 3)])),
     ]);
 
-    let mut board = Board { grid, pieces };
+    let board = Board { grid, pieces };
 
     assert_eq!(
         board.evaluate_endgame(&game_state),
@@ -251,7 +251,7 @@ fn test_evaluate_endgame() {
         (Piece::King(Color::White), HashSet::from([(6, 3)])),
     ]);
 
-    let mut board = Board { grid, pieces };
+    let board = Board { grid, pieces };
 
     assert_eq!(
         board.evaluate_endgame(&game_state),
@@ -274,7 +274,7 @@ fn test_evaluate_endgame() {
         (Piece::King(Color::White), HashSet::from([(0, 4)])),
     ]);
 
-    let mut board = Board { grid, pieces };
+    let board = Board { grid, pieces };
 
     assert_eq!(
         board.evaluate_endgame(&game_state),
@@ -298,7 +298,7 @@ fn test_evaluate_endgame() {
         (Piece::Rook(Color::White), HashSet::from([(0, 1)])),
     ]);
 
-    let mut board = Board { grid, pieces };
+    let board = Board { grid, pieces };
 
     assert_eq!(
         board.evaluate_endgame(&game_state),
@@ -0,0 +1,94 @@
+use std::sync::mpsc::Receiver;
+
+use super::{GameController, GameEvent};
+use crate::{Color, DrawReason, EndgameStatus, Game, Move, MoveError, Piece, PromotionPiece, Setup, Square, WinReason};
+
+fn controller_from_fen(fen: &str) -> GameController {
+    GameController::new(Game::from_setup(&Setup::from_fen(fen).unwrap()).unwrap())
+}
+
+fn events(receiver: &Receiver<GameEvent>) -> Vec<GameEvent> {
+    receiver.try_iter().collect()
+}
+
+#[test]
+fn test_castling_and_en_passant_events() {
+    let mut controller = controller_from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    let receiver = controller.subscribe();
+    controller.play_move(Square::E1, Square::G1).unwrap();
+    controller.play_move(Square::E8, Square::C8).unwrap();
+    assert_eq!(events(&receiver), vec![
+        GameEvent::MovePlayed(Move::new(Square::E1, Square::G1), Color::White),
+        GameEvent::Castle { color: Color::White, rook_start: Square::H1, rook_end: Square::F1 },
+        GameEvent::MovePlayed(Move::new(Square::E8, Square::C8), Color::Black),
+        GameEvent::Castle { color: Color::Black, rook_start: Square::A8, rook_end: Square::D8 },
+    ]);
+
+    let mut controller = controller_from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+    let receiver = controller.subscribe();
+    controller.play_move(Square::E5, Square::D6).unwrap();
+    assert_eq!(events(&receiver), vec![
+        GameEvent::MovePlayed(Move::new(Square::E5, Square::D6), Color::White),
+        GameEvent::Capture(Piece::Pawn(Color::Black), Square::D5),
+    ]);
+}
+
+#[test]
+fn test_promotion_events() {
+    let mut controller = controller_from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+    let receiver = controller.subscribe();
+    controller.play_move(Square::A7, Square::B8).unwrap();
+    assert_eq!(events(&receiver), vec![
+        GameEvent::MovePlayed(Move::new(Square::A7, Square::B8), Color::White),
+        GameEvent::Capture(Piece::Rook(Color::Black), Square::B8),
+        GameEvent::PromotionPending(Square::B8),
+    ]);
+    controller.promote_pawn(PromotionPiece::Queen).unwrap();
+    assert_eq!(events(&receiver), vec![
+        GameEvent::Promotion(Piece::Queen(Color::White), Square::B8),
+        GameEvent::Check(Color::Black),
+    ]);
+
+    let mut controller = controller_from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+    let receiver = controller.subscribe();
+    let mv = Move::Normal { start: Square::A7, end: Square::A8, promotion: Some(PromotionPiece::Knight) };
+    controller.play(mv).unwrap();
+    assert_eq!(events(&receiver), vec![
+        GameEvent::MovePlayed(mv, Color::White),
+        GameEvent::Promotion(Piece::Knight(Color::White), Square::A8),
+        GameEvent::GameOver(EndgameStatus::Draw(DrawReason::InsufficientMaterial)),
+    ]);
+}
+
+#[test]
+fn test_checkmate_undo_and_subscribers() {
+    let mut controller = GameController::new(Game::new());
+    let receiver = controller.subscribe();
+    let dropped = controller.subscribe();
+    drop(dropped);
+
+    assert_eq!(controller.play_move(Square::E2, Square::E5), Err(MoveError::IllegalPieceMove));
+    assert!(events(&receiver).is_empty(), "A refused move sends nothing");
+
+    for mv in [Move::new(Square::F2, Square::F3), Move::new(Square::E7, Square::E5), Move::new(Square::G2, Square::G4)] {
+        controller.play(mv).unwrap();
+    }
+    events(&receiver);
+    controller.play(Move::new(Square::D8, Square::H4)).unwrap();
+    assert_eq!(events(&receiver), vec![
+        GameEvent::MovePlayed(Move::new(Square::D8, Square::H4), Color::Black),
+        GameEvent::Check(Color::White),
+        GameEvent::GameOver(EndgameStatus::Win(Color::Black, WinReason::Checkmate)),
+    ]);
+    assert_eq!(controller.game().endgame_status(), EndgameStatus::Win(Color::Black, WinReason::Checkmate));
+
+    controller.undo();
+    controller.offer_draw(Color::White);
+    controller.replace(Game::new());
+    assert_eq!(events(&receiver), vec![
+        GameEvent::MoveTakenBack(Move::new(Square::D8, Square::H4)),
+        GameEvent::DrawOffered(Color::White),
+        GameEvent::GameReplaced,
+    ]);
+    assert_eq!(controller.subscribers.len(), 1, "The dropped receiver is forgotten");
+}
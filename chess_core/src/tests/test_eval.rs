@@ -0,0 +1,74 @@
+use super::{evaluate, Term, MAX_PHASE};
+use crate::core_struct::{Color, Piece};
+use crate::{Game, Setup, Square};
+
+/// Game from the given pieces with both kings on their starting squares, white to move
+fn game_with_pieces(pieces: &[(Square, Piece)]) -> Game {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][4] = Some(Piece::King(Color::Black));
+    for &(square, piece) in pieces {
+        let (i, j) = square.coords();
+        setup.grid[i][j] = Some(piece);
+    }
+    Game::from_setup(&setup).unwrap()
+}
+
+#[test]
+fn test_starting_position_is_even() {
+    let evaluation = evaluate(&Game::new());
+    assert_eq!(evaluation.score(), 0);
+    assert_eq!(evaluation.phase(), MAX_PHASE);
+    for term in Term::ALL {
+        assert_eq!(evaluation.term(term, Color::White), evaluation.term(term, Color::Black), "{} is symmetric", term);
+    }
+    assert!(evaluation.term(Term::BishopPair, Color::White) > 0);
+}
+
+#[test]
+fn test_material_and_phase() {
+    let game = game_with_pieces(&[(Square::D4, Piece::Queen(Color::White)), (Square::A7, Piece::Pawn(Color::Black))]);
+    let evaluation = evaluate(&game);
+    assert_eq!(evaluation.phase(), 4, "Only a queen is left");
+    assert!(evaluation.score() > 800, "White is a queen up: {}", evaluation.score());
+    assert_eq!(evaluation.relative_score(Color::Black), -evaluation.score());
+    assert!(evaluation.term(Term::Mobility, Color::White) > 0);
+    assert_eq!(evaluation.term(Term::Mobility, Color::Black), 0, "Pawns and kings have no mobility");
+
+    let evaluation = evaluate(&game_with_pieces(&[]));
+    assert_eq!(evaluation.phase(), 0);
+    assert_eq!(evaluation.score(), 0);
+}
+
+#[test]
+fn test_pawn_structure() {
+    // White: doubled and isolated pawns on the a file. Black: a pawn on d5 with no white pawn in front
+    let game = game_with_pieces(&[
+        (Square::A2, Piece::Pawn(Color::White)),
+        (Square::A3, Piece::Pawn(Color::White)),
+        (Square::D5, Piece::Pawn(Color::Black)),
+    ]);
+    let evaluation = evaluate(&game);
+    assert!(evaluation.term(Term::PawnStructure, Color::White) < evaluation.term(Term::PawnStructure, Color::Black));
+    assert!(evaluation.term(Term::PassedPawns, Color::Black) > 0);
+    assert!(evaluation.term(Term::PassedPawns, Color::White) > 0, "No black pawn stops the a pawns either");
+
+    // A black pawn on b7 stops the a pawns
+    let game = game_with_pieces(&[(Square::A2, Piece::Pawn(Color::White)), (Square::B7, Piece::Pawn(Color::Black))]);
+    assert_eq!(evaluate(&game).term(Term::PassedPawns, Color::White), 0);
+}
+
+#[test]
+fn test_king_safety() {
+    let sheltered = game_with_pieces(&[
+        (Square::D2, Piece::Pawn(Color::White)),
+        (Square::E2, Piece::Pawn(Color::White)),
+        (Square::F2, Piece::Pawn(Color::White)),
+        (Square::A1, Piece::Rook(Color::White)),
+        (Square::A8, Piece::Rook(Color::Black)),
+    ]);
+    let evaluation = evaluate(&sheltered);
+    assert!(evaluation.term_score(Term::KingSafety, Color::White).middlegame > 0);
+    assert!(evaluation.term_score(Term::KingSafety, Color::Black).middlegame < 0, "Open files around the black king");
+    assert_eq!(evaluation.term_score(Term::KingSafety, Color::Black).endgame, 0, "King safety doesn't matter in the endgame");
+}
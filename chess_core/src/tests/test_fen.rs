@@ -0,0 +1,82 @@
+use crate::core_struct::{Color, Piece};
+use crate::{Game, Move, ParseError, Setup, Square, Variant};
+
+#[test]
+fn test_to_fen() {
+    let mut game = Game::new();
+    assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+    game.play(Move::new(Square::E2, Square::E4)).unwrap();
+    assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    game.play(Move::new(Square::C7, Square::C5)).unwrap();
+    game.play(Move::new(Square::G1, Square::F3)).unwrap();
+    assert_eq!(game.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][4] = Some(Piece::King(Color::Black));
+    setup.turn = Color::Black;
+    assert_eq!(Game::from_setup(&setup).unwrap().to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn test_to_fen_pockets() {
+    let mut game = Game::with_variant(Variant::Crazyhouse);
+    for mv in [Move::new(Square::E2, Square::E4), Move::new(Square::D7, Square::D5), Move::new(Square::E4, Square::D5)] {
+        game.play(mv).unwrap();
+    }
+    assert_eq!(game.to_fen(), "rnbqkbnr/ppp1pppp/8/3P4/8/8/PPPP1PPP/RNBQKBNR[P] b KQkq - 0 2");
+
+    let fen = game.to_fen();
+    let mut read = Game::from_setup(&Setup::from_fen(&fen).unwrap()).unwrap();
+    assert_eq!(read.variant(), Variant::Crazyhouse);
+    assert_eq!(read.to_fen(), fen, "The pockets read back");
+    read.play(Move::new(Square::D8, Square::D5)).unwrap();
+    assert!(read.drop_piece(crate::PieceKind::Pawn, Square::E6).is_ok(), "Both pawns taken can be dropped");
+
+    // A promoted queen goes back to the pocket as a pawn
+    let fen = "4k3/8/8/8/8/8/4K3/Q~6r[Nn] b - - 0 30";
+    let mut game = Game::from_setup(&Setup::from_fen(fen).unwrap()).unwrap();
+    assert_eq!(game.to_fen(), fen);
+    game.play(Move::new(Square::H1, Square::A1)).unwrap();
+    assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/4K3/r7[Nnp] w - - 0 31");
+
+    assert_eq!(Setup::from_fen("4k3/8/8/8/8/8/8/4K3[Kn] w - - 0 1"), Err(ParseError::FenPocket));
+    assert_eq!(Setup::from_fen("4k3/8/8/8/8/8/8/4K3[n w - - 0 1"), Err(ParseError::FenPocket));
+    assert_eq!(Setup::from_fen("4k3/8/8/8/8/8/8/~4K3 w - - 0 1"), Err(ParseError::FenInvalidPiece));
+    assert_eq!(Setup::from_fen("4k3/8/8/8/8/8/8/Q~3K3 w - - 0 1"), Err(ParseError::FenInvalidPiece));
+}
+
+#[test]
+fn test_fullmove_number_during_promotion() {
+    let mut game = Game::from_setup(&Setup::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 40").unwrap()).unwrap();
+    game.play_move(Square::A7, Square::A8).unwrap();
+    assert_eq!(game.fullmove_number(), 40, "White's move isn't over until the pawn is promoted");
+    game.promote_pawn(crate::PromotionPiece::Queen).unwrap();
+    assert_eq!(game.to_fen(), "Q3k3/8/8/8/8/8/8/4K3 b - - 0 40");
+
+    let mut game = Game::from_setup(&Setup::from_fen("4k3/8/8/8/8/8/p7/4K3 b - - 0 40").unwrap()).unwrap();
+    game.play_move(Square::A2, Square::A1).unwrap();
+    assert_eq!(game.fullmove_number(), 40);
+    game.promote_pawn(crate::PromotionPiece::Queen).unwrap();
+    assert_eq!(game.fullmove_number(), 41);
+}
+
+#[test]
+fn test_from_fen() {
+    let fen = "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2";
+    let game = Game::from_setup(&Setup::from_fen(fen).unwrap()).unwrap();
+    assert_eq!(game.to_fen(), fen);
+    assert_eq!(Setup::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap(), Setup::default());
+
+    let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+    let game = Game::from_setup(&Setup::from_fen(fen).unwrap()).unwrap();
+    assert_eq!(game.to_fen(), fen);
+    assert_eq!(game.fullmove_number(), 3);
+
+    assert_eq!(Setup::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1"), Err(ParseError::FenRankCount));
+    assert_eq!(Setup::from_fen("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), Err(ParseError::FenRankLength));
+    assert_eq!(Setup::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1"), Err(ParseError::FenInvalidPiece));
+    assert_eq!(Setup::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1"), Err(ParseError::FenTurn));
+    assert_eq!(Setup::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - zero 1"), Err(ParseError::FenHalfmoveClock));
+}
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use super::Game;
+use crate::core_struct::{Board, Color, Piece, PieceKind};
+use crate::{DrawReason, EndgameStatus, File, Move, MoveError, ParseError, PromotionPiece, Setup, SetupError, Square, Variant, WinReason};
+
+/// Game with only the given pieces on the board, white to move and no castling
+fn game_with_pieces(variant: Variant, pieces: &[(Square, Piece)]) -> Game {
+    let mut grid = [[None; 8]; 8];
+    let mut positions: HashMap<Piece, _> = HashMap::new();
+    for &(square, piece) in pieces {
+        let (i, j) = square.coords();
+        grid[i][j] = Some(piece);
+        positions.entry(piece).or_insert_with(std::collections::HashSet::new).insert((i, j));
+    }
+    let mut game = Game::with_variant(variant);
+    game.board = Board { grid, pieces: positions };
+    game.game_state.white_castle_king_side = false;
+    game.game_state.white_castle_queen_side = false;
+    game.game_state.black_castle_king_side = false;
+    game.game_state.black_castle_queen_side = false;
+    game
+}
+
+#[test]
+fn test_crazyhouse_captures_go_to_pocket() {
+    let mut game = Game::with_variant(Variant::Crazyhouse);
+    game.play_move(Square::E2, Square::E4).unwrap(); // e4
+    game.play_move(Square::D7, Square::D5).unwrap(); // d5
+    assert_eq!(game.play_move(Square::E4, Square::D5), Ok(Some(Piece::Pawn(Color::Black))), "exd5 takes a black pawn");
+    assert_eq!(game.pocket(Color::White).count(PieceKind::Pawn), 1, "The taken pawn changes color");
+    assert!(game.pocket(Color::Black).is_empty());
+
+    game.play_move(Square::D8, Square::D5).unwrap(); // Qxd5
+    assert_eq!(game.pocket(Color::Black).count(PieceKind::Pawn), 1);
+
+    assert_eq!(game.drop_piece(PieceKind::Knight, Square::C3), Err(MoveError::NotInPocket(PieceKind::Knight)));
+    assert_eq!(game.drop_piece(PieceKind::Pawn, Square::A1), Err(MoveError::SquareOccupied(Square::A1)));
+    assert!(game.drop_piece(PieceKind::Pawn, Square::E6).is_ok(), "Pawn dropped on e6");
+    assert!(game.pocket(Color::White).is_empty());
+    assert_eq!(game.piece_at(Square::E6), Some(Piece::Pawn(Color::White)));
+    assert_eq!(game.turn(), Color::Black, "A drop ends the turn");
+}
+
+#[test]
+fn test_promoted_piece_is_captured_as_pawn() {
+    let mut game = game_with_pieces(Variant::Crazyhouse, &[
+        (Square::A1, Piece::King(Color::White)),
+        (Square::B7, Piece::Pawn(Color::White)),
+        (Square::H6, Piece::King(Color::Black)),
+        (Square::G8, Piece::Rook(Color::Black)),
+    ]);
+
+    game.play_move(Square::B7, Square::B8).unwrap();
+    game.promote_pawn(PromotionPiece::Queen).unwrap();
+    assert_eq!(game.play_move(Square::G8, Square::B8), Ok(Some(Piece::Pawn(Color::White))), "The promoted queen is taken as a pawn");
+    assert_eq!(game.pocket(Color::Black).count(PieceKind::Pawn), 1);
+    assert_eq!(game.pocket(Color::Black).count(PieceKind::Queen), 0);
+}
+
+#[test]
+fn test_drop_can_block_checkmate() {
+    let pieces = [
+        (Square::H1, Piece::King(Color::White)),
+        (Square::G2, Piece::Pawn(Color::White)),
+        (Square::H2, Piece::Pawn(Color::White)),
+        (Square::A1, Piece::Rook(Color::Black)),
+        (Square::A8, Piece::King(Color::Black)),
+    ];
+
+    let mut game = game_with_pieces(Variant::Crazyhouse, &pieces);
+    assert_eq!(game.evaluate_endgame(), EndgameStatus::Win(Color::Black, WinReason::Checkmate), "Back rank mate with an empty pocket");
+
+    let mut game = game_with_pieces(Variant::Crazyhouse, &pieces);
+    game.add_to_pocket(Piece::Knight(Color::White)).unwrap();
+    assert_eq!(game.evaluate_endgame(), EndgameStatus::Ongoing, "A knight drop can block the check");
+    assert!(game.drop_piece(PieceKind::Knight, Square::C3).is_err(), "The drop has to block the check");
+    assert!(game.drop_piece(PieceKind::Knight, Square::F1).is_ok());
+}
+
+#[test]
+fn test_bughouse_captures_leave_the_board() {
+    let mut game = Game::with_variant(Variant::Bughouse);
+    game.play_move(Square::E2, Square::E4).unwrap(); // e4
+    game.play_move(Square::D7, Square::D5).unwrap(); // d5
+    assert_eq!(game.play_move(Square::E4, Square::D5), Ok(Some(Piece::Pawn(Color::Black))));
+    assert!(game.pocket(Color::White).is_empty(), "The pawn goes to the partner's board");
+
+    game.add_to_pocket(Piece::Knight(Color::Black)).unwrap();
+    assert!(game.drop_piece(PieceKind::Knight, Square::F6).is_ok(), "Knight received from the partner dropped on f6");
+
+    assert_eq!(game.add_to_pocket(Piece::King(Color::White)), Err(MoveError::KingInPocket));
+    for _ in 0..255 {
+        game.add_to_pocket(Piece::Queen(Color::White)).unwrap();
+    }
+    assert_eq!(game.add_to_pocket(Piece::Queen(Color::White)), Err(MoveError::PocketFull(PieceKind::Queen)));
+    assert_eq!(game.pocket(Color::White).count(PieceKind::Queen), 255);
+}
+
+#[test]
+fn test_standard_has_no_drops() {
+    let mut game = Game::new();
+    assert_eq!(game.add_to_pocket(Piece::Knight(Color::White)), Err(MoveError::DropsNotAllowed));
+    assert_eq!(game.drop_piece(PieceKind::Knight, Square::D4), Err(MoveError::DropsNotAllowed));
+}
+
+#[test]
+fn test_move_errors() {
+    let mut game = Game::new();
+    assert_eq!(game.play_move(Square::E4, Square::E5), Err(MoveError::NoPiece(Square::E4)));
+    assert_eq!(game.play_move(Square::E7, Square::E5), Err(MoveError::WrongColor));
+    assert_eq!(game.play_move(Square::D1, Square::D2), Err(MoveError::OwnPieceOnTarget));
+    assert_eq!(game.play_move(Square::G1, Square::G3), Err(MoveError::IllegalPieceMove));
+    assert_eq!(game.promote_pawn(PromotionPiece::Queen), Err(MoveError::NoPromotionPending));
+
+    game.play_move(Square::F2, Square::F3).unwrap(); // f3
+    game.play_move(Square::E7, Square::E5).unwrap(); // e5
+    game.play_move(Square::G2, Square::G4).unwrap(); // g4
+    game.play_move(Square::D8, Square::H4).unwrap(); // Qh4#
+    assert_eq!(game.play_move(Square::E1, Square::F2), Err(MoveError::LeavesKingInCheck));
+
+    game.evaluate_endgame();
+    assert_eq!(game.play_move(Square::A2, Square::A3), Err(MoveError::GameOver));
+}
+
+#[test]
+fn test_from_setup() {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[0][7] = Some(Piece::Rook(Color::White));
+    setup.grid[7][4] = Some(Piece::King(Color::Black));
+    setup.grid[4][3] = Some(Piece::Pawn(Color::Black)); // Black pawn on d5 that just moved
+    setup.grid[4][4] = Some(Piece::Pawn(Color::White)); // White pawn on e5 that can take en passant
+    setup.white_castle_king_side = true;
+    setup.en_passant = Some(File::D);
+
+    let game = Game::from_setup(&setup).unwrap();
+    assert_eq!(game.get_legal_moves(Square::E5), vec![Square::E6, Square::D6], "exd6 en passant is legal");
+    assert!(game.get_legal_moves(Square::E1).contains(&Square::G1), "White can castle king side");
+
+    setup.grid[4][3] = None;
+    assert!(matches!(Game::from_setup(&setup), Err(SetupError::InvalidEnPassant(File::D))));
+}
+
+#[test]
+fn test_from_setup_ends_checkmate() {
+    let mut setup = Setup::empty();
+    setup.grid[0][7] = Some(Piece::King(Color::White));
+    setup.grid[1][6] = Some(Piece::Pawn(Color::White));
+    setup.grid[1][7] = Some(Piece::Pawn(Color::White));
+    setup.grid[0][0] = Some(Piece::Rook(Color::Black));
+    setup.grid[7][0] = Some(Piece::King(Color::Black));
+
+    let game = Game::from_setup(&setup).unwrap();
+    assert_eq!(game.endgame_status(), EndgameStatus::Win(Color::Black, WinReason::Checkmate));
+}
+
+#[test]
+fn test_castling_lost_when_rook_is_taken() {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[0][7] = Some(Piece::Rook(Color::White));
+    setup.grid[7][4] = Some(Piece::King(Color::Black));
+    setup.grid[6][7] = Some(Piece::Rook(Color::Black));
+    setup.white_castle_king_side = true;
+    setup.turn = Color::Black;
+
+    let mut game = Game::from_setup(&setup).unwrap();
+    game.play_move(Square::H7, Square::H1).unwrap(); // Rxh1+
+    game.play_move(Square::E1, Square::E2).unwrap(); // Ke2
+    game.play_move(Square::H1, Square::H3).unwrap(); // Rh3
+    game.play_move(Square::E2, Square::E1).unwrap(); // Ke1
+    game.play_move(Square::H3, Square::A3).unwrap(); // Ra3
+    assert!(!game.get_legal_moves(Square::E1).contains(&Square::G1), "No rook left to castle with");
+}
+
+#[test]
+fn test_bad_input_is_refused() {
+    let mut game = Game::new();
+    assert!(game.get_legal_moves(Square::D4).is_empty(), "No piece on d4");
+    assert!(game.get_legal_moves(Square::E7).is_empty(), "Not black's turn");
+    assert_eq!(game.play(Move::new(Square::D4, Square::A1)), Err(MoveError::NoPiece(Square::D4)));
+    assert_eq!("e2i4".parse::<Move>(), Err(ParseError::InvalidSquare), "Squares off the board can't be written");
+    assert_eq!(game.to_fen(), Game::new().to_fen(), "Nothing was played");
+}
+
+#[test]
+fn test_attackers_checkers_and_pins() {
+    let game = game_with_pieces(Variant::Standard, &[
+        (Square::E1, Piece::King(Color::White)),
+        (Square::E2, Piece::Knight(Color::White)),
+        (Square::E8, Piece::Rook(Color::Black)),
+        (Square::A4, Piece::Bishop(Color::Black)),
+        (Square::D3, Piece::Pawn(Color::Black)),
+        (Square::H8, Piece::King(Color::Black)),
+    ]);
+    assert!(game.checkers().is_empty());
+    assert_eq!(game.pinned_pieces(Color::White), vec![Square::E2], "The knight stands between the rook and the king");
+    let mut attackers = game.attackers_of(Square::E2, Color::Black);
+    attackers.sort();
+    assert_eq!(attackers, vec![Square::D3, Square::E8], "The pawn and the rook, not the bishop");
+    assert!(game.get_legal_moves(Square::E2).is_empty(), "A pinned knight can't move");
+
+    let mut game = game;
+    game.play_move(Square::E1, Square::F1).unwrap(); // Kf1
+    game.play_move(Square::A4, Square::B5).unwrap(); // Bb5, nothing pinned or checking any more
+    assert!(game.pinned_pieces(Color::White).is_empty());
+    assert!(game.checkers().is_empty());
+}
+
+#[test]
+fn test_mate_queries_leave_the_game_ongoing() {
+    // Fool's mate
+    let mut game = Game::new();
+    for mv in [Move::new(Square::F2, Square::F3), Move::new(Square::E7, Square::E5), Move::new(Square::G2, Square::G4), Move::new(Square::D8, Square::H4)] {
+        game.play(mv).unwrap();
+    }
+    assert!(game.is_in_check());
+    assert_eq!(game.checkers(), vec![Square::H4]);
+    assert!(game.is_checkmate());
+    assert!(!game.is_stalemate());
+    assert_eq!(game.endgame_status(), EndgameStatus::Ongoing, "Asking doesn't end the game");
+
+    let game = game_with_pieces(Variant::Standard, &[
+        (Square::A1, Piece::King(Color::White)),
+        (Square::B3, Piece::Queen(Color::Black)),
+        (Square::H8, Piece::King(Color::Black)),
+    ]);
+    assert!(game.is_stalemate());
+    assert!(!game.is_checkmate());
+    assert!(game.all_legal_moves().is_empty());
+    assert_eq!(game.endgame_status(), EndgameStatus::Ongoing);
+}
+
+#[test]
+fn test_repetitions_are_claimed_then_automatic() {
+    let mut game = Game::new();
+    assert_eq!(game.claim_draw(), Err(MoveError::NoDrawToClaim));
+    let knights = [Move::new(Square::G1, Square::F3), Move::new(Square::G8, Square::F6), Move::new(Square::F3, Square::G1), Move::new(Square::F6, Square::G8)];
+    for mv in knights.iter().chain(&knights) {
+        game.play(*mv).unwrap();
+    }
+    assert!(game.evaluate_endgame().is_ongoing(), "Threefold repetition doesn't end the game on its own");
+    assert_eq!(game.can_claim_draw(), Some(DrawReason::ThreefoldRepetition));
+
+    let mut claimed = game.clone();
+    assert_eq!(claimed.claim_draw(), Ok(DrawReason::ThreefoldRepetition));
+    assert_eq!(claimed.endgame_status(), EndgameStatus::Draw(DrawReason::ThreefoldRepetition));
+    assert_eq!(claimed.claim_draw(), Err(MoveError::GameOver));
+    assert_eq!(claimed.evaluate_endgame(), EndgameStatus::Draw(DrawReason::ThreefoldRepetition), "The claim stays");
+
+    let mut resigned = game.clone();
+    resigned.end_game(EndgameStatus::Win(Color::Black, WinReason::Resignation));
+    assert_eq!(resigned.evaluate_endgame(), EndgameStatus::Win(Color::Black, WinReason::Resignation));
+    assert_eq!(resigned.endgame_status(), EndgameStatus::Win(Color::Black, WinReason::Resignation), "The resignation isn't undone");
+
+    for mv in knights.iter().chain(&knights) {
+        game.play(*mv).unwrap();
+    }
+    assert_eq!(game.evaluate_endgame(), EndgameStatus::Draw(DrawReason::FivefoldRepetition));
+}
+
+#[test]
+fn test_move_rules_are_claimed_then_automatic() {
+    // White: Kg6, Ra1. Black: Kh8
+    let mut setup = Setup::empty();
+    setup.set_piece(Square::G6, Some(Piece::King(Color::White)));
+    setup.set_piece(Square::A1, Some(Piece::Rook(Color::White)));
+    setup.set_piece(Square::H8, Some(Piece::King(Color::Black)));
+
+    setup.halfmove_clock = 99;
+    let mut game = Game::from_setup(&setup).unwrap();
+    assert_eq!(game.can_claim_draw(), None);
+    game.play_move(Square::A1, Square::A2).unwrap();
+    assert!(game.evaluate_endgame().is_ongoing(), "The fifty-move rule has to be claimed");
+    assert_eq!(game.can_claim_draw(), Some(DrawReason::FiftyMoveRule));
+
+    setup.halfmove_clock = 149;
+    let mut game = Game::from_setup(&setup).unwrap();
+    game.play_move(Square::A1, Square::A2).unwrap();
+    assert_eq!(game.evaluate_endgame(), EndgameStatus::Draw(DrawReason::SeventyFiveMoveRule));
+
+    let mut game = Game::from_setup(&setup).unwrap();
+    game.play_move(Square::A1, Square::A8).unwrap();
+    assert_eq!(game.evaluate_endgame(), EndgameStatus::Win(Color::White, WinReason::Checkmate), "A mate on the last move still wins");
+}
+
+#[test]
+fn test_only_dead_positions_lack_material() {
+    let kings = [(Square::E1, Piece::King(Color::White)), (Square::E8, Piece::King(Color::Black))];
+    let cases = [
+        (vec![], true),
+        (vec![(Square::D4, Piece::Knight(Color::White))], true),
+        (vec![(Square::C1, Piece::Bishop(Color::White)), (Square::F8, Piece::Bishop(Color::Black))], true),
+        (vec![(Square::C1, Piece::Bishop(Color::White)), (Square::A3, Piece::Bishop(Color::White)), (Square::F8, Piece::Bishop(Color::Black))], true),
+        (vec![(Square::C1, Piece::Bishop(Color::White)), (Square::C8, Piece::Bishop(Color::Black))], false),
+        (vec![(Square::C1, Piece::Bishop(Color::White)), (Square::B8, Piece::Knight(Color::Black))], false),
+        (vec![(Square::B1, Piece::Knight(Color::White)), (Square::G1, Piece::Knight(Color::White))], false),
+        (vec![(Square::A2, Piece::Pawn(Color::White))], false),
+    ];
+    for (pieces, dead) in cases {
+        let mut game = game_with_pieces(Variant::Standard, &[&kings[..], &pieces[..]].concat());
+        let expected = if dead { EndgameStatus::Draw(DrawReason::InsufficientMaterial) } else { EndgameStatus::Ongoing };
+        assert_eq!(game.evaluate_endgame(), expected, "{:?}", pieces);
+    }
+}
+
+#[test]
+fn test_undo_restores_board_and_repetitions() {
+    let mut game = Game::new();
+    let mut fens = vec![game.to_fen()];
+    // En passant and castling on both sides of the board
+    for mv in ["e2e4", "g8f6", "e4e5", "d7d5", "e5d6", "e7d6", "g1f3", "f8e7", "f1e2", "e8g8", "e1g1"] {
+        game.play(mv.parse().unwrap()).unwrap();
+        fens.push(game.to_fen());
+    }
+    while let Some(fen) = fens.pop() {
+        assert_eq!(game.to_fen(), fen);
+        let expected = Board::from_grid(game.board.grid);
+        for (piece, squares) in &expected.pieces {
+            assert_eq!(game.board.pieces.get(piece).cloned().unwrap_or_default(), *squares, "The squares of {:?} after undo", piece);
+        }
+        game.undo();
+    }
+
+    let knights: Vec<Move> = ["g1f3", "g8f6", "f3g1", "f6g8"].iter().map(|mv| mv.parse().unwrap()).collect();
+    for &mv in &knights {
+        game.play(mv).unwrap();
+    }
+    game.play(Move::new(Square::E2, Square::E4)).unwrap();
+    game.undo();
+    for &mv in &knights {
+        game.play(mv).unwrap();
+    }
+    assert_eq!(game.can_claim_draw(), Some(DrawReason::ThreefoldRepetition), "Taking back a pawn move brings back the positions before it");
+}
+
+#[test]
+fn test_san_while_promotion_pending() {
+    let mut game = game_with_pieces(Variant::Standard, &[(Square::E1, Piece::King(Color::White)), (Square::A8, Piece::King(Color::Black)), (Square::H7, Piece::Pawn(Color::White))]);
+    game.play_move(Square::H7, Square::H8).unwrap();
+    assert_eq!(game.to_san(Move::new(Square::E1, Square::E2)), Err(MoveError::PromotionPending));
+    assert_eq!(game.line_to_san(&[Move::new(Square::A8, Square::B7)]), Vec::<String>::new());
+}
@@ -1,7 +1,8 @@
 
 use std::collections::{HashMap, HashSet};
-use crate::core_struct::{Color, Piece, Board}; 
+use crate::core_struct::{Color, Piece, PieceKind, Board};
 use crate::game::GameState;
+use crate::{MoveError, Square};
 
 
 
@@ -152,7 +153,7 @@ fn test_king_move(){
         (Piece::King(Color::Black), HashSet::from([(5, 3)])),
     ]);
 
-    let mut board = Board { grid, pieces: pieces };
+    let board = Board { grid, pieces };
 
     let game_state = Default::default();
 
@@ -220,3 +221,35 @@ fn test_king_move(){
 
 }
 
+
+#[test]
+fn test_drop() {
+    let mut grid: [[Option<Piece>; 8]; 8] = [[None; 8]; 8];
+    grid[0][4] = Some(Piece::King(Color::White)); // White king at e1
+    grid[7][4] = Some(Piece::King(Color::Black)); // Black king at e8
+    grid[3][4] = Some(Piece::Rook(Color::Black)); // Black rook at e4 giving check
+
+    let pieces = HashMap::from([
+        (Piece::King(Color::White), HashSet::from([(0, 4)])),
+        (Piece::King(Color::Black), HashSet::from([(7, 4)])),
+        (Piece::Rook(Color::Black), HashSet::from([(3, 4)])),
+    ]);
+
+    let mut board = Board { grid, pieces };
+
+    let mut game_state = GameState::default();
+    game_state.white_pocket.add(PieceKind::Pawn).unwrap();
+    game_state.white_pocket.add(PieceKind::Knight).unwrap();
+
+    assert!(board.is_valid_drop(Piece::Pawn(Color::White), (1, 4), &game_state).is_ok(), "White pawn dropped on e2 blocks the check");
+    assert_eq!(board.is_valid_drop(Piece::Pawn(Color::White), (2, 3), &game_state), Err(MoveError::LeavesKingInCheck), "White pawn dropped on d3 doesn't block the check");
+    assert_eq!(board.is_valid_drop(Piece::Knight(Color::White), (3, 4), &game_state), Err(MoveError::SquareOccupied(Square::E4)));
+    assert_eq!(board.is_valid_drop(Piece::Bishop(Color::White), (2, 4), &game_state), Err(MoveError::NotInPocket(PieceKind::Bishop)));
+    assert_eq!(board.is_valid_drop(Piece::Pawn(Color::Black), (2, 4), &game_state), Err(MoveError::WrongColor));
+
+    board.execute_move((3, 4), (3, 3)); // Move the black rook to d4, the king is not in check anymore
+
+    assert!(board.is_valid_drop(Piece::Knight(Color::White), (7, 0), &game_state).is_ok(), "Knight dropped on a8");
+    assert_eq!(board.is_valid_drop(Piece::Pawn(Color::White), (7, 0), &game_state), Err(MoveError::PawnDropOnBackRank));
+    assert_eq!(board.is_valid_drop(Piece::Pawn(Color::White), (0, 0), &game_state), Err(MoveError::PawnDropOnBackRank));
+}
@@ -0,0 +1,136 @@
+use super::Move;
+use crate::core_struct::{Color, Piece, PieceKind};
+use crate::{Game, MoveError, ParseError, PromotionPiece, Setup, Square, Variant};
+
+fn perft(game: &mut Game, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut nodes = 0;
+    for mv in game.all_legal_moves() {
+        game.play(mv).unwrap();
+        nodes += perft(game, depth - 1);
+        game.undo();
+    }
+    nodes
+}
+
+#[test]
+fn test_perft() {
+    let mut game = Game::new();
+    assert_eq!(perft(&mut game, 1), 20);
+    assert_eq!(perft(&mut game, 2), 400);
+    assert_eq!(perft(&mut game, 3), 8902);
+    assert!(game.history().is_empty(), "Every move was taken back");
+}
+
+#[test]
+fn test_undo() {
+    let mut game = Game::new();
+    assert_eq!(game.undo(), None);
+
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.play_move(Square::D7, Square::D5).unwrap();
+    game.play_move(Square::E4, Square::D5).unwrap();
+    assert_eq!(game.history(), &[Move::new(Square::E2, Square::E4), Move::new(Square::D7, Square::D5), Move::new(Square::E4, Square::D5)]);
+
+    assert_eq!(game.undo(), Some(Move::new(Square::E4, Square::D5)));
+    assert_eq!(game.piece_at(Square::D5), Some(Piece::Pawn(Color::Black)), "The taken pawn is back");
+    assert_eq!(game.turn(), Color::White);
+    game.undo();
+    game.undo();
+    assert_eq!(game.polyglot_key(), Game::new().polyglot_key(), "Back to the starting position");
+
+    // Undoing a pawn move that waits for its promotion piece
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][0] = Some(Piece::King(Color::Black));
+    setup.grid[6][7] = Some(Piece::Pawn(Color::White));
+    let mut game = Game::from_setup(&setup).unwrap();
+    game.play_move(Square::H7, Square::H8).unwrap();
+    assert!(game.undo().is_some());
+    assert!(game.is_promotion_pending().is_none());
+    assert_eq!(game.piece_at(Square::H7), Some(Piece::Pawn(Color::White)));
+
+    assert_eq!(game.play(Move::new(Square::H7, Square::H8)), Err(MoveError::MissingPromotionPiece));
+    let promotion = Move::Normal { start: Square::H7, end: Square::H8, promotion: Some(PromotionPiece::Rook) };
+    game.play(promotion).unwrap();
+    assert_eq!(game.history(), &[promotion]);
+}
+
+#[test]
+fn test_san() {
+    let mut game = Game::new();
+    assert_eq!(game.to_san(Move::new(Square::E2, Square::E4)), Ok("e4".to_string()));
+    assert_eq!(game.to_san(Move::new(Square::G1, Square::F3)), Ok("Nf3".to_string()));
+    assert_eq!(game.to_san(Move::new(Square::E2, Square::E5)), Err(MoveError::IllegalPieceMove));
+
+    // 1.e4 d5 2.exd5 Nf6 3.Nc3 Nbd7 4.d4 e5 5.dxe6
+    let line = [
+        Move::new(Square::E2, Square::E4), Move::new(Square::D7, Square::D5), Move::new(Square::E4, Square::D5), Move::new(Square::G8, Square::F6),
+        Move::new(Square::B1, Square::C3), Move::new(Square::B8, Square::D7), Move::new(Square::D2, Square::D4), Move::new(Square::E7, Square::E5),
+        Move::new(Square::D5, Square::E6),
+    ];
+    assert_eq!(game.line_to_san(&line), ["e4", "d5", "exd5", "Nf6", "Nc3", "Nbd7", "d4", "e5", "dxe6"]);
+    for mv in line {
+        game.play(mv).unwrap();
+    }
+    game.play(Move::new(Square::A7, Square::A6)).unwrap(); // a6
+    assert_eq!(game.to_san(Move::new(Square::E6, Square::F7)), Ok("exf7+".to_string()));
+    assert_eq!(game.to_san(Move::new(Square::F1, Square::B5)), Ok("Bb5".to_string()));
+
+    // Fool's mate and castling
+    let mut game = Game::new();
+    let line = [Move::new(Square::F2, Square::F3), Move::new(Square::E7, Square::E5), Move::new(Square::G2, Square::G4), Move::new(Square::D8, Square::H4)];
+    assert_eq!(game.line_to_san(&line), ["f3", "e5", "g4", "Qh4#"]);
+    for mv in [Move::new(Square::E2, Square::E4), Move::new(Square::E7, Square::E5), Move::new(Square::G1, Square::F3), Move::new(Square::B8, Square::C6), Move::new(Square::F1, Square::C4), Move::new(Square::G8, Square::F6)] {
+        game.play(mv).unwrap();
+    }
+    assert_eq!(game.to_san(Move::new(Square::E1, Square::G1)), Ok("O-O".to_string()));
+}
+
+#[test]
+fn test_san_drops_and_promotions() {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][0] = Some(Piece::King(Color::Black));
+    setup.grid[6][7] = Some(Piece::Pawn(Color::White));
+    let game = Game::from_setup(&setup).unwrap();
+    let promotion = Move::Normal { start: Square::H7, end: Square::H8, promotion: Some(PromotionPiece::Queen) };
+    assert_eq!(game.to_san(promotion), Ok("h8=Q+".to_string()));
+    assert_eq!(promotion.to_string(), "h7h8q");
+
+    let mut game = Game::with_variant(Variant::Crazyhouse);
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.play_move(Square::D7, Square::D5).unwrap();
+    game.play_move(Square::E4, Square::D5).unwrap();
+    game.play_move(Square::D8, Square::D5).unwrap();
+    let drop = Move::Drop { kind: PieceKind::Pawn, square: Square::C3 };
+    assert_eq!(game.to_san(drop), Ok("P@c3".to_string()));
+    assert!(game.all_legal_moves().contains(&drop));
+    assert_eq!(drop.to_string(), "P@c3");
+}
+
+#[test]
+fn test_parse_san() {
+    let game = Game::new();
+    assert_eq!(game.parse_san("e4"), Ok(Move::new(Square::E2, Square::E4)));
+    assert_eq!(game.parse_san("Nf3"), Ok(Move::new(Square::G1, Square::F3)));
+    assert_eq!(game.parse_san("Nf3!?"), Ok(Move::new(Square::G1, Square::F3)));
+    assert_eq!(game.parse_san("e5"), Err(ParseError::UnknownMove));
+    assert_eq!(game.parse_san("Ke2"), Err(ParseError::UnknownMove));
+
+    // Every move of a line reads back as itself
+    let mut game = Game::new();
+    let line = [
+        Move::new(Square::E2, Square::E4), Move::new(Square::D7, Square::D5), Move::new(Square::E4, Square::D5), Move::new(Square::G8, Square::F6),
+        Move::new(Square::B1, Square::C3), Move::new(Square::B8, Square::D7), Move::new(Square::F1, Square::B5), Move::new(Square::C7, Square::C6),
+        Move::new(Square::G1, Square::F3), Move::new(Square::E7, Square::E6),
+    ];
+    for (mv, san) in line.into_iter().zip(game.line_to_san(&line)) {
+        assert_eq!(game.parse_san(&san), Ok(mv), "{}", san);
+        game.play(mv).unwrap();
+    }
+    assert_eq!(game.parse_san("0-0"), Ok(Move::new(Square::E1, Square::G1)));
+    assert_eq!(game.parse_san("O-O+"), Ok(Move::new(Square::E1, Square::G1)), "A wrong check mark is ignored");
+}
@@ -0,0 +1,87 @@
+use super::{read_pgn, PgnGame};
+use crate::core_struct::{Color, Piece};
+use crate::{EndgameStatus, Game, Move, ParseError, Square, Variant, WinReason};
+
+const TWO_GAMES: &str = r#"[Event "Casual"]
+[White "Anderssen"]
+[Black "Kieseritzky"]
+[Result "0-1"]
+
+1. f3 {weak} e5 2. g4?? (2. e4 Nf6 (2... d5) 3. d3) 2... Qh4# $1 0-1
+
+[Event "Second"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 w - - 0 40"]
+
+40.e4 Kd7 41. Kd2 ; a comment to the end of the line
+Ke6 *
+"#;
+
+#[test]
+fn test_read_pgn() {
+    let games = read_pgn(TWO_GAMES);
+    assert_eq!(games.len(), 2);
+
+    let first = games[0].as_ref().unwrap();
+    assert_eq!(first.tag("White"), Some("Anderssen"));
+    assert_eq!(first.result, "0-1");
+    assert_eq!(first.moves, [Move::new(Square::F2, Square::F3), Move::new(Square::E7, Square::E5), Move::new(Square::G2, Square::G4), Move::new(Square::D8, Square::H4)]);
+    assert_eq!(first.final_position().endgame_status(), EndgameStatus::Win(Color::Black, WinReason::Checkmate));
+
+    let second = games[1].as_ref().unwrap();
+    assert_eq!(second.moves.len(), 4);
+    assert_eq!(second.result, "*");
+    assert_eq!(second.final_position().to_fen(), "8/8/4k3/8/4P3/8/3K4/8 w - - 3 42");
+}
+
+#[test]
+fn test_read_pgn_errors() {
+    let games = read_pgn("1. e4 e5 2. Ke3 Nc6 *\n\n1. d4 {not closed");
+    assert_eq!(games.len(), 2);
+    let error = games[0].as_ref().unwrap_err();
+    assert_eq!((error.game, error.ply, error.token.as_str(), error.reason), (0, 2, "Ke3", ParseError::UnknownMove));
+    assert_eq!(games[1].as_ref().unwrap_err().reason, ParseError::UnclosedComment);
+
+    let games = read_pgn("[Variant \"Atomic\"]\n1. e4 *");
+    assert_eq!(games[0].as_ref().unwrap_err().reason, ParseError::UnsupportedVariant);
+}
+
+#[test]
+fn test_pgn_round_trip() {
+    for game in read_pgn(TWO_GAMES) {
+        let game = game.unwrap();
+        let written = game.to_pgn();
+        let read = read_pgn(&written).pop().unwrap().unwrap();
+        assert_eq!(read.moves, game.moves);
+        assert_eq!(read.result, game.result);
+        assert_eq!(read.tag("Event"), game.tag("Event"));
+        assert_eq!(read.start.to_fen(), game.start.to_fen());
+    }
+    assert!(read_pgn(TWO_GAMES)[1].as_ref().unwrap().to_pgn().contains("[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 40\"]\n\n40. e4 Kd7 41. Kd2 Ke6 *\n"));
+
+    let mut game = Game::new();
+    game.play(Move::new(Square::D2, Square::D4)).unwrap();
+    let written = PgnGame::from_game(&game).to_pgn();
+    assert!(written.starts_with("[Event \"?\"]\n"));
+    assert!(written.ends_with("\n\n1. d4 *\n"));
+
+    // A crazyhouse game from a position with pockets
+    let pgn = "[Variant \"Crazyhouse\"]\n[FEN \"4k3/8/8/8/8/8/8/4K3[Nn] w - - 0 1\"]\n\n1. N@f3 N@c6 *\n";
+    let game = read_pgn(pgn).pop().unwrap().unwrap();
+    let read = read_pgn(&game.to_pgn()).pop().unwrap().unwrap();
+    assert_eq!(read.moves, game.moves);
+    assert_eq!(read.final_position().to_fen(), "4k3/8/2n5/8/8/5N2/8/4K3[] w - - 2 2");
+}
+
+#[test]
+fn test_pgn_of_bughouse_game() {
+    let mut game = Game::with_variant(Variant::Bughouse);
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.add_to_pocket(Piece::Knight(Color::Black)).unwrap();
+    game.play("N@e6".parse().unwrap()).unwrap();
+    assert_eq!(game.history_to_san(), ["e4", "N@e6"], "The knight received after e4 is dropped");
+
+    let pgn = PgnGame::from_game(&game);
+    assert!(pgn.to_pgn().ends_with("\n\n1. e4 N@e6 *\n"));
+    assert_eq!(pgn.final_position().to_fen(), game.to_fen());
+}
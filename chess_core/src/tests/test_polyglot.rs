@@ -0,0 +1,84 @@
+use super::{BookMove, OpeningBook};
+use crate::{Game, PromotionPiece, Square};
+
+// Small book: 1.e4 (100) 1.d4 (60) 1.c4 and 1.Nf3 (20), the Ruy Lopez up to 5.O-O and a pawn that promotes
+const BOOK: &[u8] = include_bytes!("fixtures/small_book.bin");
+
+fn play(game: &mut Game, moves: &[(Square, Square)]) {
+    for &(start, end) in moves {
+        game.play_move(start, end).unwrap();
+    }
+}
+
+#[test]
+fn test_polyglot_key() {
+    // Keys given in the Polyglot book format specification
+    let mut game = Game::new();
+    assert_eq!(game.polyglot_key(), 0x463b96181691fc9c, "Starting position");
+    play(&mut game, &[(Square::E2, Square::E4)]);
+    assert_eq!(game.polyglot_key(), 0x823c9b50fd114196, "e4, no black pawn can take en passant");
+    play(&mut game, &[(Square::D7, Square::D5)]);
+    assert_eq!(game.polyglot_key(), 0x0756b94461c50fb0);
+    play(&mut game, &[(Square::E4, Square::E5)]);
+    assert_eq!(game.polyglot_key(), 0x662fafb965db29d4);
+    play(&mut game, &[(Square::F7, Square::F5)]);
+    assert_eq!(game.polyglot_key(), 0x22a48b5a8e47ff78, "f5, exf6 en passant is possible");
+    play(&mut game, &[(Square::E1, Square::E2)]);
+    assert_eq!(game.polyglot_key(), 0x652a607ca3f242c1, "Ke2, white can't castle anymore");
+    play(&mut game, &[(Square::E8, Square::F7)]);
+    assert_eq!(game.polyglot_key(), 0x00fdd303c946bdd9);
+
+    let mut game = Game::new();
+    play(&mut game, &[(Square::A2, Square::A4), (Square::B7, Square::B5), (Square::H2, Square::H4), (Square::B5, Square::B4), (Square::C2, Square::C4)]);
+    assert_eq!(game.polyglot_key(), 0x3c8123ea7b067637);
+    play(&mut game, &[(Square::B4, Square::C3), (Square::A1, Square::A3)]);
+    assert_eq!(game.polyglot_key(), 0x5c3f9b829b279560, "Rook moved, no more queen side castling for white");
+}
+
+#[test]
+fn test_book_moves() {
+    let book = OpeningBook::from_bytes(BOOK).unwrap();
+    let mut game = Game::new();
+
+    let moves = book.moves(&game);
+    assert_eq!(moves.len(), 4);
+    assert_eq!(moves[0], BookMove { start: Square::E2, end: Square::E4, promotion: None, weight: 100 });
+    assert_eq!(book.best_move(&game).map(|book_move| book_move.end), Some(Square::E4), "e4 has the highest weight");
+    for _ in 0..20 {
+        assert!(moves.contains(&book.random_move(&game).unwrap()));
+    }
+
+    play(&mut game, &[(Square::A2, Square::A3)]);
+    assert!(book.moves(&game).is_empty(), "a3 is not in the book");
+    assert_eq!(book.random_move(&game), None);
+}
+
+#[test]
+fn test_book_castling_and_promotion() {
+    let book = OpeningBook::from_bytes(BOOK).unwrap();
+
+    let mut game = Game::new();
+    // e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6
+    play(&mut game, &[
+        (Square::E2, Square::E4), (Square::E7, Square::E5), (Square::G1, Square::F3), (Square::B8, Square::C6),
+        (Square::F1, Square::B5), (Square::A7, Square::A6), (Square::B5, Square::A4), (Square::G8, Square::F6),
+    ]);
+    let castle = book.best_move(&game).unwrap();
+    assert_eq!((castle.start, castle.end), (Square::E1, Square::G1), "e1h1 in the book is O-O");
+    assert!(game.play_move(castle.start, castle.end).is_ok());
+
+    let mut game = Game::new();
+    // a4 h5 a5 h4 a6 h3 axb7 hxg2
+    play(&mut game, &[
+        (Square::A2, Square::A4), (Square::H7, Square::H5), (Square::A4, Square::A5), (Square::H5, Square::H4),
+        (Square::A5, Square::A6), (Square::H4, Square::H3), (Square::A6, Square::B7), (Square::H3, Square::G2),
+    ]);
+    let promotion = book.best_move(&game).unwrap();
+    assert_eq!((promotion.start, promotion.end, promotion.promotion), (Square::B7, Square::A8, Some(PromotionPiece::Knight)), "bxa8=N");
+}
+
+#[test]
+fn test_invalid_book() {
+    assert!(OpeningBook::from_bytes(&BOOK[..20]).is_err());
+    assert!(OpeningBook::from_bytes(&[]).unwrap().is_empty());
+}
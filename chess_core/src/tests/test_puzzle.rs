@@ -0,0 +1,74 @@
+use super::{load_puzzles, updated_rating, Answer, Puzzle, PuzzleError, DEFAULT_RATING};
+use crate::{EndgameStatus, Move};
+
+fn mv(notation: &str) -> Move {
+    notation.parse().unwrap()
+}
+
+#[test]
+fn test_load_puzzles() {
+    let puzzles = load_puzzles("src/tests/fixtures/puzzles.csv").unwrap();
+    assert_eq!(puzzles.len(), 2);
+    assert_eq!(puzzles[0].id, "00sHx");
+    assert_eq!(puzzles[0].rating, 1760);
+    assert_eq!(puzzles[0].moves, vec![mv("e8d7"), mv("a2e6"), mv("d7d8"), mv("f7f8")]);
+    assert!(puzzles[0].is_mate());
+    assert!(!puzzles[1].is_mate());
+
+    assert!(Puzzle::from_csv_line("1,8/8/8/8/8/8/8/8 w - - 0 1,e2e4 e7e5,1500").is_err(), "No kings");
+    assert!(Puzzle::from_csv_line("1,4k3/8/8/8/8/8/8/4K3 w - - 0 1,e1e2 e8e9,1500").is_err(), "Invalid move");
+    assert!(Puzzle::from_csv_line("1,4k3/8/8/8/8/8/8/4K3 w - - 0 1,e1e2 e8d8,x").is_err(), "Invalid rating");
+
+    let mut empty = puzzles[0].clone();
+    empty.moves.clear();
+    assert_eq!(empty.start().err(), Some(PuzzleError::TooFewMoves), "A puzzle built by hand without moves");
+}
+
+#[test]
+fn test_solve_puzzle() {
+    let puzzle = &load_puzzles("src/tests/fixtures/puzzles.csv").unwrap()[1];
+    let mut attempt = puzzle.start().unwrap();
+    assert_eq!(attempt.game().history(), &[mv("e3g3")]);
+
+    assert_eq!(attempt.play(mv("f4g3")), Answer::Wrong);
+    assert_eq!(attempt.mistakes(), 1);
+    assert_eq!(attempt.game().history().len(), 1, "A wrong move is not played");
+
+    assert_eq!(attempt.play(mv("e8e1")), Answer::Correct(mv("g1h2")));
+    assert_eq!(attempt.play(mv("e1c1")), Answer::Correct(mv("a1c1")));
+    assert_eq!(attempt.hint(), Some(mv("f4h6")));
+    assert_eq!(attempt.play(mv("f4h6")), Answer::Correct(mv("h2g1")));
+    assert_eq!(attempt.play(mv("h6c1")), Answer::Solved);
+    assert!(attempt.is_solved());
+}
+
+#[test]
+fn test_alternative_mate() {
+    // Both rooks can mate on the back rank
+    let puzzle = Puzzle::from_csv_line("mate,6k1/5ppp/8/8/8/8/1R6/R5K1 b - - 0 1,g8h8 a1a8,1000,0,0,0,mate mateIn1").unwrap();
+    let mut attempt = puzzle.start().unwrap();
+    assert_eq!(attempt.play(mv("b2b8")), Answer::Solved);
+    assert!(matches!(attempt.game().endgame_status(), EndgameStatus::Win(..)));
+    assert_eq!(attempt.mistakes(), 0);
+
+    // The ladder mate works with either rook going first
+    let puzzle = Puzzle::from_csv_line("ladder,4k3/8/8/8/8/8/8/RR4K1 b - - 0 1,e8f8 a1a7 f8g8 b1b8,1000,0,0,0,mate mateIn2").unwrap();
+    let mut attempt = puzzle.start().unwrap();
+    assert_eq!(attempt.play(mv("a1a2")), Answer::Wrong, "No mate in time after a waiting move");
+    assert!(matches!(attempt.play(mv("b1b7")), Answer::Correct(_)), "Another move that still mates in two");
+    assert_eq!(attempt.hint().map(|hint| hint.to_string()).as_deref(), Some("a1a8"));
+    assert_eq!(attempt.play(mv("a1a8")), Answer::Solved);
+    assert_eq!(attempt.mistakes(), 1);
+
+    let puzzle = Puzzle::from_csv_line("ladder,4k3/8/8/8/8/8/8/RR4K1 b - - 0 1,e8f8 a1a7 f8g8 b1b8,1000,0,0,0,endgame").unwrap();
+    assert_eq!(puzzle.start().unwrap().play(mv("b1b7")), Answer::Wrong, "Only mate puzzles accept other moves");
+}
+
+#[test]
+fn test_updated_rating() {
+    assert_eq!(updated_rating(DEFAULT_RATING, 1500, true), 1516);
+    assert_eq!(updated_rating(DEFAULT_RATING, 1500, false), 1484);
+    assert!(updated_rating(DEFAULT_RATING, 2500, true) > 1530);
+    assert!(updated_rating(DEFAULT_RATING, 2500, false) > 1498);
+}
+
@@ -0,0 +1,35 @@
+use crate::core_struct::{Color, Piece, PieceKind};
+use crate::{Game, Move, Setup, Square, Variant};
+
+#[test]
+fn test_replay_receives_pieces_after_their_move() {
+    let mut game = Game::with_variant(Variant::Bughouse);
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.add_to_pocket(Piece::Knight(Color::Black)).unwrap();
+    game.play("N@e6".parse().unwrap()).unwrap();
+
+    let mut replay = game.replay().unwrap();
+    assert_eq!(replay.game().pocket(Color::Black).count(PieceKind::Knight), 0);
+    assert_eq!(replay.play_next(), Ok(Some(Move::new(Square::E2, Square::E4))));
+    assert_eq!(replay.game().pocket(Color::Black).count(PieceKind::Knight), 1, "The knight arrived after e4");
+    assert_eq!(replay.next_move(), Some("N@e6".parse().unwrap()));
+    assert!(replay.play_next().unwrap().is_some());
+    assert_eq!(replay.play_next(), Ok(None));
+    assert_eq!(replay.into_game().to_fen(), game.to_fen());
+}
+
+#[test]
+fn test_replay_ends_on_pending_promotion() {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][0] = Some(Piece::King(Color::Black));
+    setup.grid[6][7] = Some(Piece::Pawn(Color::White));
+    let mut game = Game::from_setup(&setup).unwrap();
+    game.play_move(Square::H7, Square::H8).unwrap();
+
+    let mut replay = game.replay().unwrap();
+    assert!(replay.play_next().unwrap().is_some());
+    assert_eq!(replay.ply(), 1);
+    assert!(replay.game().is_promotion_pending().is_some());
+    assert_eq!(replay.next_move(), None);
+}
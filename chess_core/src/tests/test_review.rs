@@ -0,0 +1,61 @@
+use super::{review_game, Judgement};
+use crate::core_struct::{Color, Piece, PieceKind};
+use crate::{Game, Move, Square, Variant};
+
+fn fools_mate() -> Game {
+    let mut game = Game::new();
+    for mv in [
+        Move::new(Square::F2, Square::F3),
+        Move::new(Square::E7, Square::E5),
+        Move::new(Square::G2, Square::G4),
+        Move::new(Square::D8, Square::H4),
+    ] {
+        game.play(mv).unwrap();
+    }
+    game.evaluate_endgame();
+    game
+}
+
+#[test]
+fn test_review_finds_blunder() {
+    let review = review_game(&fools_mate(), 2).unwrap();
+    assert_eq!(review.moves.len(), 4);
+
+    let blunder = &review.moves[2];
+    assert_eq!(blunder.san, "g4");
+    assert_eq!(blunder.judgement, Some(Judgement::Blunder));
+    assert!(blunder.best_line.is_some());
+    assert_eq!(review.count(Color::White, Judgement::Blunder), 1);
+
+    let mate = &review.moves[3];
+    assert_eq!(mate.san, "Qh4#");
+    assert_eq!(mate.judgement, None);
+    assert!(mate.best_line.is_none(), "The mate is the best move");
+    assert_eq!(review.count(Color::Black, Judgement::Blunder), 0);
+
+    assert!(review.accuracy(Color::Black).unwrap() > review.accuracy(Color::White).unwrap());
+    assert_eq!(review.position(4).unwrap().to_fen(), fools_mate().to_fen());
+}
+
+#[test]
+fn test_review_pgn() {
+    let pgn = review_game(&fools_mate(), 2).unwrap().to_pgn();
+    assert!(pgn.starts_with("[Event \"?\"]\n"));
+    assert!(pgn.contains("[Result \"0-1\"]\n"));
+    assert!(!pgn.contains("[FEN"));
+    assert!(pgn.contains("2. g4 $4 {Blunder."));
+    assert!(pgn.contains("2... Qh4# 0-1\n"), "Black's move is numbered again after the comment");
+}
+
+#[test]
+fn test_review_drop_of_received_piece() {
+    let mut game = Game::with_variant(Variant::Bughouse);
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.add_to_pocket(Piece::Knight(Color::Black)).unwrap();
+    game.play("N@e6".parse().unwrap()).unwrap();
+
+    let review = review_game(&game, 1).unwrap();
+    assert_eq!(review.moves.iter().map(|mv| mv.san.as_str()).collect::<Vec<_>>(), ["e4", "N@e6"]);
+    assert_eq!(review.position(2).unwrap().to_fen(), game.to_fen());
+    assert!(review.position(1).unwrap().drop_piece(PieceKind::Knight, Square::E6).is_ok());
+}
@@ -0,0 +1,58 @@
+use std::sync::atomic::AtomicBool;
+
+use super::{analyze, best_line, LineScore};
+use crate::core_struct::{Color, Piece};
+use crate::{Game, Move, Setup, Square};
+
+#[test]
+fn test_finds_mate_in_one() {
+    // Back rank mate: Ra1-a8
+    let mut setup = Setup::empty();
+    setup.grid[0][6] = Some(Piece::King(Color::White));
+    setup.grid[0][0] = Some(Piece::Rook(Color::White));
+    setup.grid[7][6] = Some(Piece::King(Color::Black));
+    setup.grid[6][5] = Some(Piece::Pawn(Color::Black));
+    setup.grid[6][6] = Some(Piece::Pawn(Color::Black));
+    setup.grid[6][7] = Some(Piece::Pawn(Color::Black));
+    let game = Game::from_setup(&setup).unwrap();
+
+    let line = best_line(&game, 3).unwrap();
+    assert_eq!(line.moves[0], Move::new(Square::A1, Square::A8));
+    assert_eq!(line.san[0], "Ra8#");
+    assert_eq!(line.score, LineScore::Mate(1));
+    assert_eq!(line.score.to_string(), "#1");
+}
+
+#[test]
+fn test_takes_hanging_queen() {
+    // The knight on d4 can take the queen on e6
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[3][3] = Some(Piece::Knight(Color::White));
+    setup.grid[7][4] = Some(Piece::King(Color::Black));
+    setup.grid[5][4] = Some(Piece::Queen(Color::Black));
+    setup.grid[6][0] = Some(Piece::Pawn(Color::Black));
+    let game = Game::from_setup(&setup).unwrap();
+
+    let line = best_line(&game, 2).unwrap();
+    assert_eq!(line.moves[0], Move::new(Square::D4, Square::E6), "Nxe6");
+    assert!(matches!(line.score, LineScore::Centipawns(score) if score > 0));
+}
+
+#[test]
+fn test_multi_pv() {
+    let game = Game::new();
+    let mut results = Vec::new();
+    analyze(&game, 2, 3, &AtomicBool::new(false), |lines| results.push(lines.to_vec()));
+
+    assert_eq!(results.len(), 2, "One report per depth");
+    let lines = &results[1];
+    assert_eq!(lines.len(), 3);
+    assert!(lines.iter().all(|line| line.depth == 2 && line.moves.len() == 2 && line.san.len() == 2));
+    assert_ne!(lines[0].moves[0], lines[1].moves[0], "Each line starts with another move");
+
+    // A stopped search reports nothing
+    let mut reports = 0;
+    analyze(&game, 5, 3, &AtomicBool::new(true), |_| reports += 1);
+    assert_eq!(reports, 0);
+}
@@ -0,0 +1,88 @@
+use super::{Setup, SetupError};
+use crate::core_struct::{Board, Color, Piece};
+use crate::{File, Square};
+
+fn validate(setup: &Setup) -> Result<(), SetupError> {
+    Board::from_grid(setup.grid).validate_setup(setup)
+}
+
+/// Kings on e1 and e8, white to move, no castling
+fn kings_only() -> Setup {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][4] = Some(Piece::King(Color::Black));
+    setup
+}
+
+#[test]
+fn test_starting_position_is_valid() {
+    assert_eq!(validate(&Setup::default()), Ok(()));
+    assert_eq!(validate(&kings_only()), Ok(()));
+}
+
+#[test]
+fn test_king_count() {
+    assert_eq!(validate(&Setup::empty()), Err(SetupError::KingCount(Color::White, 0)));
+
+    let mut setup = kings_only();
+    setup.grid[3][3] = Some(Piece::King(Color::Black));
+    assert_eq!(validate(&setup), Err(SetupError::KingCount(Color::Black, 2)));
+}
+
+#[test]
+fn test_pawn_on_back_rank() {
+    let mut setup = kings_only();
+    setup.grid[7][0] = Some(Piece::Pawn(Color::White));
+    assert_eq!(validate(&setup), Err(SetupError::PawnOnBackRank(Square::A8)));
+
+    let mut setup = kings_only();
+    setup.grid[0][7] = Some(Piece::Pawn(Color::Black));
+    assert_eq!(validate(&setup), Err(SetupError::PawnOnBackRank(Square::H1)));
+}
+
+#[test]
+fn test_opponent_in_check() {
+    let mut setup = kings_only();
+    setup.grid[3][4] = Some(Piece::Rook(Color::White)); // White rook on e4 checks the black king
+
+    assert_eq!(validate(&setup), Err(SetupError::OpponentInCheck), "Black is in check with white to move");
+
+    setup.turn = Color::Black;
+    assert_eq!(validate(&setup), Ok(()), "Black is in check with black to move");
+}
+
+#[test]
+fn test_castling_rights() {
+    let mut setup = kings_only();
+    setup.white_castle_king_side = true;
+    assert_eq!(validate(&setup), Err(SetupError::InvalidCastlingRights(Color::White)), "No rook on h1");
+
+    setup.grid[0][7] = Some(Piece::Rook(Color::White));
+    assert_eq!(validate(&setup), Ok(()));
+
+    setup.black_castle_queen_side = true;
+    setup.grid[7][0] = Some(Piece::Rook(Color::Black));
+    setup.grid[7][4] = None;
+    setup.grid[7][3] = Some(Piece::King(Color::Black));
+    assert_eq!(validate(&setup), Err(SetupError::InvalidCastlingRights(Color::Black)), "Black king not on e8");
+}
+
+#[test]
+fn test_en_passant() {
+    let mut setup = kings_only();
+    setup.turn = Color::Black;
+    setup.grid[3][3] = Some(Piece::Pawn(Color::White)); // White pawn on d4
+    setup.en_passant = Some(File::D);
+    assert_eq!(validate(&setup), Ok(()));
+
+    setup.en_passant = Some(File::C);
+    assert_eq!(validate(&setup), Err(SetupError::InvalidEnPassant(File::C)), "No pawn on c4");
+
+    setup.en_passant = Some(File::D);
+    setup.grid[1][3] = Some(Piece::Queen(Color::White));
+    assert_eq!(validate(&setup), Err(SetupError::InvalidEnPassant(File::D)), "The pawn couldn't have come from d2");
+
+    setup.grid[1][3] = None;
+    setup.turn = Color::White;
+    assert_eq!(validate(&setup), Err(SetupError::InvalidEnPassant(File::D)), "White can't take its own pawn");
+}
@@ -0,0 +1,164 @@
+use crate::core_struct::{Color, Piece, PieceKind};
+use crate::{DrawReason, EndgameStatus, File, Game, Move, PromotionPiece, Setup, Square, Variant, WinReason};
+
+fn round_trip(game: &Game) -> Game {
+    let json = serde_json::to_string(game).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn test_snapshot_keeps_position_and_history() {
+    let mut game = Game::new();
+    for mv in [Move::new(Square::E2, Square::E4), Move::new(Square::A7, Square::A6), Move::new(Square::E4, Square::E5), Move::new(Square::D7, Square::D5)] {
+        game.play(mv).unwrap();
+    }
+    game.play(Move::new(Square::E1, Square::E2)).unwrap(); // Ke2, white can't castle any more
+
+    let restored = round_trip(&game);
+    assert_eq!(restored.to_fen(), game.to_fen());
+    assert_eq!(restored.history(), game.history());
+    assert_eq!(restored.turn(), Color::Black);
+
+    // The moves can still be taken back, back to the en passant of the previous position
+    let mut restored = restored;
+    restored.undo();
+    assert!(restored.all_legal_moves().contains(&Move::new(Square::E5, Square::D6)), "exd6 en passant");
+    while restored.undo().is_some() {}
+    assert_eq!(restored.to_fen(), Game::new().to_fen());
+}
+
+#[test]
+fn test_snapshot_keeps_repetitions() {
+    let mut game = Game::new();
+    let knights = [Move::new(Square::G1, Square::F3), Move::new(Square::G8, Square::F6), Move::new(Square::F3, Square::G1), Move::new(Square::F6, Square::G8)];
+    for mv in knights {
+        game.play(mv).unwrap();
+    }
+    assert!(game.evaluate_endgame().is_ongoing(), "The starting position was seen twice");
+
+    let mut restored = round_trip(&game);
+    for mv in knights {
+        restored.play(mv).unwrap();
+    }
+    assert!(restored.evaluate_endgame().is_ongoing(), "Threefold repetition has to be claimed");
+    assert_eq!(restored.can_claim_draw(), Some(DrawReason::ThreefoldRepetition), "The earlier repetitions count");
+}
+
+#[test]
+fn test_snapshot_keeps_pending_promotion_and_end() {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][0] = Some(Piece::King(Color::Black));
+    setup.grid[6][7] = Some(Piece::Pawn(Color::White));
+    let mut game = Game::from_setup(&setup).unwrap();
+    game.play_move(Square::H7, Square::H8).unwrap();
+
+    let mut restored = round_trip(&game);
+    assert_eq!(restored.is_promotion_pending(), Some((File::H, Color::White)));
+    restored.promote_pawn(PromotionPiece::Rook).unwrap();
+    assert_eq!(restored.piece_at(Square::H8), Some(Piece::Rook(Color::White)));
+
+    restored.end_game(EndgameStatus::Win(Color::Black, WinReason::Resignation));
+    assert_eq!(round_trip(&restored).endgame_status(), EndgameStatus::Win(Color::Black, WinReason::Resignation));
+}
+
+#[test]
+fn test_snapshot_keeps_pockets() {
+    let mut game = Game::with_variant(Variant::Crazyhouse);
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.play_move(Square::D7, Square::D5).unwrap();
+    game.play_move(Square::E4, Square::D5).unwrap(); // exd5, a pawn in white's pocket
+    game.add_to_pocket(Piece::Knight(Color::Black)).unwrap();
+
+    let mut restored = round_trip(&game);
+    assert_eq!(restored.variant(), Variant::Crazyhouse);
+    assert_eq!(restored.pocket(Color::White).count(PieceKind::Pawn), 1);
+    assert_eq!(restored.pocket(Color::Black).count(PieceKind::Knight), 1);
+    assert!(restored.drop_piece(PieceKind::Knight, Square::F6).is_ok());
+}
+
+#[test]
+fn test_snapshot_keeps_pieces_received() {
+    let mut game = Game::with_variant(Variant::Bughouse);
+    game.add_to_pocket(Piece::Rook(Color::White)).unwrap();
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.add_to_pocket(Piece::Knight(Color::Black)).unwrap();
+    game.play_move(Square::E7, Square::E5).unwrap();
+
+    let mut restored = round_trip(&game);
+    assert_eq!(restored.variant(), Variant::Bughouse);
+    assert_eq!(restored.to_fen(), game.to_fen());
+    restored.undo();
+    assert_eq!(restored.pocket(Color::Black).count(PieceKind::Knight), 1);
+    assert!(restored.drop_piece(PieceKind::Knight, Square::F6).is_ok(), "The knight can be dropped after it arrived");
+    restored.undo();
+    restored.undo();
+    assert_eq!(restored.pocket(Color::Black).count(PieceKind::Knight), 0, "The knight arrived after e4");
+    assert_eq!(restored.pocket(Color::White).count(PieceKind::Rook), 1);
+}
+
+#[test]
+fn test_snapshot_schema() {
+    let mut game = Game::with_variant(Variant::Crazyhouse);
+    for mv in ["e2e4", "d7d5", "e4d5", "d8d5", "P@e4"] {
+        game.play(mv.parse().unwrap()).unwrap();
+    }
+    game.end_game(EndgameStatus::Win(Color::White, WinReason::Resignation));
+
+    let json = serde_json::to_value(&game).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "version": crate::SNAPSHOT_VERSION,
+            "variant": "Crazyhouse",
+            "fen": "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+            "moves": ["e2e4", "d7d5", "e4d5", "d8d5", "P@e4"],
+            "received": [],
+            "result": { "Win": ["White", "Resignation"] },
+        })
+    );
+    assert_eq!(serde_json::from_value::<Game>(json).unwrap().to_fen(), game.to_fen());
+}
+
+#[test]
+fn test_snapshot_size() {
+    let mut game = Game::new();
+    let knights = [Move::new(Square::G1, Square::F3), Move::new(Square::G8, Square::F6), Move::new(Square::F3, Square::G1), Move::new(Square::F6, Square::G8)];
+    for mv in knights.iter().cycle().take(120) {
+        game.play(*mv).unwrap();
+    }
+    assert!(serde_json::to_string(&game).unwrap().len() < 1000, "About 7 bytes per move");
+}
+
+#[test]
+fn test_snapshot_version() {
+    let mut json: serde_json::Value = serde_json::to_value(Game::new()).unwrap();
+    assert_eq!(json["version"], crate::SNAPSHOT_VERSION);
+    json["version"] = serde_json::json!(crate::SNAPSHOT_VERSION + 1);
+    assert!(serde_json::from_value::<Game>(json).is_err(), "A newer format is refused");
+}
+
+#[test]
+fn test_snapshot_is_checked() {
+    let json = serde_json::to_value(Game::new()).unwrap();
+
+    let mut no_king = json.clone();
+    no_king["fen"] = serde_json::json!("rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert!(serde_json::from_value::<Game>(no_king).is_err(), "Black has no king");
+
+    let mut illegal = json.clone();
+    illegal["moves"] = serde_json::json!(["e2e4", "e7e5", "e4e5"]);
+    assert!(serde_json::from_value::<Game>(illegal).is_err(), "The pawn is blocked");
+
+    let mut unreadable = json.clone();
+    unreadable["moves"] = serde_json::json!(["e2e9"]);
+    assert!(serde_json::from_value::<Game>(unreadable).is_err());
+
+    let mut promotion = serde_json::to_value(Game::from_setup(&Setup::from_fen("k7/7P/8/8/8/8/8/K7 w - - 0 1").unwrap()).unwrap()).unwrap();
+    promotion["moves"] = serde_json::json!(["h7h8", "a8b8"]);
+    assert!(serde_json::from_value::<Game>(promotion).is_err(), "Only the last move can wait for its promotion");
+
+    let mut received = json;
+    received["received"] = serde_json::json!([[0, { "Pawn": "White" }]]);
+    assert!(serde_json::from_value::<Game>(received).is_err(), "Standard chess has no pockets");
+}
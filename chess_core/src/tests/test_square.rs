@@ -0,0 +1,40 @@
+use super::{File, Rank, Square};
+use crate::core_struct::Color;
+use crate::errors::ParseError;
+
+#[test]
+fn test_square_names() {
+    assert_eq!("e4".parse(), Ok(Square::E4));
+    assert_eq!(Square::new(File::A, Rank::Eighth), Square::A8);
+    assert_eq!(Square::H1.to_string(), "h1");
+    for name in ["", "e", "e9", "i1", "e44", "E4"] {
+        assert_eq!(name.parse::<Square>(), Err(ParseError::InvalidSquare), "{}", name);
+    }
+    assert_eq!(serde_json::to_string(&Square::G7).unwrap(), "\"g7\"");
+    assert_eq!(serde_json::from_str::<Square>("\"c3\"").unwrap(), Square::C3);
+    assert!(serde_json::from_str::<Square>("\"z0\"").is_err());
+}
+
+#[test]
+fn test_square_helpers() {
+    let squares: Vec<Square> = Square::all().collect();
+    assert_eq!(squares.len(), 64);
+    assert_eq!((squares[0], squares[8], squares[63]), (Square::A1, Square::A2, Square::H8));
+
+    assert_eq!(Square::E4.offset(1, 2), Some(Square::F6));
+    assert_eq!(Square::B1.offset(-2, 1), None);
+    assert_eq!(Square::H8.offset(0, 1), None);
+
+    assert_eq!(Square::A1.distance(Square::H8), 7);
+    assert_eq!(Square::E4.distance(Square::C5), 2);
+    assert_eq!(Square::E4.distance(Square::E4), 0);
+
+    assert_eq!(Square::A1.color(), Color::Black);
+    assert_eq!(Square::H1.color(), Color::White);
+    assert_eq!(Square::D1.color(), Color::White, "The queen starts on her color");
+    assert_eq!(Square::D8.color(), Color::Black);
+
+    assert_eq!(File::from_char('c').map(File::index), Some(2));
+    assert_eq!(Rank::new(8), None);
+    assert_eq!(Rank::back_rank(Color::Black), Rank::Eighth);
+}
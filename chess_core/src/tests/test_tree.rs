@@ -0,0 +1,92 @@
+use super::{GameTree, TreeToken};
+use crate::{Game, Move};
+
+fn mv(notation: &str) -> Move {
+    notation.parse().unwrap()
+}
+
+/// 1.e4 e5 2.Nf3 (2.Nc3 Nf6) 2...Nc6, at the end of the main line
+fn tree_with_variation() -> GameTree {
+    let mut tree = GameTree::new(Game::new());
+    for notation in ["e2e4", "e7e5", "g1f3", "b8c6"] {
+        tree.add_move(mv(notation)).unwrap();
+    }
+    let e5 = tree.mainline()[1];
+    tree.go_to(e5).unwrap();
+    tree.add_move(mv("b1c3")).unwrap();
+    tree.add_move(mv("g8f6")).unwrap();
+    tree.go_to(tree.mainline()[3]).unwrap();
+    tree
+}
+
+#[test]
+fn test_add_variation() {
+    let tree = tree_with_variation();
+    assert_eq!(tree.to_movetext(), "1.e4 e5 2.Nf3 (2.Nc3 Nf6) 2...Nc6");
+    assert_eq!(tree.game().history(), &[mv("e2e4"), mv("e7e5"), mv("g1f3"), mv("b8c6")]);
+
+    let e5 = tree.node(tree.mainline()[1]).unwrap();
+    assert_eq!(e5.children().len(), 2);
+    assert_eq!(e5.san(), "e5");
+    assert_eq!(e5.number(), 1);
+}
+
+#[test]
+fn test_navigation() {
+    let mut tree = tree_with_variation();
+    let nc3 = tree.node(tree.mainline()[1]).unwrap().children()[1];
+    let nf6 = tree.node(nc3).unwrap().children()[0];
+
+    tree.go_to(nf6).unwrap();
+    assert_eq!(tree.game().history(), &[mv("e2e4"), mv("e7e5"), mv("b1c3"), mv("g8f6")]);
+    assert_eq!(tree.game().to_fen(), "rnbqkb1r/pppp1ppp/5n2/4p3/4P3/2N5/PPPP1PPP/R1BQKBNR w KQkq - 2 3");
+
+    assert!(tree.go_back());
+    assert_eq!(tree.current(), nc3);
+    // Playing a move that is already in the tree follows it
+    assert_eq!(tree.add_move(mv("g8f6")).unwrap(), nf6);
+
+    let game = tree.game_at(tree.mainline()[2]).unwrap();
+    assert_eq!(game.history_to_san(), ["e4", "e5", "Nf3"]);
+    assert_eq!(tree.current(), nf6, "Looking at a node doesn't move the tree");
+
+    tree.go_to(tree.root()).unwrap();
+    assert!(!tree.go_back());
+    assert!(tree.go_forward());
+    assert_eq!(tree.game().history(), &[mv("e2e4")]);
+}
+
+#[test]
+fn test_promote_and_delete() {
+    let mut tree = tree_with_variation();
+    let nc3 = tree.node(tree.mainline()[1]).unwrap().children()[1];
+    let nf6 = tree.node(nc3).unwrap().children()[0];
+
+    tree.promote_to_mainline(nf6).unwrap();
+    assert_eq!(tree.to_movetext(), "1.e4 e5 2.Nc3 (2.Nf3 Nc6) 2...Nf6");
+    assert_eq!(tree.mainline().len(), 4);
+
+    // The game is on 2...Nc6, which is deleted with 2.Nf3
+    let nf3 = tree.node(tree.mainline()[1]).unwrap().children()[1];
+    tree.delete(nf3).unwrap();
+    assert_eq!(tree.to_movetext(), "1.e4 e5 2.Nc3 Nf6");
+    assert_eq!(tree.game().history(), &[mv("e2e4"), mv("e7e5")]);
+    assert!(tree.node(nf3).is_none());
+    assert!(tree.go_to(nf3).is_err());
+    assert!(tree.delete(tree.root()).is_err());
+}
+
+#[test]
+fn test_comments_and_nags() {
+    let mut tree = tree_with_variation();
+    let e4 = tree.mainline()[0];
+    let nc3 = tree.node(tree.mainline()[1]).unwrap().children()[1];
+    tree.set_comment(e4, "Best by test").unwrap();
+    tree.add_nag(e4, 1).unwrap();
+    tree.add_nag(nc3, 5).unwrap();
+    assert_eq!(tree.to_movetext(), "1.e4! {Best by test} 1...e5 2.Nf3 (2.Nc3!? Nf6) 2...Nc6");
+
+    tree.remove_nag(e4, 1).unwrap();
+    assert_eq!(tree.tokens()[0], TreeToken::Move(e4, "1.e4".to_string()));
+    assert!(tree.add_move(mv("e2e4")).is_err(), "Illegal move");
+}
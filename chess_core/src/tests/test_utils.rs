@@ -1,10 +1,11 @@
-use crate::utils::BiRange;
+use crate::utils::{parse_move, BiRange};
+use crate::{ParseError, Square};
 
 #[test]
 fn test_birange() {
-    assert_eq!(BiRange::new(0,0).collect::<Vec<usize>>(), vec![]);
-    assert_eq!(BiRange::new(0,1).collect::<Vec<usize>>(), vec![]);
-    assert_eq!(BiRange::new(1,0).collect::<Vec<usize>>(), vec![]);
+    assert_eq!(BiRange::new(0,0).collect::<Vec<usize>>(), Vec::<usize>::new());
+    assert_eq!(BiRange::new(0,1).collect::<Vec<usize>>(), Vec::<usize>::new());
+    assert_eq!(BiRange::new(1,0).collect::<Vec<usize>>(), Vec::<usize>::new());
     assert_eq!(BiRange::new(0,2).collect::<Vec<usize>>(), vec![1]);
     assert_eq!(BiRange::new(2,0).collect::<Vec<usize>>(), vec![1]);
     assert_eq!(BiRange::new(3,8).collect::<Vec<usize>>(), vec![4, 5, 6, 7]);
@@ -12,3 +13,13 @@ fn test_birange() {
     assert_eq!(BiRange::new(8,0).collect::<Vec<usize>>(), vec![7, 6, 5, 4, 3, 2, 1]);
     assert_eq!(BiRange::new(8,3).collect::<Vec<usize>>(), vec![7, 6, 5, 4]);
 }
+
+#[test]
+fn test_parse_move() {
+    assert_eq!(parse_move("e2 e4"), Ok((Square::E2, Square::E4)));
+    assert_eq!(parse_move(" g8 f6\n"), Ok((Square::G8, Square::F6)));
+    assert_eq!(parse_move("e2e4"), Err(ParseError::ExpectedTwoSquares));
+    assert_eq!(parse_move("E2 e4"), Err(ParseError::InvalidSquare));
+    assert_eq!(parse_move("e0 e9"), Err(ParseError::InvalidSquare));
+    assert_eq!(parse_move("é2 e4"), Err(ParseError::InvalidSquare));
+}
@@ -0,0 +1,373 @@
+use std::fmt;
+
+use crate::core_struct::Color;
+use crate::errors::MoveError;
+use crate::game::Game;
+use crate::moves::Move;
+
+/// Index of a node in a game tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// Why a change to a game tree is refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeError {
+    /// The node doesn't exist, or was deleted
+    NoSuchNode,
+    RootNotDeletable,
+    Move(MoveError),
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::NoSuchNode => write!(f, "No such node"),
+            TreeError::RootNotDeletable => write!(f, "The root can't be deleted"),
+            TreeError::Move(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl From<MoveError> for TreeError {
+    fn from(e: MoveError) -> TreeError {
+        TreeError::Move(e)
+    }
+}
+
+/// A move of the tree, with the position it was played from described by its number and color
+#[derive(Debug, Clone)]
+pub struct Node {
+    mv: Option<Move>, // None for the root
+    san: String,
+    number: usize,
+    color: Color,
+    comment: String,
+    nags: Vec<u8>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>, // The first child continues the main line, the others are variations
+}
+
+impl Node {
+    pub fn mv(&self) -> Option<Move> {
+        self.mv
+    }
+
+    pub fn san(&self) -> &str {
+        &self.san
+    }
+
+    /// Number of the move, e.g. 14 for both 14.Nf3 and 14...Nc6
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Color that played the move
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// Numeric annotation glyphs, e.g. 1 for ! and 4 for ??
+    pub fn nags(&self) -> &[u8] {
+        &self.nags
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+}
+
+/// Piece of the move text of a tree, in the order of PGN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeToken {
+    /// A move with its number when it needs one and its annotations, e.g. 14...Nc6?!
+    Move(NodeId, String),
+    Comment(NodeId, String),
+    VariationStart,
+    VariationEnd,
+}
+
+/// A game with variations. The game is kept at the current node, moving to another node
+/// takes back the moves up to their common ancestor and plays the moves down to it
+#[derive(Debug, Clone)]
+pub struct GameTree {
+    nodes: Vec<Option<Node>>, // Deleted nodes leave None
+    path: Vec<NodeId>, // From the root to the current node
+    game: Game,
+}
+
+impl GameTree {
+    /// A tree starting at the current position of the game
+    pub fn new(game: Game) -> GameTree {
+        let root = Node {
+            mv: None,
+            san: String::new(),
+            number: game.fullmove_number(),
+            color: game.turn().opposite(),
+            comment: String::new(),
+            nags: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+        };
+        GameTree { nodes: vec![Some(root)], path: vec![NodeId(0)], game }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn current(&self) -> NodeId {
+        self.path[self.path.len() - 1]
+    }
+
+    /// The game at the current node
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(id.0).and_then(|node| node.as_ref())
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut Node, TreeError> {
+        self.nodes.get_mut(id.0).and_then(|node| node.as_mut()).ok_or(TreeError::NoSuchNode)
+    }
+
+    /// Plays a move from the current node. A new move starts a variation when the node already has a continuation,
+    /// a move that was already played there is followed instead
+    pub fn add_move(&mut self, mv: Move) -> Result<NodeId, TreeError> {
+        let current = self.current();
+        let node = self.node(current).ok_or(TreeError::NoSuchNode)?;
+        let existing = node.children.iter().copied().find(|&child| self.node(child).and_then(|node| node.mv) == Some(mv));
+        if let Some(child) = existing {
+            self.go_to(child)?;
+            return Ok(child);
+        }
+
+        let san = self.game.to_san(mv)?;
+        let node = Node {
+            mv: Some(mv),
+            san,
+            number: self.game.fullmove_number(),
+            color: self.game.turn(),
+            comment: String::new(),
+            nags: Vec::new(),
+            parent: Some(current),
+            children: Vec::new(),
+        };
+        // to_san only accepts legal moves
+        self.game.play(mv).unwrap();
+        self.game.evaluate_endgame();
+
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Some(node));
+        self.node_mut(current)?.children.push(id);
+        self.path.push(id);
+        Ok(id)
+    }
+
+    /// Moves the game to the position of a node
+    pub fn go_to(&mut self, id: NodeId) -> Result<(), TreeError> {
+        let mut target = Vec::new();
+        let mut node = Some(id);
+        while let Some(id) = node {
+            target.push(id);
+            node = self.node(id).ok_or(TreeError::NoSuchNode)?.parent;
+        }
+        target.reverse();
+
+        let common = self.path.iter().zip(&target).take_while(|(a, b)| a == b).count();
+        while self.path.len() > common {
+            self.game.undo();
+            self.path.pop();
+        }
+        for &id in &target[common..] {
+            // The moves of the tree were legal when they were added
+            let mv = self.nodes[id.0].as_ref().and_then(|node| node.mv).unwrap();
+            self.game.play(mv).unwrap();
+            self.game.evaluate_endgame();
+            self.path.push(id);
+        }
+        Ok(())
+    }
+
+    /// A copy of the game at a node, without moving the tree
+    pub fn game_at(&self, id: NodeId) -> Result<Game, TreeError> {
+        let mut tree = self.clone();
+        tree.go_to(id)?;
+        Ok(tree.game)
+    }
+
+    /// Goes to the parent of the current node, false at the root
+    pub fn go_back(&mut self) -> bool {
+        match self.node(self.current()).and_then(|node| node.parent) {
+            Some(parent) => self.go_to(parent).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Follows the main continuation of the current node, false at the end of a line
+    pub fn go_forward(&mut self) -> bool {
+        match self.node(self.current()).and_then(|node| node.children.first().copied()) {
+            Some(child) => self.go_to(child).is_ok(),
+            None => false,
+        }
+    }
+
+    /// The nodes of the main line, without the root
+    pub fn mainline(&self) -> Vec<NodeId> {
+        let mut line = Vec::new();
+        let mut node = self.root();
+        while let Some(&child) = self.node(node).and_then(|node| node.children.first()) {
+            line.push(child);
+            node = child;
+        }
+        line
+    }
+
+    /// Makes the line of a node the main line, at every branch between it and the root
+    pub fn promote_to_mainline(&mut self, id: NodeId) -> Result<(), TreeError> {
+        let mut child = id;
+        while let Some(parent) = self.node(child).ok_or(TreeError::NoSuchNode)?.parent {
+            let children = &mut self.node_mut(parent)?.children;
+            if let Some(index) = children.iter().position(|&other| other == child) {
+                let promoted = children.remove(index);
+                children.insert(0, promoted);
+            }
+            child = parent;
+        }
+        Ok(())
+    }
+
+    /// Deletes a node and every move after it. The game goes back to its parent if it was in the deleted moves
+    pub fn delete(&mut self, id: NodeId) -> Result<(), TreeError> {
+        let parent = self.node(id).ok_or(TreeError::NoSuchNode)?.parent.ok_or(TreeError::RootNotDeletable)?;
+        if self.path.contains(&id) {
+            self.go_to(parent)?;
+        }
+        self.node_mut(parent)?.children.retain(|&child| child != id);
+
+        let mut deleted = vec![id];
+        while let Some(id) = deleted.pop() {
+            if let Some(node) = self.nodes[id.0].take() {
+                deleted.extend(node.children);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_comment(&mut self, id: NodeId, comment: &str) -> Result<(), TreeError> {
+        self.node_mut(id)?.comment = comment.to_string();
+        Ok(())
+    }
+
+    pub fn add_nag(&mut self, id: NodeId, nag: u8) -> Result<(), TreeError> {
+        let node = self.node_mut(id)?;
+        if !node.nags.contains(&nag) {
+            node.nags.push(nag);
+        }
+        Ok(())
+    }
+
+    pub fn remove_nag(&mut self, id: NodeId, nag: u8) -> Result<(), TreeError> {
+        self.node_mut(id)?.nags.retain(|&other| other != nag);
+        Ok(())
+    }
+
+    /// The move text of the whole tree: every move followed by its comment, then by the variations that replace it
+    pub fn tokens(&self) -> Vec<TreeToken> {
+        let mut tokens = Vec::new();
+        self.line_tokens(self.root(), true, &mut tokens);
+        tokens
+    }
+
+    /// Tokens of the moves after a node, a black move needs its number when it starts a line or follows a comment
+    fn line_tokens(&self, mut id: NodeId, mut needs_number: bool, tokens: &mut Vec<TreeToken>) {
+        while let Some(node) = self.node(id) {
+            let main = match node.children.first() {
+                Some(&main) => main,
+                None => return,
+            };
+            needs_number = self.move_tokens(main, needs_number, tokens);
+
+            for &variation in &node.children[1..] {
+                tokens.push(TreeToken::VariationStart);
+                let after_comment = self.move_tokens(variation, true, tokens);
+                self.line_tokens(variation, after_comment, tokens);
+                tokens.push(TreeToken::VariationEnd);
+                needs_number = true;
+            }
+            id = main;
+        }
+    }
+
+    /// Pushes the move and its comment, returns whether the next move needs its number
+    fn move_tokens(&self, id: NodeId, needs_number: bool, tokens: &mut Vec<TreeToken>) -> bool {
+        let node = match self.node(id) {
+            Some(node) => node,
+            None => return needs_number,
+        };
+        let mut text = match node.color {
+            Color::White => format!("{}.", node.number),
+            Color::Black if needs_number => format!("{}...", node.number),
+            Color::Black => String::new(),
+        };
+        text.push_str(&node.san);
+        for &nag in &node.nags {
+            text.push_str(&nag_text(nag));
+        }
+        tokens.push(TreeToken::Move(id, text));
+
+        if node.comment.is_empty() {
+            return false;
+        }
+        tokens.push(TreeToken::Comment(id, node.comment.clone()));
+        true
+    }
+
+    /// The tree in PGN move text, variations in parentheses and comments in braces
+    pub fn to_movetext(&self) -> String {
+        let mut text = String::new();
+        let mut after_start = true;
+        for token in self.tokens() {
+            let (word, separated) = match token {
+                TreeToken::Move(_, san) => (san, true),
+                TreeToken::Comment(_, comment) => (format!("{{{}}}", comment), true),
+                TreeToken::VariationStart => ("(".to_string(), true),
+                TreeToken::VariationEnd => (")".to_string(), false),
+            };
+            if separated && !after_start {
+                text.push(' ');
+            }
+            after_start = word == "(";
+            text.push_str(&word);
+        }
+        text
+    }
+}
+
+/// The symbol of the common annotations, $n for the others
+fn nag_text(nag: u8) -> String {
+    match nag {
+        1 => "!".to_string(),
+        2 => "?".to_string(),
+        3 => "!!".to_string(),
+        4 => "??".to_string(),
+        5 => "!?".to_string(),
+        6 => "?!".to_string(),
+        nag => format!(" ${}", nag),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_tree.rs"]
+mod test_tree;
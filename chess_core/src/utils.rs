@@ -1,3 +1,6 @@
+use crate::errors::ParseError;
+use crate::square::Square;
+
 pub struct BiRange {
     start: usize,
     end: usize,
@@ -34,31 +37,15 @@ impl Iterator for BiRange {
 }
 
 
-pub fn parse_move(input: &str) -> Result<((usize, usize), (usize, usize)), &'static str> {
+/// Reads a move typed as its two squares, e.g. "e2 e4"
+pub fn parse_move(input: &str) -> Result<(Square, Square), ParseError> {
     let input: Vec<&str> = input.trim().split(" ").collect();
     if input.len() != 2 {
-        return Err("Invalid input");
-    }
-
-    let start = input[0];
-    let end = input[1];
-
-    if start.len() != 2 || end.len() != 2 {
-        return Err("Invalid input");
+        return Err(ParseError::ExpectedTwoSquares);
     }
 
-    let start: Vec<char> = start.chars().collect();
-    let end: Vec<char> = end.chars().collect();
-
-    let start = (
-        start[1].to_digit(10).ok_or("Invalid input")? as usize - 1,
-        start[0] as usize - 'a' as usize,
-    );
-
-    let end = (
-        end[1].to_digit(10).ok_or("Invalid input")? as usize - 1,
-        end[0] as usize - 'a' as usize,
-    );
+    let start = input[0].parse()?;
+    let end = input[1].parse()?;
 
     Ok((start, end))
 }
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core_struct::PieceKind;
+use crate::errors::MoveError;
+
+/// The set of rules a game is played with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    Standard,
+    /// Captured pieces change color and go to the pocket of the player who took them
+    Crazyhouse,
+    /// Captured pieces go to the partner on the other board, who receives them with `Game::add_to_pocket`
+    Bughouse,
+}
+
+impl Variant {
+    /// Whether pieces can be dropped from a pocket in this variant
+    pub fn has_drops(&self) -> bool {
+        matches!(self, Variant::Crazyhouse | Variant::Bughouse)
+    }
+}
+
+/// The pieces a player holds in hand and can drop on the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Pocket {
+    counts: [u8; 5], // pawn, knight, bishop, rook, queen
+}
+
+impl Pocket {
+    /// The kinds of pieces that can be in a pocket, in the order they are displayed
+    pub const KINDS: [PieceKind; 5] = [
+        PieceKind::Pawn,
+        PieceKind::Knight,
+        PieceKind::Bishop,
+        PieceKind::Rook,
+        PieceKind::Queen,
+    ];
+
+    fn index(kind: PieceKind) -> Option<usize> {
+        match kind {
+            PieceKind::Pawn => Some(0),
+            PieceKind::Knight => Some(1),
+            PieceKind::Bishop => Some(2),
+            PieceKind::Rook => Some(3),
+            PieceKind::Queen => Some(4),
+            PieceKind::King => None, // A king is never captured
+        }
+    }
+
+    /// Returns how many pieces of that kind are in the pocket
+    pub fn count(&self, kind: PieceKind) -> u8 {
+        Pocket::index(kind).map_or(0, |i| self.counts[i])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|&count| count == 0)
+    }
+
+    pub(crate) fn add(&mut self, kind: PieceKind) -> Result<(), MoveError> {
+        let i = Pocket::index(kind).ok_or(MoveError::KingInPocket)?;
+        self.counts[i] = self.counts[i].checked_add(1).ok_or(MoveError::PocketFull(kind))?;
+        Ok(())
+    }
+
+    /// Returns false if there was no such piece in the pocket
+    pub(crate) fn remove(&mut self, kind: PieceKind) -> bool {
+        match Pocket::index(kind) {
+            Some(i) if self.counts[i] > 0 => {
+                self.counts[i] -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Positions drawn to PNG images and games to animated GIFs, without a window, with the piece images of the GUIs
+
+use std::fs;
+use std::path::Path;
+
+use chess_core::{Color, Game, Move, Piece, PieceKind, Square};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::{self, FilterType};
+use image::{Delay, Frame, Pixel, Rgba, RgbaImage};
+
+const LAST_MOVE_COLOR: Rgba<u8> = Rgba([230, 217, 51, 115]);
+const CHECK_COLOR: Rgba<u8> = Rgba([230, 26, 26, 140]);
+const HIGHLIGHT_COLOR: Rgba<u8> = Rgba([51, 102, 230, 115]);
+const ARROW_COLOR: Rgba<u8> = Rgba([26, 153, 51, 204]);
+const BROWN_SQUARES: (Rgba<u8>, Rgba<u8>) = (Rgba([239, 216, 181, 255]), Rgba([181, 135, 99, 255]));
+
+/// Colors of the board: the board image of the piece directory, or squares drawn in two colors like the GUIs' themes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Classic,
+    Brown,
+    Green,
+    Blue,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Classic, Theme::Brown, Theme::Green, Theme::Blue];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Classic => "classic",
+            Theme::Brown => "brown",
+            Theme::Green => "green",
+            Theme::Blue => "blue",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        Theme::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    /// Colors of the light and the dark squares. None for the board image
+    pub fn colors(self) -> Option<(Rgba<u8>, Rgba<u8>)> {
+        match self {
+            Theme::Classic => None,
+            Theme::Brown => Some(BROWN_SQUARES),
+            Theme::Green => Some((Rgba([237, 237, 209, 255]), Rgba([117, 150, 86, 255]))),
+            Theme::Blue => Some((Rgba([221, 226, 229, 255]), Rgba([140, 163, 173, 255]))),
+        }
+    }
+}
+
+/// How a position is drawn, and what is drawn over it
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub square_size: u32, // In pixels
+    pub orientation: Color, // Color at the bottom
+    pub theme: Theme, // The classic theme needs the board image of the piece directory
+    pub highlights: Vec<Square>,
+    pub arrows: Vec<(Square, Square)>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> ExportOptions {
+        ExportOptions {
+            square_size: 64,
+            orientation: Color::White,
+            theme: Theme::Brown,
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+        }
+    }
+}
+
+/// The piece set read from its directory, the same PNG images as in the GUIs, and the board image if there is one
+pub struct PieceImages {
+    pieces: Vec<(Piece, RgbaImage)>,
+    board: Option<BoardImage>,
+}
+
+/// The board image with the width in pixels of the border around its squares
+struct BoardImage {
+    image: RgbaImage,
+    border: u32,
+}
+
+impl BoardImage {
+    /// Reads 8x8-board.png, and its border from 8x8-board.txt: name=value lines like border=8. Without it the image is only squares
+    fn load(directory: &Path) -> Result<Option<BoardImage>, String> {
+        let Ok(image) = image::open(directory.join("8x8-board.png")) else {
+            return Ok(None);
+        };
+        let image = image.to_rgba8();
+        let metadata = directory.join("8x8-board.txt");
+        let content = fs::read_to_string(&metadata).unwrap_or_default();
+        let mut border = 0;
+        for line in content.lines() {
+            if let Some(("border", value)) = line.split_once('=').map(|(name, value)| (name.trim(), value.trim())) {
+                border = value.parse().map_err(|_| format!("{}: the border is a number of pixels", metadata.display()))?;
+            }
+        }
+        if border * 2 >= image.width().min(image.height()) {
+            return Err(format!("{}: the border is wider than the board image", metadata.display()));
+        }
+        Ok(Some(BoardImage { image, border }))
+    }
+
+    /// The squares without the border, resized to a board of squares of that size
+    fn squares(&self, square_size: u32) -> RgbaImage {
+        let (width, height) = (self.image.width() - 2 * self.border, self.image.height() - 2 * self.border);
+        let inner = imageops::crop_imm(&self.image, self.border, self.border, width, height).to_image();
+        imageops::resize(&inner, square_size * 8, square_size * 8, FilterType::Triangle)
+    }
+}
+
+/// The images resized for squares of one size
+struct ScaledImages {
+    pieces: Vec<(Piece, RgbaImage)>,
+    board: Option<RgbaImage>,
+}
+
+impl PieceImages {
+    /// Reads the pieces named like white-knight.png, and 8x8-board.png for the classic theme
+    pub fn load(directory: &str) -> Result<PieceImages, String> {
+        let mut pieces = Vec::new();
+        for color in [Color::White, Color::Black] {
+            for kind in [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen, PieceKind::King] {
+                let piece = Piece::new(kind, color);
+                let path = Path::new(directory).join(format!("{}-{}.png", color, kind_name(kind)));
+                let image = image::open(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                pieces.push((piece, image.to_rgba8()));
+            }
+        }
+        let board = BoardImage::load(Path::new(directory))?;
+        Ok(PieceImages { pieces, board })
+    }
+
+    /// The images resized for squares of that size, the board without its border
+    fn scaled(&self, square_size: u32) -> ScaledImages {
+        let pieces = self
+            .pieces
+            .iter()
+            .map(|(piece, image)| (*piece, imageops::resize(image, square_size, square_size, FilterType::Triangle)))
+            .collect();
+        let board = self.board.as_ref().map(|board| board.squares(square_size));
+        ScaledImages { pieces, board }
+    }
+}
+
+impl ScaledImages {
+    fn piece(&self, piece: Piece) -> Option<&RgbaImage> {
+        self.pieces.iter().find(|(other, _)| *other == piece).map(|(_, image)| image)
+    }
+}
+
+fn kind_name(kind: PieceKind) -> &'static str {
+    match kind {
+        PieceKind::Pawn => "pawn",
+        PieceKind::Knight => "knight",
+        PieceKind::Bishop => "bishop",
+        PieceKind::Rook => "rook",
+        PieceKind::Queen => "queen",
+        PieceKind::King => "king",
+    }
+}
+
+/// The position of the game with its last move and a king in check highlighted, then the highlights and arrows of the options
+pub fn render_position(game: &Game, images: &PieceImages, options: &ExportOptions) -> RgbaImage {
+    draw_position(game, &images.scaled(options.square_size), options, true)
+}
+
+pub fn save_position_png(game: &Game, images: &PieceImages, options: &ExportOptions, path: &Path) -> Result<(), String> {
+    render_position(game, images, options).save(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Every position of the game from its start, one frame per move, with the highlights and arrows on the last frame only
+pub fn save_game_gif(game: &Game, images: &PieceImages, options: &ExportOptions, frame_delay_ms: u32, path: &Path) -> Result<(), String> {
+    let scaled = images.scaled(options.square_size);
+    let mut replay = game.replay().map_err(|e| e.to_string())?;
+
+    let delay = Delay::from_numer_denom_ms(frame_delay_ms, 1);
+    let mut frames = vec![Frame::from_parts(draw_position(replay.game(), &scaled, options, false), 0, 0, delay)];
+    while let Some(mv) = replay.next_move() {
+        replay.play_next().map_err(|e| format!("Move {}: {}", mv, e))?;
+        let last = replay.next_move().is_none();
+        frames.push(Frame::from_parts(draw_position(replay.game(), &scaled, options, last), 0, 0, delay));
+    }
+
+    let file = fs::File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut encoder = GifEncoder::new_with_speed(file, 10);
+    encoder.set_repeat(Repeat::Infinite).map_err(|e| e.to_string())?;
+    encoder.encode_frames(frames).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn draw_position(game: &Game, images: &ScaledImages, options: &ExportOptions, annotated: bool) -> RgbaImage {
+    let size = options.square_size;
+    let mut picture = match (options.theme.colors(), &images.board) {
+        (None, Some(board)) => board.clone(),
+        (colors, _) => {
+            // Without its board image the classic theme is drawn in brown
+            let (light, dark) = colors.unwrap_or(BROWN_SQUARES);
+            let mut picture = RgbaImage::new(size * 8, size * 8);
+            for square in Square::all() {
+                let color = if square.color() == Color::Black { dark } else { light };
+                fill_square(&mut picture, square, options, color);
+            }
+            picture
+        }
+    };
+
+    let last_move_squares = match game.history().last() {
+        Some(Move::Normal { start, end, .. }) => vec![*start, *end],
+        Some(Move::Drop { square, .. }) => vec![*square],
+        None => Vec::new(),
+    };
+    for square in last_move_squares {
+        fill_square(&mut picture, square, options, LAST_MOVE_COLOR);
+    }
+    if game.is_in_check() {
+        let king = Piece::new(PieceKind::King, game.turn());
+        if let Some(square) = Square::all().find(|&square| game.piece_at(square) == Some(king)) {
+            fill_square(&mut picture, square, options, CHECK_COLOR);
+        }
+    }
+    if annotated {
+        for &square in &options.highlights {
+            fill_square(&mut picture, square, options, HIGHLIGHT_COLOR);
+        }
+    }
+
+    for square in Square::all() {
+        if let Some(image) = game.piece_at(square).and_then(|piece| images.piece(piece)) {
+            let (x, y) = square_corner(square, options);
+            imageops::overlay(&mut picture, image, x.into(), y.into());
+        }
+    }
+
+    if annotated {
+        for &(start, end) in &options.arrows {
+            draw_arrow(&mut picture, start, end, options);
+        }
+    }
+    picture
+}
+
+/// Top left corner of a square in the picture
+fn square_corner(square: Square, options: &ExportOptions) -> (u32, u32) {
+    let (file, rank) = (square.file().index() as u32, square.rank().index() as u32);
+    let (column, row) = match options.orientation {
+        Color::White => (file, 7 - rank),
+        Color::Black => (7 - file, rank),
+    };
+    (column * options.square_size, row * options.square_size)
+}
+
+fn square_center(square: Square, options: &ExportOptions) -> (f32, f32) {
+    let (x, y) = square_corner(square, options);
+    let half = options.square_size as f32 / 2.0;
+    (x as f32 + half, y as f32 + half)
+}
+
+fn fill_square(picture: &mut RgbaImage, square: Square, options: &ExportOptions, color: Rgba<u8>) {
+    let (x, y) = square_corner(square, options);
+    for dy in 0..options.square_size {
+        for dx in 0..options.square_size {
+            picture.get_pixel_mut(x + dx, y + dy).blend(&color);
+        }
+    }
+}
+
+/// Arrow from the center of one square to the other, its head ending on the center of the end square
+fn draw_arrow(picture: &mut RgbaImage, start: Square, end: Square, options: &ExportOptions) {
+    let (from, to) = (square_center(start, options), square_center(end, options));
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return;
+    }
+    let size = options.square_size as f32;
+    let (half_width, head_length, head_half_width) = (size * 0.08, size * 0.4, size * 0.22);
+    let (ux, uy) = (dx / length, dy / length);
+
+    // Each pixel is painted once, by looking where it lies along the arrow and how far from its middle
+    for (x, y, pixel) in picture.enumerate_pixels_mut() {
+        let (px, py) = (x as f32 + 0.5 - from.0, y as f32 + 0.5 - from.1);
+        let along = px * ux + py * uy;
+        let across = (px * uy - py * ux).abs();
+        let in_shaft = (0.0..=length - head_length).contains(&along) && across <= half_width;
+        let in_head = along > length - head_length && along <= length && across <= head_half_width * (length - along) / head_length;
+        if in_shaft || in_head {
+            pixel.blend(&ARROW_COLOR);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_export.rs"]
+mod test_export;
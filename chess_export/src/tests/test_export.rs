@@ -0,0 +1,105 @@
+use std::fs;
+
+use chess_core::{Color, Game, Move, Piece, Setup, Square, Variant};
+use image::codecs::gif::GifDecoder;
+use image::{AnimationDecoder, Rgba, RgbaImage};
+
+use super::{render_position, save_game_gif, square_center, ExportOptions, PieceImages, Theme, BROWN_SQUARES};
+
+fn images() -> PieceImages {
+    PieceImages::load("../assets").unwrap()
+}
+
+fn pixel(picture: &RgbaImage, square: Square, options: &ExportOptions) -> Rgba<u8> {
+    let (x, y) = square_center(square, options);
+    *picture.get_pixel(x as u32, y as u32)
+}
+
+#[test]
+fn test_render_position() {
+    let mut game = Game::new();
+    game.play(Move::new(Square::E2, Square::E4)).unwrap();
+    let options = ExportOptions { square_size: 16, ..Default::default() };
+    let picture = render_position(&game, &images(), &options);
+    assert_eq!(picture.dimensions(), (128, 128));
+
+    let (light, dark) = BROWN_SQUARES;
+    assert_eq!(pixel(&picture, Square::D3, &options), light);
+    assert_eq!(pixel(&picture, Square::D4, &options), dark);
+    let last_move = pixel(&picture, Square::E2, &options);
+    assert!(last_move != light && last_move != dark, "The last move is highlighted");
+
+    let king = pixel(&picture, Square::E1, &options);
+    assert!(king != light && king != dark && king[3] == 255, "The king is drawn over its square");
+
+    let flipped_options = ExportOptions { orientation: Color::Black, ..options.clone() };
+    let flipped = render_position(&game, &images(), &flipped_options);
+    assert_eq!(pixel(&flipped, Square::E1, &flipped_options), king);
+    assert_eq!(*flipped.get_pixel(8, 8), pixel(&picture, Square::H1, &options), "h1 is at the top left with black at the bottom");
+}
+
+#[test]
+fn test_highlights_and_theme() {
+    let game = Game::new();
+    let options = ExportOptions { square_size: 16, theme: Theme::Blue, highlights: vec![Square::D3], ..Default::default() };
+    let picture = render_position(&game, &images(), &options);
+    let (light, _) = Theme::Blue.colors().unwrap();
+    assert_eq!(pixel(&picture, Square::F3, &options), light);
+    let highlight = pixel(&picture, Square::D3, &options);
+    assert!(highlight != light && highlight[2] > highlight[0], "d3 is tinted blue");
+}
+
+#[test]
+fn test_board_image_without_its_border() {
+    let game = Game::from_setup(&Setup::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap()).unwrap();
+    let options = ExportOptions { square_size: 96, theme: Theme::Classic, ..Default::default() };
+    let picture = render_position(&game, &images(), &options);
+    assert_eq!(picture.dimensions(), (768, 768));
+    // The board image has a gray border of 8 pixels around white and gray squares, a8 is white
+    assert_eq!(*picture.get_pixel(0, 0), Rgba([255, 255, 255, 255]), "The border is cut off");
+    assert_eq!(*picture.get_pixel(100, 50), Rgba([170, 170, 170, 255]), "b8 is gray");
+}
+
+/// Frames of the game as a GIF
+fn gif_frames(game: &Game, name: &str) -> Vec<image::Frame> {
+    let path = std::env::temp_dir().join(format!("chess_export_{}_{}.gif", name, std::process::id()));
+    let options = ExportOptions { square_size: 8, ..Default::default() };
+    save_game_gif(game, &images(), &options, 500, &path).unwrap();
+
+    let decoder = GifDecoder::new(fs::File::open(&path).unwrap()).unwrap();
+    let frames = decoder.into_frames().collect_frames().unwrap();
+    fs::remove_file(&path).unwrap();
+    frames
+}
+
+#[test]
+fn test_game_gif() {
+    let mut game = Game::new();
+    for mv in ["e2e4", "e7e5", "g1f3"] {
+        game.play(mv.parse().unwrap()).unwrap();
+    }
+    let frames = gif_frames(&game, "game");
+    assert_eq!(frames.len(), 4, "The starting position and one frame per move");
+    assert!(frames.iter().all(|frame| frame.buffer().dimensions() == (64, 64)));
+    assert_eq!(frames[0].delay().numer_denom_ms(), (500, 1));
+}
+
+#[test]
+fn test_game_gif_with_received_piece() {
+    let mut game = Game::with_variant(Variant::Bughouse);
+    game.play_move(Square::E2, Square::E4).unwrap();
+    game.add_to_pocket(Piece::Knight(Color::Black)).unwrap();
+    game.play("N@e6".parse().unwrap()).unwrap();
+    assert_eq!(gif_frames(&game, "bughouse").len(), 3, "The knight received after e4 is dropped");
+}
+
+#[test]
+fn test_game_gif_with_pending_promotion() {
+    let mut setup = Setup::empty();
+    setup.grid[0][4] = Some(Piece::King(Color::White));
+    setup.grid[7][0] = Some(Piece::King(Color::Black));
+    setup.grid[6][7] = Some(Piece::Pawn(Color::White));
+    let mut game = Game::from_setup(&setup).unwrap();
+    game.play_move(Square::H7, Square::H8).unwrap();
+    assert_eq!(gif_frames(&game, "promotion").len(), 2, "The pawn waits on h8 in the last frame");
+}
@@ -0,0 +1,92 @@
+use chess_core::{Game, GameEvent, Move, Piece, Square};
+use macroquad::prelude::{get_time, WHITE};
+
+use crate::board::{self, Textures};
+use crate::input;
+use crate::settings::AnimationSpeed;
+
+/// A piece going from one square to another, drawn between them until the animation is over
+#[derive(Debug, Clone, Copy)]
+struct Slide {
+    piece: Piece,
+    start: Square,
+    end: Square,
+}
+
+/// The last move played, sliding to its square. A castling also moves the rook, and the piece taken fades out
+#[derive(Debug, Default)]
+pub struct MoveAnimation {
+    slides: Vec<Slide>,
+    captured: Option<(Piece, Square)>,
+    started: f64,
+    duration: f32,
+}
+
+impl MoveAnimation {
+    /// Starts the animation of the last move among the events, the game being the one they came from
+    pub fn update(&mut self, events: &[GameEvent], game: &Game, speed: AnimationSpeed) {
+        for event in events {
+            match *event {
+                GameEvent::MovePlayed(Move::Normal { start, end, .. }, _) => {
+                    *self = MoveAnimation { started: get_time(), duration: speed.duration(), ..Default::default() };
+                    // The piece is read after the move, so a promoted pawn already shows its new piece
+                    if let Some(piece) = game.piece_at(end) {
+                        self.slides.push(Slide { piece, start, end });
+                    }
+                }
+                GameEvent::Castle { color, rook_start, rook_end } => {
+                    self.slides.push(Slide { piece: Piece::Rook(color), start: rook_start, end: rook_end });
+                }
+                GameEvent::Capture(piece, square) => self.captured = Some((piece, square)),
+                GameEvent::MovePlayed(Move::Drop { .. }, _) | GameEvent::MoveTakenBack(_) | GameEvent::GameReplaced => {
+                    *self = MoveAnimation::default();
+                }
+                _ => {}
+            }
+        }
+        // Pieces still on their square once the animation is off
+        if self.duration <= 0.0 {
+            *self = MoveAnimation::default();
+        }
+    }
+
+    /// How far the animation has gone, from 0 to 1
+    fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        ((get_time() - self.started) as f32 / self.duration).clamp(0.0, 1.0)
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.slides.is_empty() && self.progress() < 1.0
+    }
+
+    /// Squares where the board must not draw its piece, since the animation draws it
+    pub fn moving_squares(&self) -> Vec<Square> {
+        if !self.is_running() {
+            return Vec::new();
+        }
+        self.slides.iter().map(|slide| slide.end).collect()
+    }
+
+    /// Draw the piece taken fading out under the moving pieces, which slow down as they arrive
+    pub fn draw(&self, textures: &Textures) {
+        if !self.is_running() {
+            return;
+        }
+        let t = self.progress();
+        let eased = t * t * (3.0 - 2.0 * t);
+
+        if let Some((piece, square)) = self.captured {
+            let fading = macroquad::color::Color { a: 1.0 - t, ..WHITE };
+            board::draw_piece(piece, input::square_position(square), fading, textures);
+        }
+        for slide in &self.slides {
+            let (start_x, start_y) = input::square_position(slide.start);
+            let (end_x, end_y) = input::square_position(slide.end);
+            let position = (start_x + (end_x - start_x) * eased, start_y + (end_y - start_y) * eased);
+            board::draw_piece(slide.piece, position, WHITE, textures);
+        }
+    }
+}
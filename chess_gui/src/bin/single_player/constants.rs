@@ -0,0 +1 @@
+pub const BOOK_MOVES: usize = 6; // Book moves shown on the side panel, the most played first
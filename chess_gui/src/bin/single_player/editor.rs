@@ -0,0 +1,177 @@
+use chess_core::{Color, File, Game, Piece, PieceKind, Setup};
+use macroquad::prelude::{
+    draw_rectangle_lines, draw_texture_ex, is_mouse_button_pressed, vec2,
+    DrawTextureParams, MouseButton, GREEN, WHITE,
+};
+use macroquad::ui::{hash, root_ui, widgets, Skin};
+
+use chess_gui::board::{self, Textures};
+use chess_gui::constants::{BOARD_SIZE, SIDE_PANEL_WIDTH};
+use chess_gui::{dialogs, input};
+
+const PALETTE_TILE_SIZE: f32 = 64.0;
+const PALETTE_KINDS: [PieceKind; 6] = [
+    PieceKind::King,
+    PieceKind::Queen,
+    PieceKind::Rook,
+    PieceKind::Bishop,
+    PieceKind::Knight,
+    PieceKind::Pawn,
+];
+const EN_PASSANT_FILES: [&str; 9] = ["-", "a", "b", "c", "d", "e", "f", "g", "h"];
+
+/// Board editor: place pieces from the palette with a left click, remove them with a right click
+pub struct Editor {
+    setup: Setup,
+    brush: Piece,
+    en_passant: usize, // Index in EN_PASSANT_FILES
+    error: Option<String>,
+    skin: Skin,
+}
+
+impl Editor {
+    pub fn new() -> Editor {
+        Editor {
+            setup: Setup::default(),
+            brush: Piece::Pawn(Color::White),
+            en_passant: 0,
+            error: None,
+            skin: root_ui().default_skin(),
+        }
+    }
+
+    /// Draw the editor and handle its input, returns the game to play once the setup is valid
+    pub fn run_frame(&mut self, textures: &Textures) -> Option<Game> {
+        board::draw_position(|square| self.setup.piece_at(square), textures);
+        self.draw_palette(textures);
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            if let Some(piece) = select_palette_piece() {
+                self.brush = piece;
+            } else if let Some(square) = input::select_square() {
+                // Clicking a square with the piece it already holds removes it
+                let piece = if self.setup.piece_at(square) == Some(self.brush) { None } else { Some(self.brush) };
+                self.setup.set_piece(square, piece);
+                self.error = None;
+            }
+        }
+        if is_mouse_button_pressed(MouseButton::Right) {
+            if let Some(square) = input::select_square() {
+                self.setup.set_piece(square, None);
+                self.error = None;
+            }
+        }
+
+        let game = self.draw_controls();
+
+        if let Some(error) = &self.error {
+            dialogs::draw_message_box(error);
+        }
+
+        game
+    }
+
+    fn draw_palette(&self, textures: &Textures) {
+        for (color_index, color) in [Color::White, Color::Black].into_iter().enumerate() {
+            for (row, kind) in PALETTE_KINDS.iter().enumerate() {
+                let piece = Piece::new(*kind, color);
+                let (x, y) = palette_slot(color_index, row);
+                draw_texture_ex(
+                    textures.piece(piece),
+                    x,
+                    y,
+                    WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(vec2(PALETTE_TILE_SIZE, PALETTE_TILE_SIZE)),
+                        ..Default::default()
+                    },
+                );
+                if piece == self.brush {
+                    draw_rectangle_lines(x, y, PALETTE_TILE_SIZE, PALETTE_TILE_SIZE, 4.0, GREEN);
+                }
+            }
+        }
+    }
+
+    fn draw_controls(&mut self) -> Option<Game> {
+        let mut game = None;
+        let setup = &mut self.setup;
+        let mut en_passant = self.en_passant;
+        let mut start = false;
+
+        root_ui().push_skin(&self.skin);
+        // The controls keep their size when the window is resized, only their position follows the panel
+        let (x, y) = input::to_screen((BOARD_SIZE, palette_slot(0, PALETTE_KINDS.len()).1));
+        widgets::Window::new(hash!("editor_controls"), vec2(x, y), vec2(SIDE_PANEL_WIDTH, 300.0))
+            .titlebar(false)
+            .movable(false)
+            .ui(&mut root_ui(), |ui| {
+                let turn = match setup.turn {
+                    Color::White => "White to move",
+                    Color::Black => "Black to move",
+                };
+                if ui.button(None, turn) {
+                    setup.turn = setup.turn.opposite();
+                }
+
+                ui.separator();
+                ui.checkbox(hash!(), "White O-O", &mut setup.white_castle_king_side);
+                ui.checkbox(hash!(), "White O-O-O", &mut setup.white_castle_queen_side);
+                ui.checkbox(hash!(), "Black O-O", &mut setup.black_castle_king_side);
+                ui.checkbox(hash!(), "Black O-O-O", &mut setup.black_castle_queen_side);
+                ui.combo_box(hash!(), "En passant", &EN_PASSANT_FILES, &mut en_passant);
+
+                ui.separator();
+                if ui.button(None, "Clear") {
+                    *setup = Setup::empty();
+                    en_passant = 0;
+                }
+                if ui.button(None, "Starting position") {
+                    *setup = Setup::default();
+                    en_passant = 0;
+                }
+                if ui.button(None, "Start") {
+                    start = true;
+                }
+            });
+        root_ui().pop_skin();
+
+        self.en_passant = en_passant;
+        self.setup.en_passant = en_passant.checked_sub(1).and_then(File::new);
+
+        if start {
+            match Game::from_setup(&self.setup) {
+                Ok(new_game) => game = Some(new_game),
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+
+        game
+    }
+}
+
+/// Top left corner of a palette slot, white pieces in the first column and black pieces in the second
+fn palette_slot(column: usize, row: usize) -> (f32, f32) {
+    let margin = (SIDE_PANEL_WIDTH - 2.0 * PALETTE_TILE_SIZE) / 3.0;
+    (
+        BOARD_SIZE + margin + column as f32 * (PALETTE_TILE_SIZE + margin),
+        margin + row as f32 * PALETTE_TILE_SIZE,
+    )
+}
+
+fn select_palette_piece() -> Option<Piece> {
+    let mouse_pos = input::mouse_in_layout();
+    for (column, color) in [Color::White, Color::Black].into_iter().enumerate() {
+        for (row, kind) in PALETTE_KINDS.iter().enumerate() {
+            let (x, y) = palette_slot(column, row);
+            if mouse_pos.0 >= x
+                && mouse_pos.0 <= x + PALETTE_TILE_SIZE
+                && mouse_pos.1 >= y
+                && mouse_pos.1 <= y + PALETTE_TILE_SIZE
+            {
+                return Some(Piece::new(*kind, color));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,64 @@
+use chess_core::puzzle::PuzzleAttempt;
+use chess_core::{BookMove, Color, PromotionPiece};
+use chess_gui::constants::WINDOW_HEIGHT;
+use macroquad::prelude::{draw_text, BLACK, DARKGRAY};
+
+use crate::constants::BOOK_MOVES;
+
+/// List the book moves of the current position on the panel right of the board, with their share of the total weight
+pub fn draw_book_moves(x: f32, top: f32, moves: &[BookMove]) {
+    draw_text("Book moves", x, top, 30.0, BLACK);
+
+    if moves.is_empty() {
+        draw_text("Out of book", x, top + 40.0, 24.0, BLACK);
+        return;
+    }
+
+    let total: u32 = moves.iter().map(|book_move| book_move.weight as u32).sum();
+    for (index, book_move) in moves.iter().take(BOOK_MOVES).enumerate() {
+        let share = (100 * book_move.weight as u32).checked_div(total).unwrap_or(0);
+        let y = top + 40.0 + index as f32 * 30.0;
+        draw_text(&move_name(book_move), x, y, 24.0, BLACK);
+        draw_text(&format!("{} ({}%)", book_move.weight, share), x + 80.0, y, 24.0, BLACK);
+    }
+}
+
+/// Show the puzzle on the panel right of the board: its rating and themes, the rating of the player and how the last move went
+pub fn draw_puzzle_panel(x: f32, top: f32, attempt: Option<&PuzzleAttempt>, rating: i32, feedback: &str) {
+    draw_text("Puzzles", x, top, 30.0, BLACK);
+    draw_text(&format!("Your rating {}", rating), x, top + 40.0, 24.0, BLACK);
+
+    if let Some(attempt) = attempt {
+        let puzzle = attempt.puzzle();
+        let goal = if puzzle.is_mate() { "Find the mate" } else { "Find the best move" };
+        let side = match attempt.game().turn() {
+            Color::White => "White to play",
+            Color::Black => "Black to play",
+        };
+        draw_text(&format!("Puzzle {} ({})", puzzle.id, puzzle.rating), x, top + 90.0, 22.0, BLACK);
+        draw_text(side, x, top + 120.0, 22.0, BLACK);
+        draw_text(goal, x, top + 146.0, 22.0, BLACK);
+    }
+    draw_text(feedback, x, top + 196.0, 26.0, BLACK);
+
+    draw_text("H: hint, N: next puzzle", x, top + 226.0, 20.0, DARKGRAY);
+}
+
+/// Coordinate notation of a book move, e.g. e2e4 or b7a8n
+fn move_name(book_move: &BookMove) -> String {
+    let promotion = match book_move.promotion {
+        Some(PromotionPiece::Queen) => "q",
+        Some(PromotionPiece::Rook) => "r",
+        Some(PromotionPiece::Bishop) => "b",
+        Some(PromotionPiece::Knight) => "n",
+        None => "",
+    };
+    format!("{}{}{}", book_move.start, book_move.end, promotion)
+}
+
+/// Keys of the move list at the bottom of the side panel
+pub fn draw_move_hints(x: f32, viewing: bool) {
+    let first = if viewing { "Enter: play from here" } else { "Left/Right: view moves" };
+    draw_text(first, x, WINDOW_HEIGHT - 42.0, 20.0, DARKGRAY);
+    draw_text("Backspace: take back", x, WINDOW_HEIGHT - 20.0, 20.0, DARKGRAY);
+}
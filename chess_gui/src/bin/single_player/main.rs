@@ -0,0 +1,376 @@
+mod constants;
+mod editor;
+mod gui;
+mod puzzles;
+mod utils;
+
+use std::cell::Cell;
+
+use chess_core::search::Analyzer;
+use chess_core::puzzle;
+use chess_core::{Color, Game, GameController, GameTree, NodeId, OpeningBook, Piece, PieceKind};
+use chess_gui::constants::{
+    ANALYSIS_LINES, EVAL_BAR_WIDTH, POCKET_PANEL_WIDTH, SETTINGS_FILE, SIDE_PANEL_WIDTH, WINDOW_HEIGHT,
+};
+use chess_gui::animation::MoveAnimation;
+use chess_gui::review::ReviewScreen;
+use chess_gui::settings::Settings;
+use chess_gui::sound::Sounds;
+use chess_gui::{board, dialogs, input, panels};
+use macroquad::prelude::{
+    clear_background, is_key_pressed, is_mouse_button_pressed, is_mouse_button_released, next_frame, Conf, KeyCode,
+    MouseButton, WHITE,
+};
+
+use crate::constants::BOOK_MOVES;
+use crate::editor::Editor;
+use crate::puzzles::PuzzleTrainer;
+
+fn save_settings(settings: &Settings) {
+    if let Err(e) = settings.save() {
+        eprintln!("Could not save the settings to {}: {}", SETTINGS_FILE, e);
+    }
+}
+
+/// Brings the game to another one, playing the moves it adds so that they are animated, or replacing it
+fn catch_up(game: &mut GameController, target: Game) {
+    let played = game.game().history();
+    if target.history().len() > played.len() && target.history().starts_with(played) {
+        let new_moves = target.history()[played.len()..].to_vec();
+        if new_moves.into_iter().all(|mv| game.play(mv).is_ok()) {
+            return;
+        }
+    }
+    game.replace(target);
+}
+
+// Define the window configuration
+fn window_conf() -> Conf {
+    let pocket_width = if utils::variant_from_args().has_drops() { POCKET_PANEL_WIDTH as i32 } else { 0 };
+    Conf {
+        window_title: "Chess Game".to_string(),
+        window_width: 784 + pocket_width + SIDE_PANEL_WIDTH as i32, // Match the board size including borders, and the panels right of it
+        window_height: WINDOW_HEIGHT as i32, // The board and the bars of captured pieces above and below it
+        fullscreen: false, // Disable fullscreen (optional)
+        window_resizable: true, // The layout is scaled to fit the window
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut settings = Settings::load();
+    if let Some(pieces) = utils::pieces_from_args() {
+        settings.pieces = pieces;
+    }
+    let mut textures = match board::load_textures(&settings.pieces, settings.theme).await {
+        Ok(textures) => textures,
+        Err(e) => {
+            eprintln!("Could not load the pieces from {}: {}", settings.pieces, e);
+            settings.pieces = Settings::default().pieces;
+            board::load_textures(&settings.pieces, settings.theme).await.unwrap()
+        }
+    };
+    save_settings(&settings);
+    let sounds = Sounds::load(&settings.sounds).await;
+
+    let mut game = GameController::new(Game::with_variant(utils::variant_from_args()));
+    let events = game.subscribe();
+    let mut animation = MoveAnimation::default();
+    let mut editor = utils::editor_from_args().then(Editor::new); // The game starts once the editor is done
+    let book = utils::book_from_args().and_then(|path| match OpeningBook::open(&path) {
+        Ok(book) => Some(book),
+        Err(e) => {
+            eprintln!("Could not read the book {}: {}", path, e);
+            None
+        }
+    });
+    let mut trainer = utils::puzzles_from_args().and_then(|path| match puzzle::load_puzzles(&path) {
+        Ok(puzzles) => Some(PuzzleTrainer::new(puzzles, &path)),
+        Err(e) => {
+            eprintln!("Could not read the puzzles {}: {}", path, e);
+            None
+        }
+    });
+    if let Some(new_game) = trainer.as_mut().and_then(|trainer| trainer.next_puzzle()) {
+        game.replace(new_game);
+    }
+
+    let mut move_input = input::MoveInput::default();
+    let mut dragged: Option<PieceKind> = None; // Piece taken from a pocket
+    let mut analyzer: Option<Analyzer> = None; // Restarted whenever the position changes, in analysis mode
+    let mut review: Option<ReviewScreen> = None; // Replaces the game until the player goes back to it
+    let mut tree = GameTree::new(game.game().clone()); // Every move played, with the variations tried after taking moves back
+    let mut viewed: Option<(NodeId, Game)> = None; // Move of the tree shown on the board without playing from it
+
+    let should_quit = Cell::new(false);
+    let should_restart = Cell::new(false);
+    let should_review = Cell::new(false);
+
+    dialogs::create_skin();
+    if settings.coordinates || utils::coordinates_from_args() {
+        input::toggle_coordinates();
+    }
+
+    loop {
+        clear_background(WHITE);
+        input::fit_to_window(game.game().variant());
+
+        // Turn the board around, show the files and ranks or change the board colors, on every screen
+        if is_key_pressed(KeyCode::F) {
+            input::flip_board();
+        }
+        if is_key_pressed(KeyCode::C) {
+            input::toggle_coordinates();
+            settings.coordinates = input::coordinates_shown();
+            save_settings(&settings);
+        }
+        if is_key_pressed(KeyCode::T) {
+            textures.theme = textures.theme.next();
+            settings.theme = textures.theme;
+            save_settings(&settings);
+        }
+        // Change how fast the pieces move, or turn the sound off
+        if is_key_pressed(KeyCode::A) {
+            settings.animation = settings.animation.next();
+            save_settings(&settings);
+        }
+        if is_key_pressed(KeyCode::S) {
+            settings.sound = !settings.sound;
+            save_settings(&settings);
+        }
+
+        if let Some(board_editor) = &mut editor {
+            if let Some(new_game) = board_editor.run_frame(&textures) {
+                tree = GameTree::new(new_game.clone());
+                game.replace(new_game);
+                editor = None;
+            }
+            next_frame().await;
+            continue;
+        }
+
+        if let Some(review_screen) = &mut review {
+            if review_screen.run_frame(&textures) {
+                review = None;
+            }
+            next_frame().await;
+            continue;
+        }
+
+        // The book plays black while the position is in the book, then the player takes over both sides. Not during puzzles
+        if let Some(book) = book.as_ref().filter(|_| trainer.is_none()) {
+            let position = game.game();
+            if position.turn() == Color::Black && position.endgame_status().is_ongoing() && position.is_promotion_pending().is_none() {
+                if let Some(book_move) = book.random_move(position) {
+                    if game.play_move(book_move.start, book_move.end).is_ok() {
+                        if let Some(promotion_piece) = book_move.promotion {
+                            let _ = game.promote_pawn(promotion_piece);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Go back one move in the tree, and before the book's answer too since the book would play it again.
+        // A different move played from there starts a variation
+        if is_key_pressed(KeyCode::Backspace) && dragged.is_none() {
+            tree.go_back();
+            if book.is_some() && tree.game().turn() == Color::Black {
+                tree.go_back();
+            }
+            game.replace(tree.game().clone());
+            viewed = None;
+            move_input.clear();
+        }
+        // Delete the current move and the moves after it, or make its line the main line
+        if is_key_pressed(KeyCode::Delete) && dragged.is_none() {
+            let _ = tree.delete(tree.current());
+            game.replace(tree.game().clone());
+            viewed = None;
+        }
+        if is_key_pressed(KeyCode::P) {
+            let _ = tree.promote_to_mainline(tree.current());
+        }
+
+        // Step through the moves of the tree without playing them, Enter plays from the move shown
+        let shown_node = viewed.as_ref().map_or(tree.current(), |(id, _)| *id);
+        let mut view_node = None;
+        if is_key_pressed(KeyCode::Left) && dragged.is_none() {
+            view_node = tree.node(shown_node).and_then(|node| node.parent());
+        }
+        if is_key_pressed(KeyCode::Right) && dragged.is_none() {
+            view_node = tree.node(shown_node).and_then(|node| node.children().first().copied());
+        }
+        if is_key_pressed(KeyCode::Enter) {
+            if let Some((id, _)) = viewed.take() {
+                let _ = tree.go_to(id);
+                game.replace(tree.game().clone());
+                move_input.clear();
+            }
+        }
+        // Claim a draw by threefold repetition or the fifty-move rule, nothing happens when there is none to claim
+        if is_key_pressed(KeyCode::D) && viewed.is_none() {
+            let _ = game.claim_draw();
+        }
+
+        // Animate and sound the moves played since the last frame
+        let new_events: Vec<_> = events.try_iter().collect();
+        animation.update(&new_events, game.game(), settings.animation);
+        if settings.sound {
+            sounds.play(&new_events);
+        }
+
+        let board = viewed.as_ref().map_or(game.game(), |(_, viewed_game)| viewed_game);
+        if viewed.is_none() {
+            let hidden: Vec<_> = move_input.dragged().into_iter().chain(animation.moving_squares()).collect();
+            board::draw_board(board, &hidden, &textures);
+            animation.draw(&textures);
+        } else {
+            board::draw_board(board, &[], &textures);
+        }
+        board::draw_captured_pieces(board, &textures);
+        if board.variant().has_drops() {
+            board::draw_pockets(board, &textures);
+        }
+
+        // The side panel is split between the book moves, the analysis, the puzzle and the moves, top to bottom
+        let panel_x = input::side_panel_x(game.game().variant());
+        let text_x = if utils::analysis_from_args() { panel_x + EVAL_BAR_WIDTH + 16.0 } else { panel_x + 20.0 };
+        let text_width = panel_x + SIDE_PANEL_WIDTH - text_x - 8.0;
+        let mut section_top = 40.0;
+        if let Some(book) = &book {
+            gui::draw_book_moves(text_x, section_top, &book.moves(game.game()));
+            section_top += 70.0 + BOOK_MOVES as f32 * 30.0;
+        }
+        if utils::analysis_from_args() {
+            let analyzed = board.clone();
+            if analyzer.as_ref().is_none_or(|analyzer| !analyzer.is_analyzing(&analyzed)) {
+                analyzer = Some(Analyzer::start(&analyzed, ANALYSIS_LINES));
+            }
+            let lines = analyzer.as_mut().map_or(&[][..], |analyzer| analyzer.lines());
+            if analyzed.endgame_status().is_ongoing() {
+                board::draw_best_move_arrows(lines);
+            }
+            panels::draw_eval_bar(panel_x + 8.0, lines.first().map(|line| line.score));
+            let analysis_height = 40.0 + ANALYSIS_LINES as f32 * 100.0;
+            panels::draw_analysis_lines(text_x, section_top, section_top + analysis_height - 60.0, text_width, lines, &analyzed);
+            section_top += analysis_height;
+        }
+        if let Some(trainer) = &mut trainer {
+            if is_key_pressed(KeyCode::N) {
+                if let Some(new_game) = trainer.next_puzzle() {
+                    tree = GameTree::new(new_game.clone());
+                    game.replace(new_game);
+                    viewed = None;
+                    move_input.clear();
+                }
+            }
+            if is_key_pressed(KeyCode::H) {
+                trainer.show_hint();
+            }
+            if let Some(hint) = trainer.hint().filter(|_| viewed.is_none()) {
+                board::draw_move_arrow(hint, macroquad::color::Color::new(0.1, 0.7, 0.2, 0.8));
+            }
+            gui::draw_puzzle_panel(text_x, section_top, trainer.attempt(), trainer.rating(), trainer.feedback());
+            section_top += 260.0;
+        }
+        let highlighted = viewed.as_ref().map_or(tree.current(), |(id, _)| *id);
+        if let Some(id) = panels::draw_move_tree(text_x, section_top, WINDOW_HEIGHT - 60.0, text_width, &tree, highlighted) {
+            view_node = Some(id);
+        }
+        gui::draw_move_hints(text_x, viewed.is_some());
+
+        // Viewing the current move goes back to the game
+        if let Some(id) = view_node {
+            viewed = match tree.game_at(id) {
+                Ok(viewed_game) if id != tree.current() => Some((id, viewed_game)),
+                _ => None,
+            };
+            move_input.clear();
+        }
+
+        // Handle input and show promotion menu
+
+        if viewed.is_some() {
+            // The board only shows a move of the tree, nothing can be played on it
+        } else if let Some((column, color)) = game.game().is_promotion_pending() {
+            board::show_promotion_menu((column, color), &textures);
+            if is_mouse_button_pressed(MouseButton::Left) {
+                if let Some(square) = input::select_square() {
+                    if let Some(promotion_piece) =
+                        input::select_promotion_piece(square, column, color)
+                    {
+                        game.promote_pawn(promotion_piece).unwrap();
+                    }
+                }
+            }
+        } else if let Some(kind) = dragged {
+            // The piece is dropped when the mouse button is released
+            if is_mouse_button_released(MouseButton::Left) {
+                if let Some(square) = input::select_square() {
+                    let _ = game.drop_piece(kind, square);
+                }
+                dragged = None;
+            }
+        } else if let Some(kind) = input::select_pocket_piece(game.game().turn()).filter(|_| is_mouse_button_pressed(MouseButton::Left)) {
+            let position = game.game();
+            if position.variant().has_drops() && position.pocket(position.turn()).count(kind) > 0 && position.endgame_status().is_ongoing() {
+                dragged = Some(kind);
+                move_input.clear();
+            }
+        } else {
+            let turn = game.game().turn();
+            move_input.update(&mut game, turn, |game, start, end| game.play_move(start, end).is_ok());
+        }
+
+        // The trainer answers the move, or puts the pieces back after a wrong one
+        if let Some(new_game) = trainer.as_mut().and_then(|trainer| trainer.check_move(game.game())) {
+            catch_up(&mut game, new_game);
+        }
+
+        // Moves played on the board go to the tree, a game replaced by a new one starts a new tree
+        let played = game.game();
+        if played.is_promotion_pending().is_none() && played.history() != tree.game().history() {
+            if played.history().starts_with(tree.game().history()) {
+                let new_moves = played.history()[tree.game().history().len()..].to_vec();
+                for mv in new_moves {
+                    let _ = tree.add_move(mv);
+                }
+            } else {
+                tree = GameTree::new(played.clone());
+            }
+        }
+
+        if game.game().endgame_status() != chess_core::EndgameStatus::Ongoing && trainer.is_none() && viewed.is_none() {
+            dialogs::draw_game_over_box(&should_quit, &should_restart, &should_review, game.game());
+        }
+
+        if should_restart.replace(false) {
+            game.replace(Game::with_variant(game.game().variant()));
+        }
+
+        if should_review.replace(false) {
+            review = Some(ReviewScreen::new(game.game()));
+            move_input.clear();
+        }
+
+        if should_quit.get() {
+            break;
+        }
+
+        board::show_legal_moves(game.game(), move_input.selected(), &textures);
+        if let Some(square) = move_input.dragged() {
+            if let Some(piece) = game.game().piece_at(square) {
+                board::draw_dragged_piece(piece, &textures);
+            }
+        }
+
+        if let Some(kind) = dragged {
+            board::show_legal_drops(game.game(), kind, &textures);
+            board::draw_dragged_piece(Piece::new(kind, game.game().turn()), &textures);
+        }
+
+        next_frame().await;
+    }
+}
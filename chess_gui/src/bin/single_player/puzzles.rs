@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+use std::fs;
+
+use chess_core::puzzle::{self, Answer, Puzzle, PuzzleAttempt};
+use chess_core::{Game, Move};
+
+/// Puzzles from a CSV file, the closest to the rating of the player first. The rating is kept in a file next to the puzzles
+pub struct PuzzleTrainer {
+    puzzles: Vec<Puzzle>,
+    played: HashSet<usize>, // Indexes of the puzzles already given
+    attempt: Option<PuzzleAttempt>,
+    rating: i32,
+    rating_path: String,
+    rated: bool, // The rating can only change once per puzzle
+    hint: Option<Move>,
+    feedback: &'static str,
+}
+
+impl PuzzleTrainer {
+    pub fn new(puzzles: Vec<Puzzle>, puzzles_path: &str) -> PuzzleTrainer {
+        let rating_path = format!("{}.rating", puzzles_path);
+        let rating = fs::read_to_string(&rating_path)
+            .ok()
+            .and_then(|content| content.trim().parse().ok())
+            .unwrap_or(puzzle::DEFAULT_RATING);
+        PuzzleTrainer {
+            puzzles,
+            played: HashSet::new(),
+            attempt: None,
+            rating,
+            rating_path,
+            rated: false,
+            hint: None,
+            feedback: "",
+        }
+    }
+
+    pub fn attempt(&self) -> Option<&PuzzleAttempt> {
+        self.attempt.as_ref()
+    }
+
+    pub fn rating(&self) -> i32 {
+        self.rating
+    }
+
+    pub fn feedback(&self) -> &'static str {
+        self.feedback
+    }
+
+    /// The next move of the solution, once the player asked for it
+    pub fn hint(&self) -> Option<Move> {
+        self.hint
+    }
+
+    /// Starts the unplayed puzzle with the rating closest to the player's, returns its position
+    pub fn next_puzzle(&mut self) -> Option<Game> {
+        let index = (0..self.puzzles.len())
+            .filter(|index| !self.played.contains(index))
+            .min_by_key(|&index| (self.puzzles[index].rating - self.rating).abs());
+        let index = match index {
+            Some(index) => index,
+            None => {
+                self.attempt = None;
+                self.feedback = "Every puzzle was played";
+                return None;
+            }
+        };
+
+        self.played.insert(index);
+        // The puzzles were checked when they were read
+        self.attempt = Some(self.puzzles[index].start().unwrap());
+        self.rated = false;
+        self.hint = None;
+        self.feedback = "Your move";
+        self.attempt.as_ref().map(|attempt| attempt.game().clone())
+    }
+
+    /// Shows the next move of the solution, the puzzle then counts as failed
+    pub fn show_hint(&mut self) {
+        if let Some(attempt) = &self.attempt {
+            if !attempt.is_solved() {
+                self.hint = attempt.hint();
+                self.rate(false);
+            }
+        }
+    }
+
+    /// Checks the move the player made on the board, returns the position to show when it changes
+    pub fn check_move(&mut self, game: &Game) -> Option<Game> {
+        let attempt = self.attempt.as_mut()?;
+        let expected = attempt.game().history();
+        if game.is_promotion_pending().is_some() || game.history() == expected {
+            return None;
+        }
+
+        let is_next_move = game.history().len() == expected.len() + 1 && game.history().starts_with(expected);
+        if is_next_move && !attempt.is_solved() {
+            let answer = attempt.play(*game.history().last().unwrap());
+            self.hint = None;
+            match answer {
+                Answer::Correct(_) => self.feedback = "Correct, keep going",
+                Answer::Wrong => {
+                    self.feedback = "Wrong move, try again";
+                    self.rate(false);
+                }
+                Answer::Solved => {
+                    self.feedback = "Solved!";
+                    self.rate(true);
+                }
+            }
+        }
+        // A wrong move or a move taken back is replaced by the position of the puzzle
+        self.attempt.as_ref().map(|attempt| attempt.game().clone())
+    }
+
+    fn rate(&mut self, solved: bool) {
+        if self.rated {
+            return;
+        }
+        if let Some(attempt) = &self.attempt {
+            self.rating = puzzle::updated_rating(self.rating, attempt.puzzle().rating, solved);
+            self.rated = true;
+            if let Err(e) = fs::write(&self.rating_path, self.rating.to_string()) {
+                eprintln!("Could not save the puzzle rating to {}: {}", self.rating_path, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use chess_core::Variant;
+
+/// The variant is given as the first command line argument, standard chess by default
+pub fn variant_from_args() -> Variant {
+    match std::env::args().nth(1).as_deref() {
+        Some("crazyhouse") => Variant::Crazyhouse,
+        _ => Variant::Standard,
+    }
+}
+
+/// Start with the board editor instead of the starting position
+pub fn editor_from_args() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "editor")
+}
+
+/// Path of the Polyglot book given after the "book" argument, the book then plays black
+pub fn book_from_args() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == "book")?;
+    args.next()
+}
+
+/// Path of the puzzle file given after the "puzzles" argument, in the CSV format of the Lichess puzzle database
+pub fn puzzles_from_args() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == "puzzles")?;
+    args.next()
+}
+
+/// Write the files and ranks on the board from the start, C toggles them
+pub fn coordinates_from_args() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "coordinates")
+}
+
+/// Directory of the piece set given after the "pieces" argument, it is kept in the settings
+pub fn pieces_from_args() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == "pieces")?;
+    args.next()
+}
+
+/// Analyze the position on a background thread and show the best lines
+pub fn analysis_from_args() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "analysis")
+}
@@ -0,0 +1,467 @@
+use std::path::Path;
+
+use chess_core::search::Line;
+use chess_core::{Color, EndgameStatus, File, Game, Move, Piece, PieceKind, Pocket, Rank, Square};
+use macroquad::prelude::{
+    draw_circle_lines, draw_line, draw_rectangle, draw_text, draw_texture_ex, draw_triangle, load_texture, vec2,
+    DrawTextureParams, Texture2D, BLACK, DARKGRAY, WHITE,
+};
+
+use crate::constants::{BOARD_SIZE, BOARD_TOP, BORDER_SIZE, CAPTURED_BAR_HEIGHT, CAPTURED_PIECE_SIZE, POCKET_TILE_SIZE, TILE_SIZE};
+use crate::input;
+use crate::settings::BoardTheme;
+
+const EMPTY_POCKET_TINT: macroquad::color::Color = macroquad::color::Color::new(1.0, 1.0, 1.0, 0.3);
+
+const LAST_MOVE_COLOR: macroquad::color::Color = macroquad::color::Color::new(0.9, 0.85, 0.2, 0.45);
+
+const CHECK_COLOR: macroquad::color::Color = macroquad::color::Color::new(0.9, 0.1, 0.1, 0.55);
+const PREMOVE_COLOR: macroquad::color::Color = macroquad::color::Color::new(0.2, 0.4, 0.9, 0.45);
+
+pub struct Textures {
+    pub board: Texture2D,
+    pub theme: BoardTheme, // The board image is only drawn with the classic theme
+    pub white_pawn: Texture2D,
+    pub white_knight: Texture2D,
+    pub white_bishop: Texture2D,
+    pub white_rook: Texture2D,
+    pub white_queen: Texture2D,
+    pub white_king: Texture2D,
+    pub black_pawn: Texture2D,
+    pub black_knight: Texture2D,
+    pub black_bishop: Texture2D,
+    pub black_rook: Texture2D,
+    pub black_queen: Texture2D,
+    pub black_king: Texture2D,
+    pub no_piece: Texture2D,
+    pub is_piece: Texture2D,
+}
+
+impl Textures {
+    pub fn piece(&self, piece: Piece) -> &Texture2D {
+        match piece {
+            Piece::Pawn(Color::White) => &self.white_pawn,
+            Piece::Knight(Color::White) => &self.white_knight,
+            Piece::Bishop(Color::White) => &self.white_bishop,
+            Piece::Rook(Color::White) => &self.white_rook,
+            Piece::Queen(Color::White) => &self.white_queen,
+            Piece::King(Color::White) => &self.white_king,
+            Piece::Pawn(Color::Black) => &self.black_pawn,
+            Piece::Knight(Color::Black) => &self.black_knight,
+            Piece::Bishop(Color::Black) => &self.black_bishop,
+            Piece::Rook(Color::Black) => &self.black_rook,
+            Piece::Queen(Color::Black) => &self.black_queen,
+            Piece::King(Color::Black) => &self.black_king,
+        }
+    }
+}
+
+/// Load the board and the piece set from its directory, the pieces are PNG images named like white-knight.png
+pub async fn load_textures(pieces: &str, theme: BoardTheme) -> Result<Textures, String> {
+    let board = load_texture("assets/8x8-board.png")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let white_pawn = load_piece(pieces, "white-pawn").await?;
+    let white_knight = load_piece(pieces, "white-knight").await?;
+    let white_bishop = load_piece(pieces, "white-bishop").await?;
+    let white_rook = load_piece(pieces, "white-rook").await?;
+    let white_queen = load_piece(pieces, "white-queen").await?;
+    let white_king = load_piece(pieces, "white-king").await?;
+
+    let black_pawn = load_piece(pieces, "black-pawn").await?;
+    let black_knight = load_piece(pieces, "black-knight").await?;
+    let black_bishop = load_piece(pieces, "black-bishop").await?;
+    let black_rook = load_piece(pieces, "black-rook").await?;
+    let black_queen = load_piece(pieces, "black-queen").await?;
+    let black_king = load_piece(pieces, "black-king").await?;
+
+    let no_piece = load_texture("assets/highlight_circle.png")
+        .await
+        .map_err(|e| e.to_string())?;
+    let is_piece = load_texture("assets/hollow_circle.png")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Textures {
+        board,
+        theme,
+        white_pawn,
+        white_knight,
+        white_bishop,
+        white_rook,
+        white_queen,
+        white_king,
+        black_pawn,
+        black_knight,
+        black_bishop,
+        black_rook,
+        black_queen,
+        black_king,
+        no_piece,
+        is_piece,
+    })
+}
+
+// Define the skin for the UI
+/// Image of a piece in the directory of a piece set. SVG images can't be drawn, they have to be converted to PNG first
+async fn load_piece(directory: &str, name: &str) -> Result<Texture2D, String> {
+    let path = Path::new(directory).join(format!("{}.png", name));
+    let svg_path = path.with_extension("svg");
+    if !path.exists() && svg_path.exists() {
+        return Err(format!("{}: SVG pieces have to be converted to PNG", svg_path.display()));
+    }
+    load_texture(&path.to_string_lossy()).await.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Draw the game with its last move and a king in check highlighted,
+/// without the pieces of the hidden squares, which are dragged or moving
+pub fn draw_board(game: &Game, hidden: &[Square], textures: &Textures) {
+    draw_board_background(textures);
+    draw_highlights(game);
+    draw_pieces(|square| game.piece_at(square).filter(|_| !hidden.contains(&square)), textures);
+    if input::coordinates_shown() {
+        draw_coordinates();
+    }
+}
+
+/// Draw the board with the pieces given for each square, also used for positions that are not games yet
+pub fn draw_position(piece_at: impl Fn(Square) -> Option<Piece>, textures: &Textures) {
+    draw_board_background(textures);
+    draw_pieces(piece_at, textures);
+    if input::coordinates_shown() {
+        draw_coordinates();
+    }
+}
+
+/// Draw the empty board, the image or the squares in the colors of the theme
+fn draw_board_background(textures: &Textures) {
+    let (light, dark, border) = match textures.theme.colors() {
+        Some(colors) => colors,
+        None => {
+            draw_texture_ex(
+                &textures.board,
+                0.0,
+                BOARD_TOP,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(BOARD_SIZE, BOARD_SIZE)),
+                    ..Default::default()
+                },
+            );
+            return;
+        }
+    };
+    draw_rectangle(0.0, BOARD_TOP, BOARD_SIZE, BOARD_SIZE, border);
+    for square in Square::all() {
+        let (x, y) = input::square_position(square);
+        let color = if square.color() == Color::Black { dark } else { light };
+        draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, color);
+    }
+}
+
+/// Write the files along the bottom of the board and the ranks along its left side, in the corners of the edge squares
+fn draw_coordinates() {
+    let (bottom_rank, left_file) = match input::orientation() {
+        Color::White => (Rank::First, File::A),
+        Color::Black => (Rank::Eighth, File::H),
+    };
+    for file in File::ALL {
+        let (x, y) = input::square_position(Square::new(file, bottom_rank));
+        draw_text(&file.to_string(), x + TILE_SIZE - 14.0, y + TILE_SIZE - 6.0, 22.0, DARKGRAY);
+    }
+    for rank in Rank::ALL {
+        let (x, y) = input::square_position(Square::new(left_file, rank));
+        draw_text(&rank.to_string(), x + 4.0, y + 18.0, 22.0, DARKGRAY);
+    }
+}
+
+/// Color the squares of the last move, and the square of the king of the side to move when it is in check
+fn draw_highlights(game: &Game) {
+    let last_move_squares = match game.history().last() {
+        Some(Move::Normal { start, end, .. }) => vec![*start, *end],
+        Some(Move::Drop { square, .. }) => vec![*square],
+        None => Vec::new(),
+    };
+    for square in last_move_squares {
+        let (x, y) = input::square_position(square);
+        draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, LAST_MOVE_COLOR);
+    }
+
+    if game.is_in_check() {
+        let king = Piece::new(PieceKind::King, game.turn());
+        let square = Square::all().find(|&square| game.piece_at(square) == Some(king));
+        if let Some(square) = square {
+            let (x, y) = input::square_position(square);
+            draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, CHECK_COLOR);
+        }
+    }
+}
+
+fn draw_pieces(piece_at: impl Fn(Square) -> Option<Piece>, textures: &Textures) {
+    for square in Square::all() {
+        if let Some(piece) = piece_at(square) {
+            let (x, y) = input::square_position(square);
+            draw_piece(piece, (x, y), WHITE, textures);
+        }
+    }
+}
+
+/// Draw a piece the size of a square with its top left corner at a position of the layout, tinted by color
+pub fn draw_piece(piece: Piece, (x, y): (f32, f32), color: macroquad::color::Color, textures: &Textures) {
+    draw_texture_ex(
+        textures.piece(piece),
+        x,
+        y,
+        color,
+        DrawTextureParams {
+            dest_size: Some(vec2(TILE_SIZE, TILE_SIZE)),
+            ..Default::default()
+        },
+    );
+}
+
+/// Color the squares of the move queued for the next turn
+pub fn draw_premove((start, end): (Square, Square)) {
+    for square in [start, end] {
+        let (x, y) = input::square_position(square);
+        draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, PREMOVE_COLOR);
+    }
+}
+
+pub fn show_legal_moves(game: &Game, selected: Option<Square>, textures: &Textures) {
+    if game.endgame_status() != EndgameStatus::Ongoing {
+        return;
+    }
+
+    if let Some(selected) = selected {
+        if game.piece_at(selected).is_some() {
+            let legal_moves = game.get_legal_moves(selected);
+            for square in legal_moves {
+                let (x, y) = input::square_position(square);
+                if game.piece_at(square).is_some() {
+                    draw_texture_ex(
+                        &textures.is_piece,
+                        x,
+                        y,
+                        WHITE,
+                        DrawTextureParams {
+                            dest_size: Some(vec2(TILE_SIZE, TILE_SIZE)),
+                            ..Default::default()
+                        },
+                    );
+                } else {
+                    draw_texture_ex(
+                        &textures.no_piece,
+                        x,
+                        y,
+                        WHITE,
+                        DrawTextureParams {
+                            dest_size: Some(vec2(TILE_SIZE, TILE_SIZE)),
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub fn show_legal_drops(game: &Game, kind: PieceKind, textures: &Textures) {
+    for square in game.get_legal_drops(kind) {
+        let (x, y) = input::square_position(square);
+        draw_texture_ex(
+            &textures.no_piece,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(TILE_SIZE, TILE_SIZE)),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Draw the pockets on the panel right of the board, empty slots are faded
+pub fn draw_pockets(game: &Game, textures: &Textures) {
+    for color in [Color::White, Color::Black] {
+        let pocket = game.pocket(color);
+        for (index, kind) in Pocket::KINDS.iter().enumerate() {
+            let (x, y) = input::pocket_slot(color, index);
+            let count = pocket.count(*kind);
+            let tint = if count == 0 { EMPTY_POCKET_TINT } else { WHITE };
+
+            draw_texture_ex(
+                textures.piece(Piece::new(*kind, color)),
+                x,
+                y,
+                tint,
+                DrawTextureParams {
+                    dest_size: Some(vec2(POCKET_TILE_SIZE, POCKET_TILE_SIZE)),
+                    ..Default::default()
+                },
+            );
+            if count > 1 {
+                draw_text(&count.to_string(), x + POCKET_TILE_SIZE - 14.0, y + POCKET_TILE_SIZE - 4.0, 28.0, BLACK);
+            }
+        }
+    }
+}
+
+/// Draw the pieces each side has captured, the captures of the color at the bottom below the board and the others above,
+/// with the material advantage next to the side that is ahead. The pockets already show them in drop variants
+pub fn draw_captured_pieces(game: &Game, textures: &Textures) {
+    if game.variant().has_drops() {
+        return;
+    }
+    let material = |color: Color| -> i32 {
+        Square::all()
+            .filter_map(|square| game.piece_at(square))
+            .filter(|piece| piece.color() == color)
+            .map(|piece| piece_value(piece.kind()))
+            .sum()
+    };
+    let advantage = material(Color::White) - material(Color::Black);
+
+    for color in [Color::White, Color::Black] {
+        // The pieces captured by a color are the missing pieces of the other one
+        let captured = missing_pieces(game, color.opposite());
+        // Each side's captures are on its side of the board
+        let y = if color == input::orientation() {
+            BOARD_TOP + BOARD_SIZE + (CAPTURED_BAR_HEIGHT - CAPTURED_PIECE_SIZE) / 2.0
+        } else {
+            (CAPTURED_BAR_HEIGHT - CAPTURED_PIECE_SIZE) / 2.0
+        };
+        let mut x = BORDER_SIZE;
+        for (index, kind) in captured.iter().enumerate() {
+            // Pieces of the same kind overlap
+            if index > 0 && captured[index - 1] != *kind {
+                x += CAPTURED_PIECE_SIZE * 0.4;
+            }
+            draw_texture_ex(
+                textures.piece(Piece::new(*kind, color.opposite())),
+                x,
+                y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(CAPTURED_PIECE_SIZE, CAPTURED_PIECE_SIZE)),
+                    ..Default::default()
+                },
+            );
+            x += CAPTURED_PIECE_SIZE * 0.5;
+        }
+        let ahead = match color {
+            Color::White => advantage,
+            Color::Black => -advantage,
+        };
+        if ahead > 0 {
+            draw_text(&format!("+{}", ahead), x + CAPTURED_PIECE_SIZE + 4.0, y + CAPTURED_PIECE_SIZE - 6.0, 24.0, DARKGRAY);
+        }
+    }
+}
+
+/// Pieces of a color that are not on the board compared to the starting position, from the pawns to the queen
+fn missing_pieces(game: &Game, color: Color) -> Vec<PieceKind> {
+    let mut missing = Vec::new();
+    for (kind, count) in [(PieceKind::Pawn, 8), (PieceKind::Knight, 2), (PieceKind::Bishop, 2), (PieceKind::Rook, 2), (PieceKind::Queen, 1)] {
+        let on_board = Square::all()
+            .filter(|&square| game.piece_at(square) == Some(Piece::new(kind, color)))
+            .count();
+        missing.extend(std::iter::repeat_n(kind, count - on_board.min(count)));
+    }
+    missing
+}
+
+/// Usual value of a piece in pawns, the king doesn't count
+fn piece_value(kind: PieceKind) -> i32 {
+    match kind {
+        PieceKind::Pawn => 1,
+        PieceKind::Knight | PieceKind::Bishop => 3,
+        PieceKind::Rook => 5,
+        PieceKind::Queen => 9,
+        PieceKind::King => 0,
+    }
+}
+
+/// Draw an arrow over the board for the first move of each line, the best line is the most visible
+pub fn draw_best_move_arrows(lines: &[Line]) {
+    for (index, line) in lines.iter().enumerate().rev() {
+        let color = macroquad::color::Color::new(0.1, 0.4, 0.9, 0.8 - 0.2 * index as f32);
+        if let Some(mv) = line.moves.first() {
+            draw_move_arrow(*mv, color);
+        }
+    }
+}
+
+/// An arrow for a move on the board, a circle around the square for a drop
+pub fn draw_move_arrow(mv: Move, color: macroquad::color::Color) {
+    match mv {
+        Move::Normal { start, end, .. } => draw_arrow(square_center(start), square_center(end), color),
+        Move::Drop { square, .. } => {
+            let (x, y) = square_center(square);
+            draw_circle_lines(x, y, TILE_SIZE * 0.4, 6.0, color);
+        }
+    }
+}
+
+fn square_center(square: Square) -> (f32, f32) {
+    let (x, y) = input::square_position(square);
+    (x + TILE_SIZE / 2.0, y + TILE_SIZE / 2.0)
+}
+
+fn draw_arrow(from: (f32, f32), to: (f32, f32), color: macroquad::color::Color) {
+    let direction = vec2(to.0 - from.0, to.1 - from.1).normalize();
+    let normal = vec2(-direction.y, direction.x);
+    let head_length = TILE_SIZE * 0.35;
+    let tip = vec2(to.0, to.1);
+    let base = tip - direction * head_length;
+
+    draw_line(from.0, from.1, base.x, base.y, 12.0, color);
+    draw_triangle(tip, base + normal * head_length * 0.6, base - normal * head_length * 0.6, color);
+}
+
+/// Draw a piece taken from a pocket under the mouse
+pub fn draw_dragged_piece(piece: Piece, textures: &Textures) {
+    let (x, y) = input::mouse_in_layout();
+    draw_texture_ex(
+        textures.piece(piece),
+        x - TILE_SIZE / 2.0,
+        y - TILE_SIZE / 2.0,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(TILE_SIZE, TILE_SIZE)),
+            ..Default::default()
+        },
+    );
+}
+
+pub fn show_promotion_menu((file, color): (File, Color), textures: &Textures) {
+    let pieces = [
+        Piece::Queen(color),
+        Piece::Rook(color),
+        Piece::Bishop(color),
+        Piece::Knight(color),
+    ];
+    // Draw the piece textures in white squares, from the promotion square towards the middle of the board
+    let last_rank = Rank::back_rank(color.opposite());
+    let step = if color == Color::White { -1 } else { 1 };
+    for (distance, piece) in (0..).zip(pieces.iter()) {
+        let Some(rank) = last_rank.offset(step * distance) else {
+            continue;
+        };
+        let (x, y) = input::square_position(Square::new(file, rank));
+        let texture = textures.piece(*piece);
+        draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, WHITE);
+        draw_texture_ex(
+            texture,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(TILE_SIZE, TILE_SIZE)),
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,16 @@
+pub const BOARD_SIZE: f32 = 784.0; // Full board size including borders
+pub const BORDER_SIZE: f32 = 8.0; // Size of the border on each side
+pub const CAPTURED_BAR_HEIGHT: f32 = 36.0; // Bars above and below the board with the captured pieces
+pub const BOARD_TOP: f32 = CAPTURED_BAR_HEIGHT; // The board is drawn under the bar of captures of the color at the top
+pub const WINDOW_HEIGHT: f32 = BOARD_SIZE + 2.0 * CAPTURED_BAR_HEIGHT;
+pub const CAPTURED_PIECE_SIZE: f32 = 28.0; // Size of a captured piece in the bars
+pub const PLAYABLE_SIZE: f32 = BOARD_SIZE - BORDER_SIZE * 2.0; // Playable area size
+pub const TILE_SIZE: f32 = PLAYABLE_SIZE / 8.0; // Size of each square (96 pixels)
+pub const POCKET_PANEL_WIDTH: f32 = TILE_SIZE; // Panel on the right of the board holding the pockets in drop variants
+pub const POCKET_TILE_SIZE: f32 = TILE_SIZE * 0.75; // Size of a piece in a pocket
+pub const SIDE_PANEL_WIDTH: f32 = TILE_SIZE * 2.5; // Panel on the right of the board with the moves, and the analysis or the controls of each game
+pub const EVAL_BAR_WIDTH: f32 = 24.0; // Evaluation bar on the left of the side panel in analysis mode
+pub const ANALYSIS_LINES: usize = 3; // Best lines shown in analysis mode
+pub const ANALYSIS_MOVES: usize = 10; // Moves shown for each line
+pub const REVIEW_DEPTH: u32 = 3; // Depth of the search on each position of a reviewed game
+pub const SETTINGS_FILE: &str = "chess_settings.txt"; // Board theme, piece set and coordinates, kept between runs
@@ -0,0 +1,109 @@
+use std::cell::Cell;
+
+use chess_core::{Color, DrawReason, EndgameStatus, Game, WinReason};
+use macroquad::prelude::{draw_rectangle, draw_text, measure_text, vec2, RectOffset, BLACK, GREEN, WHITE};
+use macroquad::ui::{hash, root_ui, widgets, Skin};
+
+use crate::constants::{BOARD_SIZE, BOARD_TOP};
+use crate::input;
+
+pub fn create_skin() {
+    let button_style = root_ui()
+        .style_builder()
+        .color(GREEN)
+        .margin(RectOffset::new(20.0, 20.0, 10.0, 10.0))
+        .font_size(30)
+        .build();
+
+    let label_style = root_ui().style_builder().font_size(40).build();
+
+    let ui_skin = Skin {
+        button_style,
+        label_style,
+        ..root_ui().default_skin()
+    };
+
+    root_ui().push_skin(&ui_skin);
+}
+
+pub fn draw_game_over_box(should_quit: &Cell<bool>, should_restart: &Cell<bool>, should_review: &Cell<bool>, game: &Game) {
+    // Define the size of the box.
+    let box_width = 300.0;
+    let box_height = 250.0;
+
+    // Center the box on the board.
+    let box_x = (BOARD_SIZE - box_width) / 2.0;
+    let box_y = BOARD_TOP + (BOARD_SIZE - box_height) / 2.0;
+
+    let winner_text = match game.endgame_status() {
+        EndgameStatus::Draw(DrawReason::Stalemate) => "It's a draw by stalemate!",
+        EndgameStatus::Draw(DrawReason::FiftyMoveRule) => "It's a draw by the fifty-move rule!",
+        EndgameStatus::Draw(DrawReason::ThreefoldRepetition) => "It's a draw by threefold repetition!",
+        EndgameStatus::Draw(DrawReason::FivefoldRepetition) => "It's a draw by fivefold repetition!",
+        EndgameStatus::Draw(DrawReason::SeventyFiveMoveRule) => "It's a draw by the seventy-five-move rule!",
+        EndgameStatus::Draw(DrawReason::InsufficientMaterial) => "It's a draw by insufficient material!",
+        EndgameStatus::Draw(DrawReason::Agreement) => "It's a draw by mutual agreement!",
+        EndgameStatus::Win(Color::White, WinReason::Checkmate) => "White wins by checkmate!",
+        EndgameStatus::Win(Color::White, WinReason::Timeout) => "White wins by time-out!",
+        EndgameStatus::Win(Color::White, WinReason::Resignation) => "White wins by resignation!",
+        EndgameStatus::Win(Color::Black, WinReason::Checkmate) => "Black wins by checkmate!",
+        EndgameStatus::Win(Color::Black, WinReason::Timeout) => "Black wins by time-out!",
+        EndgameStatus::Win(Color::Black, WinReason::Resignation) => "Black wins by resignation!",
+        _ => "",
+    };
+
+    let text_size = measure_text(winner_text, None, 40, 1.0).width;
+    let text_x = (box_width - text_size) / 2.0;
+
+    let button1_size = measure_text("Restart", None, 30, 1.0).width + 40.0;
+    let button1_x = (box_width - button1_size) / 2.0;
+
+    let button2_size = measure_text("Quit", None, 30, 1.0).width + 40.0;
+    let button2_x = (box_width - button2_size) / 2.0;
+
+    let button3_size = measure_text("Review", None, 30, 1.0).width + 40.0;
+    let button3_x = (box_width - button3_size) / 2.0;
+
+    // Draw a simple UI box (group) at the computed position and size.
+    // The box keeps its size when the window is resized, only its position follows the board
+    let (screen_x, screen_y) = input::to_screen((box_x + box_width / 2.0, box_y + box_height / 2.0));
+    widgets::Window::new(hash!("game_over_box"), vec2(screen_x - box_width / 2.0, screen_y - box_height / 2.0), vec2(box_width, box_height))
+        .titlebar(false)
+        .movable(false)
+        .ui(&mut root_ui(), |ui| {
+            // Display the winner text.
+            ui.label(vec2(text_x, 15.0), winner_text);
+
+            // You can add spacing here if desired, e.g., a separator:
+            ui.separator();
+
+            // Draw a "Restart" button.
+            if ui.button(vec2(button1_x, 60.0), "Restart") {
+                // A new game needs a new opponent, so the connection is restarted
+                should_restart.set(true);
+            }
+
+            // Draw a "Quit" button.
+            if ui.button(vec2(button2_x, 120.0), "Quit") {
+                // Insert code here to quit the game.
+                should_quit.set(true);
+            }
+
+            // Draw a "Review" button to go through the game move by move.
+            if ui.button(vec2(button3_x, 180.0), "Review") {
+                should_review.set(true);
+            }
+        });
+}
+
+/// Draw a message in the middle of the board, e.g. to explain why a setup is invalid
+pub fn draw_message_box(text: &str) {
+    let text_size = measure_text(text, None, 30, 1.0);
+    let box_width = text_size.width + 40.0;
+    let box_height = text_size.height + 40.0;
+    let box_x = (BOARD_SIZE - box_width) / 2.0;
+    let box_y = BOARD_TOP + (BOARD_SIZE - box_height) / 2.0;
+
+    draw_rectangle(box_x, box_y, box_width, box_height, WHITE);
+    draw_text(text, box_x + 20.0, box_y + 20.0 + text_size.offset_y, 30.0, BLACK);
+}
@@ -0,0 +1,195 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use chess_core::{Color, File, GameController, PieceKind, Pocket, PromotionPiece, Rank, Square, Variant};
+use macroquad::prelude::*;
+
+use crate::constants::{
+    BOARD_SIZE, BOARD_TOP, BORDER_SIZE, PLAYABLE_SIZE, POCKET_PANEL_WIDTH, POCKET_TILE_SIZE, SIDE_PANEL_WIDTH, TILE_SIZE,
+    WINDOW_HEIGHT,
+};
+
+// The board is drawn with black at the bottom
+static FLIPPED: AtomicBool = AtomicBool::new(false);
+
+// The files and ranks are written on the board
+static COORDINATES: AtomicBool = AtomicBool::new(false);
+
+// Size of the window compared to the layout, as the bits of an f32
+static SCALE: AtomicU32 = AtomicU32::new(0x3f80_0000);
+
+/// The pieces of the promotion menu are stacked from the promotion square towards the middle of the board
+pub fn select_promotion_piece(square: Square, file: File, color: Color) -> Option<PromotionPiece> {
+    if square.file() != file {
+        return None;
+    }
+    match Rank::back_rank(color.opposite()).index().abs_diff(square.rank().index()) {
+        0 => Some(PromotionPiece::Queen),
+        1 => Some(PromotionPiece::Rook),
+        2 => Some(PromotionPiece::Bishop),
+        3 => Some(PromotionPiece::Knight),
+        _ => None,
+    }
+}
+
+/// Select a square on the board, updating the selected square and the previously selected square
+pub fn select_square() -> Option<Square> {
+    let mouse_pos = mouse_in_layout();
+    if mouse_pos.0 >= BORDER_SIZE
+        && mouse_pos.0 <= BORDER_SIZE + PLAYABLE_SIZE
+        && mouse_pos.1 >= BOARD_TOP + BORDER_SIZE
+        && mouse_pos.1 <= BOARD_TOP + BORDER_SIZE + PLAYABLE_SIZE
+    {
+        
+        let col = (((mouse_pos.0 - BORDER_SIZE) / TILE_SIZE) as usize).min(7);
+        let row = (((mouse_pos.1 - BOARD_TOP - BORDER_SIZE) / TILE_SIZE) as usize).min(7);
+        let (rank, file) = match orientation() {
+            Color::White => (7 - row, col),
+            Color::Black => (row, 7 - col),
+        };
+        return Some(Square::new(File::ALL[file], Rank::ALL[rank]));
+    }
+    None
+}
+
+/// Moves made with the mouse, by clicking the piece then its square or by dragging the piece there
+#[derive(Debug, Default)]
+pub struct MoveInput {
+    selected: Option<Square>,
+    dragging: bool, // The selected piece follows the cursor while the button is held
+    was_selected: bool, // Releasing the button on the piece that was already selected unselects it
+}
+
+impl MoveInput {
+    pub fn selected(&self) -> Option<Square> {
+        self.selected
+    }
+
+    /// Square of the piece following the cursor
+    pub fn dragged(&self) -> Option<Square> {
+        self.selected.filter(|_| self.dragging)
+    }
+
+    pub fn clear(&mut self) {
+        *self = MoveInput::default();
+    }
+
+    /// Picks up the pieces of the given color and calls play with the game and the start and end squares of a move,
+    /// play returns whether the move was made. A piece dropped on a square it can't go to goes back to its square
+    pub fn update(
+        &mut self,
+        game: &mut GameController,
+        color: Color,
+        mut play: impl FnMut(&mut GameController, Square, Square) -> bool,
+    ) {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let square = match select_square() {
+                Some(square) => square,
+                None => {
+                    self.clear();
+                    return;
+                }
+            };
+            if let Some(start) = self.selected.filter(|&start| start != square) {
+                if play(game, start, square) {
+                    self.clear();
+                    return;
+                }
+            }
+            let own_piece = game.game().piece_at(square).is_some_and(|piece| piece.color() == color);
+            self.was_selected = self.selected == Some(square);
+            self.selected = own_piece.then_some(square);
+            self.dragging = own_piece;
+        } else if self.dragging && is_mouse_button_released(MouseButton::Left) {
+            self.dragging = false;
+            match (self.selected, select_square()) {
+                (Some(start), Some(end)) if start != end && play(game, start, end) => self.clear(),
+                (Some(start), Some(end)) if start == end && self.was_selected => self.selected = None,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Top left corner of a square on the screen, with the color of the orientation at the bottom
+pub fn square_position(square: Square) -> (f32, f32) {
+    let (i, j) = (square.rank().index(), square.file().index());
+    let (row, col) = match orientation() {
+        Color::White => (7 - i, j),
+        Color::Black => (i, 7 - j),
+    };
+    (BORDER_SIZE + col as f32 * TILE_SIZE, BOARD_TOP + BORDER_SIZE + row as f32 * TILE_SIZE)
+}
+
+/// Scales the layout to fit the window, keeping its proportions. Everything is then drawn in the coordinates of the layout
+pub fn fit_to_window(variant: Variant) {
+    let width = side_panel_x(variant) + SIDE_PANEL_WIDTH;
+    let scale = (screen_width() / width).min(screen_height() / WINDOW_HEIGHT);
+    SCALE.store(scale.to_bits(), Ordering::Relaxed);
+    set_camera(&Camera2D::from_display_rect(Rect::new(0.0, 0.0, screen_width() / scale, screen_height() / scale)));
+}
+
+fn scale() -> f32 {
+    f32::from_bits(SCALE.load(Ordering::Relaxed))
+}
+
+/// Position of the mouse in the coordinates of the layout
+pub fn mouse_in_layout() -> (f32, f32) {
+    let (x, y) = mouse_position();
+    (x / scale(), y / scale())
+}
+
+/// Position on the screen of a point of the layout, for the widgets which are drawn without the scaling
+pub fn to_screen((x, y): (f32, f32)) -> (f32, f32) {
+    (x * scale(), y * scale())
+}
+
+/// Color shown at the bottom of the board
+pub fn orientation() -> Color {
+    if FLIPPED.load(Ordering::Relaxed) { Color::Black } else { Color::White }
+}
+
+pub fn set_orientation(color: Color) {
+    FLIPPED.store(color == Color::Black, Ordering::Relaxed);
+}
+
+/// Turns the board around
+pub fn flip_board() {
+    set_orientation(orientation().opposite());
+}
+
+pub fn coordinates_shown() -> bool {
+    COORDINATES.load(Ordering::Relaxed)
+}
+
+pub fn toggle_coordinates() {
+    COORDINATES.fetch_xor(true, Ordering::Relaxed);
+}
+
+/// Top left corner of the pocket slot at that index. The pocket of the color at the bottom of the board is at the bottom of the panel
+pub fn pocket_slot(color: Color, index: usize) -> (f32, f32) {
+    let x = BOARD_SIZE + (POCKET_PANEL_WIDTH - POCKET_TILE_SIZE) / 2.0;
+    let y = if color == orientation() {
+        BOARD_TOP + BOARD_SIZE - BORDER_SIZE - (index + 1) as f32 * POCKET_TILE_SIZE
+    } else {
+        BOARD_TOP + BORDER_SIZE + index as f32 * POCKET_TILE_SIZE
+    };
+    (x, y)
+}
+
+/// Select a piece in the pocket of the given color
+pub fn select_pocket_piece(color: Color) -> Option<PieceKind> {
+    let mouse_pos = mouse_in_layout();
+    Pocket::KINDS.iter().enumerate().find_map(|(index, kind)| {
+        let (x, y) = pocket_slot(color, index);
+        let inside = mouse_pos.0 >= x
+            && mouse_pos.0 <= x + POCKET_TILE_SIZE
+            && mouse_pos.1 >= y
+            && mouse_pos.1 <= y + POCKET_TILE_SIZE;
+        inside.then_some(*kind)
+    })
+}
+
+/// Left edge of the side panel, after the pockets in drop variants
+pub fn side_panel_x(variant: Variant) -> f32 {
+    if variant.has_drops() { BOARD_SIZE + POCKET_PANEL_WIDTH } else { BOARD_SIZE }
+}
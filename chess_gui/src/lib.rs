@@ -0,0 +1,11 @@
+//! Board widget shared by the GUIs: rendering, move animation and sound, mouse input, dialogs and side panels, drawn with macroquad
+
+pub mod animation;
+pub mod board;
+pub mod constants;
+pub mod dialogs;
+pub mod input;
+pub mod panels;
+pub mod review;
+pub mod settings;
+pub mod sound;
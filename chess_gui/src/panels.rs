@@ -0,0 +1,236 @@
+use chess_core::review::{Judgement, MoveReview, Review, ReviewError};
+use chess_core::search::{Line, LineScore};
+use chess_core::{Color, Game, GameTree, NodeId, TreeToken};
+use macroquad::prelude::{
+    draw_rectangle, draw_rectangle_lines, draw_text, is_mouse_button_pressed, measure_text, MouseButton, BLACK, DARKGRAY,
+    WHITE,
+};
+
+use crate::board::draw_move_arrow;
+use crate::constants::{ANALYSIS_MOVES, BOARD_SIZE, BOARD_TOP, BORDER_SIZE, EVAL_BAR_WIDTH, WINDOW_HEIGHT};
+use crate::input;
+
+/// Draw the evaluation bar, the share of the color at the bottom of the board grows from the bottom as it gets better
+pub fn draw_eval_bar(x: f32, score: Option<LineScore>) {
+    let white_share = match score {
+        Some(LineScore::Centipawns(score)) => (0.5 + score as f32 / 2000.0).clamp(0.05, 0.95),
+        Some(LineScore::Mate(moves)) if moves > 0 => 1.0,
+        Some(LineScore::Mate(_)) => 0.0,
+        None => 0.5,
+    };
+    let height = BOARD_SIZE - 2.0 * BORDER_SIZE;
+    let top = BOARD_TOP + BORDER_SIZE;
+    draw_rectangle(x, top, EVAL_BAR_WIDTH, height, DARKGRAY);
+    let white_top = match input::orientation() {
+        Color::White => top + height * (1.0 - white_share),
+        Color::Black => top,
+    };
+    draw_rectangle(x, white_top, EVAL_BAR_WIDTH, height * white_share, WHITE);
+    draw_rectangle_lines(x, top, EVAL_BAR_WIDTH, height, 2.0, BLACK);
+}
+
+/// List the analysis lines with their score and depth, in SAN with move numbers, wrapped to the panel width
+pub fn draw_analysis_lines(x: f32, top: f32, bottom: f32, width: f32, lines: &[Line], game: &Game) {
+    draw_text("Analysis", x, top, 30.0, BLACK);
+    if lines.is_empty() {
+        draw_text("Thinking...", x, top + 40.0, 20.0, BLACK);
+        return;
+    }
+
+    let mut y = top + 40.0;
+    for line in lines {
+        if y > bottom {
+            break;
+        }
+        draw_text(&format!("{}  (depth {})", line.score, line.depth), x, y, 22.0, BLACK);
+        y += 24.0;
+
+        let mut row = String::new();
+        for word in game.numbered_san(&line.san).into_iter().take(ANALYSIS_MOVES) {
+            let candidate = if row.is_empty() { word.clone() } else { format!("{} {}", row, word) };
+            if measure_text(&candidate, None, 20, 1.0).width > width && !row.is_empty() {
+                draw_text(&row, x, y, 20.0, DARKGRAY);
+                y += 22.0;
+                row = word;
+            } else {
+                row = candidate;
+            }
+        }
+        draw_text(&row, x, y, 20.0, DARKGRAY);
+        y += 36.0;
+    }
+}
+
+/// Draw the move tree: the main line in black, the variations in parentheses in gray and the highlighted move on a blue background.
+/// When the tree doesn't fit, the rows around the highlighted move are shown. Returns the move clicked, if any
+pub fn draw_move_tree(x: f32, top: f32, bottom: f32, width: f32, tree: &GameTree, highlighted: NodeId) -> Option<NodeId> {
+    draw_text("Moves", x, top, 30.0, BLACK);
+
+    // Rows of words with their color and their move
+    let mut rows: Vec<Vec<(String, macroquad::color::Color, Option<NodeId>)>> = vec![Vec::new()];
+    let mut row_width = 0.0;
+    let mut depth = 0;
+    let mut highlighted_row = 0;
+    for token in tree.tokens() {
+        let (word, node) = match token {
+            TreeToken::Move(id, text) => (text, Some(id)),
+            TreeToken::Comment(_, comment) => (format!("{{{}}}", comment), None),
+            TreeToken::VariationStart => {
+                depth += 1;
+                ("(".to_string(), None)
+            }
+            TreeToken::VariationEnd => {
+                depth -= 1;
+                (")".to_string(), None)
+            }
+        };
+        let color = if depth > 0 || word == ")" { DARKGRAY } else { BLACK };
+        let word_width = measure_text(&word, None, 20, 1.0).width + 6.0;
+        if row_width + word_width > width && row_width > 0.0 {
+            rows.push(Vec::new());
+            row_width = 0.0;
+        }
+        if node == Some(highlighted) {
+            highlighted_row = rows.len() - 1;
+        }
+        row_width += word_width;
+        rows.last_mut().unwrap().push((word, color, node));
+    }
+
+    let (mouse_x, mouse_y) = input::mouse_in_layout();
+    let clicked = is_mouse_button_pressed(MouseButton::Left);
+    let mut selected = None;
+    let visible_rows = ((bottom - top - 30.0) / 22.0).max(1.0) as usize;
+    let first_row = (highlighted_row + 1).saturating_sub(visible_rows);
+    for (index, row) in rows.iter().skip(first_row).take(visible_rows).enumerate() {
+        let y = top + 36.0 + index as f32 * 22.0;
+        let mut word_x = x;
+        for (word, color, node) in row {
+            let word_width = measure_text(word, None, 20, 1.0).width;
+            if *node == Some(highlighted) {
+                draw_rectangle(word_x - 2.0, y - 16.0, word_width + 4.0, 21.0, macroquad::color::Color::new(0.6, 0.8, 1.0, 1.0));
+            }
+            let hovered = mouse_x >= word_x - 2.0 && mouse_x <= word_x + word_width + 2.0 && mouse_y >= y - 16.0 && mouse_y <= y + 5.0;
+            if hovered && clicked && node.is_some() {
+                selected = *node;
+            }
+            draw_text(word, word_x, y, 20.0, *color);
+            word_x += word_width + 6.0;
+        }
+    }
+    selected
+}
+
+/// Draw the moves of the game in SAN, numbered from the first move with white's and black's move on the same row.
+/// When the list doesn't fit, the rows around the highlighted move are shown. Returns the number of moves played
+/// up to the move clicked, if any
+pub fn draw_move_list(x: f32, top: f32, bottom: f32, sans: &[String], highlighted: usize) -> Option<usize> {
+    draw_text("Moves", x, top, 30.0, BLACK);
+
+    let (mouse_x, mouse_y) = input::mouse_in_layout();
+    let clicked = is_mouse_button_pressed(MouseButton::Left);
+    let mut selected = None;
+    let visible_rows = ((bottom - top - 30.0) / 22.0).max(1.0) as usize;
+    let highlighted_row = highlighted.saturating_sub(1) / 2;
+    let first_row = (highlighted_row + 1).saturating_sub(visible_rows);
+    for (index, row) in sans.chunks(2).enumerate().skip(first_row).take(visible_rows) {
+        let y = top + 36.0 + (index - first_row) as f32 * 22.0;
+        draw_text(&format!("{}.", index + 1), x, y, 20.0, DARKGRAY);
+        for (column, san) in row.iter().enumerate() {
+            let ply = 2 * index + column + 1; // Moves played once this one is
+            let word_x = x + 40.0 + column as f32 * 80.0;
+            let word_width = measure_text(san, None, 20, 1.0).width;
+            if ply == highlighted {
+                draw_rectangle(word_x - 2.0, y - 16.0, word_width + 4.0, 21.0, macroquad::color::Color::new(0.6, 0.8, 1.0, 1.0));
+            }
+            let hovered = mouse_x >= word_x - 2.0 && mouse_x <= word_x + word_width + 2.0 && mouse_y >= y - 16.0 && mouse_y <= y + 5.0;
+            if hovered && clicked {
+                selected = Some(ply);
+            }
+            draw_text(san, word_x, y, 20.0, BLACK);
+        }
+    }
+    selected
+}
+
+/// Show how far the review is on the side panel
+pub fn draw_review_progress(x: f32, done: usize, total: usize) {
+    draw_text("Review", x, 40.0, 30.0, BLACK);
+    draw_text(&format!("Move {}/{}", done, total), x, 80.0, 24.0, BLACK);
+    draw_text("Escape: back", x, WINDOW_HEIGHT - 20.0, 20.0, DARKGRAY);
+}
+
+pub fn draw_review_failed(x: f32, error: &ReviewError) {
+    draw_text("Review", x, 40.0, 30.0, BLACK);
+    draw_text(&format!("Failed: {}", error), x, 80.0, 20.0, BLACK);
+    draw_text("Escape: back", x, WINDOW_HEIGHT - 20.0, 20.0, DARKGRAY);
+}
+
+/// Draw the move played from the position in red when it is judged, and the best move in green
+pub fn draw_review_arrows(move_review: &MoveReview) {
+    if let Some(best) = move_review.best_line.as_ref().and_then(|line| line.moves.first()) {
+        let played_color = match move_review.judgement {
+            Some(_) => macroquad::color::Color::new(0.9, 0.2, 0.1, 0.8),
+            None => macroquad::color::Color::new(0.4, 0.4, 0.4, 0.6),
+        };
+        draw_move_arrow(move_review.mv, played_color);
+        draw_move_arrow(*best, macroquad::color::Color::new(0.1, 0.7, 0.2, 0.8));
+    }
+}
+
+/// List the accuracy of each player, then the judgement of the move played from the position and the best line
+pub fn draw_review(x: f32, width: f32, review: &Review, ply: usize, position: &Game) {
+    draw_text("Review", x, 40.0, 30.0, BLACK);
+
+    let mut y = 80.0;
+    for (name, color) in [("White", Color::White), ("Black", Color::Black)] {
+        let accuracy = review.accuracy(color).map_or("-".to_string(), |accuracy| format!("{:.1}%", accuracy));
+        draw_text(&format!("{} accuracy {}", name, accuracy), x, y, 22.0, BLACK);
+        let counts = [Judgement::Blunder, Judgement::Mistake, Judgement::Inaccuracy]
+            .map(|judgement| format!("{} {}", review.count(color, judgement), judgement.symbol()))
+            .join("  ");
+        draw_text(&counts, x, y + 22.0, 20.0, DARKGRAY);
+        y += 56.0;
+    }
+
+    y += 20.0;
+    match review.moves.get(ply) {
+        Some(move_review) => {
+            let number = match move_review.color {
+                Color::White => format!("{}.", position.fullmove_number()),
+                Color::Black => format!("{}...", position.fullmove_number()),
+            };
+            let symbol = move_review.judgement.map_or("", |judgement| judgement.symbol());
+            draw_text(&format!("Played {}{}{}", number, move_review.san, symbol), x, y, 24.0, BLACK);
+            y += 26.0;
+            if let Some(judgement) = move_review.judgement {
+                draw_text(&format!("{}, {:.2} lost", judgement, move_review.loss as f32 / 100.0), x, y, 20.0, DARKGRAY);
+                y += 22.0;
+            }
+            if let Some(best) = &move_review.best_line {
+                y += 10.0;
+                draw_text(&format!("Best line {}", best.score), x, y, 22.0, BLACK);
+                y += 24.0;
+                let mut row = String::new();
+                for word in position.numbered_san(&best.san).into_iter().take(ANALYSIS_MOVES) {
+                    let candidate = if row.is_empty() { word.clone() } else { format!("{} {}", row, word) };
+                    if measure_text(&candidate, None, 20, 1.0).width > width && !row.is_empty() {
+                        draw_text(&row, x, y, 20.0, DARKGRAY);
+                        y += 22.0;
+                        row = word;
+                    } else {
+                        row = candidate;
+                    }
+                }
+                draw_text(&row, x, y, 20.0, DARKGRAY);
+            }
+        }
+        None => {
+            draw_text("End of the game", x, y, 24.0, BLACK);
+        }
+    }
+
+    draw_text("Left/Right: step", x, WINDOW_HEIGHT - 64.0, 20.0, DARKGRAY);
+    draw_text("Home/End: first, last", x, WINDOW_HEIGHT - 42.0, 20.0, DARKGRAY);
+    draw_text("Escape: back", x, WINDOW_HEIGHT - 20.0, 20.0, DARKGRAY);
+}
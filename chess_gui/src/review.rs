@@ -0,0 +1,74 @@
+use chess_core::review::Reviewer;
+use chess_core::Game;
+use macroquad::prelude::{is_key_pressed, KeyCode};
+
+use crate::board::{self, Textures};
+use crate::constants::{REVIEW_DEPTH, SIDE_PANEL_WIDTH};
+use crate::{input, panels};
+
+/// Review of a finished game: every move is judged on a background thread, then the player steps through the game
+pub struct ReviewScreen {
+    reviewer: Reviewer,
+    ply: usize,
+    position: Game,
+}
+
+impl ReviewScreen {
+    pub fn new(game: &Game) -> ReviewScreen {
+        ReviewScreen {
+            reviewer: Reviewer::start(game, REVIEW_DEPTH),
+            ply: game.history().len(),
+            position: game.clone(),
+        }
+    }
+
+    /// Draw the reviewed game and handle its input, returns true once the player goes back to the game
+    pub fn run_frame(&mut self, textures: &Textures) -> bool {
+        let panel_x = input::side_panel_x(self.position.variant());
+        board::draw_board(&self.position, &[], textures);
+        board::draw_captured_pieces(&self.position, textures);
+        if self.position.variant().has_drops() {
+            board::draw_pockets(&self.position, textures);
+        }
+
+        let (done, total) = self.reviewer.progress();
+        let review = match self.reviewer.review() {
+            Some(Ok(review)) => review,
+            Some(Err(e)) => {
+                panels::draw_review_failed(panel_x + 20.0, &e);
+                return is_key_pressed(KeyCode::Escape);
+            }
+            None => {
+                panels::draw_review_progress(panel_x + 20.0, done, total);
+                return is_key_pressed(KeyCode::Escape);
+            }
+        };
+
+        let previous_ply = self.ply;
+        if is_key_pressed(KeyCode::Left) {
+            self.ply = self.ply.saturating_sub(1);
+        }
+        if is_key_pressed(KeyCode::Right) {
+            self.ply = (self.ply + 1).min(review.moves.len());
+        }
+        if is_key_pressed(KeyCode::Home) {
+            self.ply = 0;
+        }
+        if is_key_pressed(KeyCode::End) {
+            self.ply = review.moves.len();
+        }
+        if self.ply != previous_ply {
+            // The review played every move already
+            if let Ok(position) = review.position(self.ply) {
+                self.position = position;
+            }
+        }
+
+        if let Some(move_review) = review.moves.get(self.ply) {
+            panels::draw_review_arrows(move_review);
+        }
+        panels::draw_review(panel_x + 20.0, SIDE_PANEL_WIDTH - 28.0, review, self.ply, &self.position);
+
+        is_key_pressed(KeyCode::Escape)
+    }
+}
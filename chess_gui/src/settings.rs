@@ -0,0 +1,159 @@
+use std::fs;
+use std::io;
+
+use macroquad::color::Color;
+
+use crate::constants::SETTINGS_FILE;
+
+/// Colors of the board: the board image, or squares drawn in two colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardTheme {
+    Classic,
+    Brown,
+    Green,
+    Blue,
+}
+
+impl BoardTheme {
+    pub const ALL: [BoardTheme; 4] = [BoardTheme::Classic, BoardTheme::Brown, BoardTheme::Green, BoardTheme::Blue];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BoardTheme::Classic => "classic",
+            BoardTheme::Brown => "brown",
+            BoardTheme::Green => "green",
+            BoardTheme::Blue => "blue",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BoardTheme> {
+        BoardTheme::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    /// Colors of the light squares, the dark squares and the border. None for the board image
+    pub fn colors(self) -> Option<(Color, Color, Color)> {
+        match self {
+            BoardTheme::Classic => None,
+            BoardTheme::Brown => Some((
+                Color::new(0.94, 0.85, 0.71, 1.0),
+                Color::new(0.71, 0.53, 0.39, 1.0),
+                Color::new(0.45, 0.32, 0.22, 1.0),
+            )),
+            BoardTheme::Green => Some((
+                Color::new(0.93, 0.93, 0.82, 1.0),
+                Color::new(0.46, 0.59, 0.34, 1.0),
+                Color::new(0.28, 0.38, 0.2, 1.0),
+            )),
+            BoardTheme::Blue => Some((
+                Color::new(0.87, 0.89, 0.9, 1.0),
+                Color::new(0.55, 0.64, 0.68, 1.0),
+                Color::new(0.33, 0.4, 0.45, 1.0),
+            )),
+        }
+    }
+
+    /// The theme after this one, back to the first after the last
+    pub fn next(self) -> BoardTheme {
+        let index = BoardTheme::ALL.iter().position(|&theme| theme == self).unwrap_or(0);
+        BoardTheme::ALL[(index + 1) % BoardTheme::ALL.len()]
+    }
+}
+
+/// How long a piece takes to slide to its square
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationSpeed {
+    Off,
+    Fast,
+    Normal,
+    Slow,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [AnimationSpeed; 4] = [AnimationSpeed::Off, AnimationSpeed::Fast, AnimationSpeed::Normal, AnimationSpeed::Slow];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AnimationSpeed::Off => "off",
+            AnimationSpeed::Fast => "fast",
+            AnimationSpeed::Normal => "normal",
+            AnimationSpeed::Slow => "slow",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<AnimationSpeed> {
+        AnimationSpeed::ALL.into_iter().find(|speed| speed.name() == name)
+    }
+
+    /// Length of a move in seconds, 0 when moves appear at once
+    pub fn duration(self) -> f32 {
+        match self {
+            AnimationSpeed::Off => 0.0,
+            AnimationSpeed::Fast => 0.1,
+            AnimationSpeed::Normal => 0.2,
+            AnimationSpeed::Slow => 0.4,
+        }
+    }
+
+    /// The speed after this one, back to off after the slowest
+    pub fn next(self) -> AnimationSpeed {
+        let index = AnimationSpeed::ALL.iter().position(|&speed| speed == self).unwrap_or(0);
+        AnimationSpeed::ALL[(index + 1) % AnimationSpeed::ALL.len()]
+    }
+}
+
+/// Look of the game kept between runs, in a file of name=value lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub theme: BoardTheme,
+    pub pieces: String, // Directory of the piece images
+    pub coordinates: bool,
+    pub animation: AnimationSpeed,
+    pub sound: bool,
+    pub sounds: String, // Directory of the sound effects, replacing the built-in ones
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            theme: BoardTheme::Classic,
+            pieces: "assets".to_string(),
+            coordinates: false,
+            animation: AnimationSpeed::Normal,
+            sound: true,
+            sounds: "assets/sounds".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings file, missing or invalid values keep their default
+    pub fn load() -> Settings {
+        let mut settings = Settings::default();
+        let content = fs::read_to_string(SETTINGS_FILE).unwrap_or_default();
+        for line in content.lines() {
+            match line.split_once('=').map(|(name, value)| (name.trim(), value.trim())) {
+                Some(("theme", value)) => settings.theme = BoardTheme::from_name(value).unwrap_or(settings.theme),
+                Some(("pieces", value)) if !value.is_empty() => settings.pieces = value.to_string(),
+                Some(("coordinates", value)) => settings.coordinates = value == "true",
+                Some(("animation", value)) => settings.animation = AnimationSpeed::from_name(value).unwrap_or(settings.animation),
+                Some(("sound", value)) => settings.sound = value == "true",
+                Some(("sounds", value)) if !value.is_empty() => settings.sounds = value.to_string(),
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let content = format!(
+            "theme={}\npieces={}\ncoordinates={}\nanimation={}\nsound={}\nsounds={}\n",
+            self.theme.name(),
+            self.pieces,
+            self.coordinates,
+            self.animation.name(),
+            self.sound,
+            self.sounds
+        );
+        fs::write(SETTINGS_FILE, content)
+    }
+}
@@ -0,0 +1,126 @@
+use chess_core::GameEvent;
+
+/// Sounds of the game, ordered from the least to the most important
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SoundEffect {
+    Move,
+    Capture,
+    Castle,
+    Check,
+    GameEnd,
+}
+
+impl SoundEffect {
+    pub const ALL: [SoundEffect; 5] = [SoundEffect::Move, SoundEffect::Capture, SoundEffect::Castle, SoundEffect::Check, SoundEffect::GameEnd];
+
+    /// Name of the WAV file that replaces the built-in sound, in the sounds directory
+    pub fn file_name(self) -> &'static str {
+        match self {
+            SoundEffect::Move => "move.wav",
+            SoundEffect::Capture => "capture.wav",
+            SoundEffect::Castle => "castle.wav",
+            SoundEffect::Check => "check.wav",
+            SoundEffect::GameEnd => "game-end.wav",
+        }
+    }
+
+    /// The one sound for the events of a move, e.g. a capture that gives check sounds like a check
+    pub fn for_events(events: &[GameEvent]) -> Option<SoundEffect> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::MovePlayed(..) => Some(SoundEffect::Move),
+                GameEvent::Capture(..) => Some(SoundEffect::Capture),
+                GameEvent::Castle { .. } => Some(SoundEffect::Castle),
+                GameEvent::Check(_) => Some(SoundEffect::Check),
+                GameEvent::GameOver(_) => Some(SoundEffect::GameEnd),
+                _ => None,
+            })
+            .max()
+    }
+}
+
+/// The loaded sound effects. Without the sound feature of the crate nothing is loaded and nothing is played
+#[cfg(feature = "sound")]
+pub struct Sounds {
+    sounds: Vec<(SoundEffect, macroquad::audio::Sound)>,
+}
+
+#[cfg(not(feature = "sound"))]
+pub struct Sounds;
+
+#[cfg(feature = "sound")]
+impl Sounds {
+    /// Loads the effects from the files of the directory, a short tone stands in for a missing file
+    pub async fn load(directory: &str) -> Sounds {
+        let mut sounds = Vec::new();
+        for effect in SoundEffect::ALL {
+            let path = std::path::Path::new(directory).join(effect.file_name());
+            let bytes = std::fs::read(&path).unwrap_or_else(|_| tone(effect));
+            match macroquad::audio::load_sound_from_bytes(&bytes).await {
+                Ok(sound) => sounds.push((effect, sound)),
+                Err(e) => eprintln!("Could not load the sound {}: {}", path.display(), e),
+            }
+        }
+        Sounds { sounds }
+    }
+
+    /// Plays the sound of the events of a move
+    pub fn play(&self, events: &[GameEvent]) {
+        let Some(effect) = SoundEffect::for_events(events) else {
+            return;
+        };
+        if let Some((_, sound)) = self.sounds.iter().find(|(loaded, _)| *loaded == effect) {
+            macroquad::audio::play_sound_once(sound);
+        }
+    }
+}
+
+#[cfg(not(feature = "sound"))]
+impl Sounds {
+    pub async fn load(_directory: &str) -> Sounds {
+        Sounds
+    }
+
+    pub fn play(&self, _events: &[GameEvent]) {}
+}
+
+/// A fading beep as a 16-bit mono WAV file, higher for the more important sounds
+#[cfg(feature = "sound")]
+fn tone(effect: SoundEffect) -> Vec<u8> {
+    const RATE: u32 = 22050;
+    const LENGTH: f32 = 0.12; // Seconds
+    let frequency = match effect {
+        SoundEffect::Move => 440.0,
+        SoundEffect::Capture => 330.0,
+        SoundEffect::Castle => 523.0,
+        SoundEffect::Check => 660.0,
+        SoundEffect::GameEnd => 880.0,
+    };
+    let samples: Vec<i16> = (0..(RATE as f32 * LENGTH) as u32)
+        .map(|i| {
+            let time = i as f32 / RATE as f32;
+            let fade = 1.0 - time / LENGTH;
+            ((time * frequency * std::f32::consts::TAU).sin() * fade * 8000.0) as i16
+        })
+        .collect();
+
+    let data_size = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // Size of the format chunk
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    wav.extend_from_slice(&RATE.to_le_bytes());
+    wav.extend_from_slice(&(RATE * 2).to_le_bytes()); // Bytes per second
+    wav.extend_from_slice(&2u16.to_le_bytes()); // Bytes per sample
+    wav.extend_from_slice(&16u16.to_le_bytes()); // Bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
@@ -0,0 +1,85 @@
+use std::sync::mpsc;
+use std::thread;
+
+use chess_core::Variant;
+use crate::{decode, encode, ClientMessage, ServerMessage};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::runtime;
+use tokio::sync::mpsc as tokio_mpsc;
+
+pub enum NetworkEvent {
+    Message(ServerMessage),
+    Disconnected(String),
+}
+
+/// Connection to the server, running on its own thread so that the interface never waits on the network
+pub struct Connection {
+    outgoing: tokio_mpsc::UnboundedSender<ClientMessage>,
+    incoming: mpsc::Receiver<NetworkEvent>,
+}
+
+impl Connection {
+    /// Connects to the server and asks to join a game of that variant
+    pub fn connect(address: &'static str, variant: Variant) -> Connection {
+        let (outgoing, to_send) = tokio_mpsc::unbounded_channel();
+        let (events, incoming) = mpsc::channel();
+
+        let _ = outgoing.send(ClientMessage::JoinGame(variant));
+
+        thread::spawn(move || {
+            let runtime = runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to start the network runtime");
+            runtime.block_on(async move {
+                let reason = match run(address, to_send, &events).await {
+                    Ok(()) => "Connection closed".to_string(),
+                    Err(e) => e.to_string(),
+                };
+                let _ = events.send(NetworkEvent::Disconnected(reason));
+            });
+        });
+
+        Connection { outgoing, incoming }
+    }
+
+    pub fn send(&self, message: ClientMessage) {
+        let _ = self.outgoing.send(message);
+    }
+
+    /// Returns the next event received from the server, if any, without blocking
+    pub fn try_recv(&self) -> Option<NetworkEvent> {
+        self.incoming.try_recv().ok()
+    }
+}
+
+async fn run(
+    address: &str,
+    mut to_send: tokio_mpsc::UnboundedReceiver<ClientMessage>,
+    events: &mpsc::Sender<NetworkEvent>,
+) -> tokio::io::Result<()> {
+    let socket = TcpStream::connect(address).await?;
+    let (reader, mut writer) = socket.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line? {
+                Some(line) => match decode(&line) {
+                    Ok(message) => {
+                        if events.send(NetworkEvent::Message(message)).is_err() {
+                            return Ok(()); // The client was closed
+                        }
+                    }
+                    Err(e) => eprintln!("Invalid message {:?}: {}", line, e),
+                },
+                None => return Ok(()),
+            },
+            message = to_send.recv() => match message {
+                Some(message) => writer.write_all(encode(&message).as_bytes()).await?,
+                None => return Ok(()),
+            },
+        }
+    }
+}
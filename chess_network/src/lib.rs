@@ -1,23 +1,68 @@
-use chess_core::{PromotionPiece, EndgameStatus};
+pub mod client;
+
+use chess_core::{Color, EndgameStatus, Game, MoveError, PieceKind, PromotionPiece, Setup, Square, Variant};
 use serde::{Deserialize, Serialize};
 
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ClientMessage {
-    SendMove((usize, usize), (usize, usize), Option<PromotionPiece>),
+    JoinGame(Variant),
+    SendMove(Square, Square, Option<PromotionPiece>),
+    SendDrop(PieceKind, Square),
     Resign,
     OfferDraw,
     AcceptDraw,
     DeclineDraw,
+    /// Claim a draw by threefold repetition or the fifty-move rule
+    ClaimDraw,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ServerMessage {
+    GameStarted(Color, Variant),
     MoveAccepted,
-    MoveRejected,
-    OpponentMove((usize, usize), (usize, usize), Option<PromotionPiece>),
+    /// Why the last move was refused, the client can show it in its own words
+    MoveRejected(MoveError),
+    /// The FEN of the position as the server has it, sent after a rejected move so that the client gets back in step
+    Resync(String),
+    OpponentMove(Square, Square, Option<PromotionPiece>),
+    OpponentDrop(PieceKind, Square),
+    /// A piece captured on the partner's board in bughouse, to add to the pocket of its color
+    PieceReceived(Color, PieceKind),
     OfferDraw,
     AcceptDraw,
     DeclineDraw,
     GameOver(EndgameStatus)
-}
\ No newline at end of file
+}
+
+/// Messages are sent as one JSON object per line
+pub fn encode<T: Serialize>(message: &T) -> String {
+    let mut line = serde_json::to_string(message).expect("messages can always be serialized");
+    line.push('\n');
+    line
+}
+
+pub fn decode<'a, T: Deserialize<'a>>(line: &'a str) -> Result<T, serde_json::Error> {
+    serde_json::from_str(line.trim_end())
+}
+
+/// The game brought back in step with the position of a Resync message: the moves the server didn't accept are taken back,
+/// or the game starts again from that position when it is not one of the game's. None when the FEN can't be read
+pub fn resync(game: &Game, fen: &str) -> Option<Game> {
+    let mut synced = game.clone();
+    loop {
+        if synced.to_fen() == fen {
+            return Some(synced);
+        }
+        if synced.undo().is_none() {
+            break;
+        }
+    }
+    let mut setup = Setup::from_fen(fen).ok()?;
+    setup.variant = game.variant();
+    Game::from_setup(&setup).ok()
+}
+
+#[cfg(test)]
+#[path = "tests/test_resync.rs"]
+mod test_resync;
@@ -0,0 +1,25 @@
+use chess_core::{Game, Move, Square, Variant};
+
+use super::resync;
+
+#[test]
+fn test_resync_takes_back_rejected_moves() {
+    let mut game = Game::new();
+    game.play(Move::new(Square::E2, Square::E4)).unwrap();
+    let server_fen = game.to_fen();
+    game.play(Move::new(Square::E7, Square::E5)).unwrap();
+
+    let synced = resync(&game, &server_fen).unwrap();
+    assert_eq!(synced.to_fen(), server_fen);
+    assert_eq!(synced.history(), &[Move::new(Square::E2, Square::E4)], "The moves the server has are kept");
+}
+
+#[test]
+fn test_resync_from_another_position() {
+    let game = Game::with_variant(Variant::Bughouse);
+    let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR[Q] b KQkq - 0 1";
+    let synced = resync(&game, fen).unwrap();
+    assert_eq!(synced.to_fen(), fen);
+    assert_eq!(synced.variant(), Variant::Bughouse, "The variant of the game is kept");
+    assert!(resync(&game, "not a fen").is_none());
+}
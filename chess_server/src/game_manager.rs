@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use chess_core::{Color, EndgameStatus, Game, Move, MoveError, Piece, PromotionPiece, Square, Variant, WinReason, DrawReason};
+use chess_network::{ClientMessage, ServerMessage};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::players::{Player, Seat, WaitingPlayer};
+
+/// Pairs the connected players by variant and starts their games
+#[derive(Default)]
+pub struct GameManager {
+    waiting: Mutex<HashMap<Variant, Vec<WaitingPlayer>>>,
+    next_id: AtomicU64,
+}
+
+impl GameManager {
+    /// Puts the player in the queue of that variant, the game starts as soon as there are enough players.
+    /// Returns the id to leave the queue with, and the receiver of the player's seat
+    pub fn join(&self, variant: Variant, sender: mpsc::UnboundedSender<ServerMessage>) -> (u64, oneshot::Receiver<Seat>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (seat_sender, seat_receiver) = oneshot::channel();
+
+        let players = {
+            let mut waiting = self.waiting.lock().unwrap();
+            let players = waiting.entry(variant).or_default();
+            players.push(WaitingPlayer { id, sender, seat: seat_sender });
+            if players.len() == seats(variant).len() {
+                Some(std::mem::take(players))
+            } else {
+                None
+            }
+        };
+
+        if let Some(players) = players {
+            start_match(variant, players);
+        }
+
+        (id, seat_receiver)
+    }
+
+    /// Takes a player who disconnected out of the queue. Returns false if their game has already started
+    pub fn leave(&self, variant: Variant, id: u64) -> bool {
+        let mut waiting = self.waiting.lock().unwrap();
+        let Some(players) = waiting.get_mut(&variant) else {
+            return false;
+        };
+        let count = players.len();
+        players.retain(|player| player.id != id);
+        players.len() != count
+    }
+}
+
+/// The board and color of each seat. In bughouse, seats 0 and 2 are partners, as are seats 1 and 3
+fn seats(variant: Variant) -> &'static [(usize, Color)] {
+    match variant {
+        Variant::Standard | Variant::Crazyhouse => &[(0, Color::White), (0, Color::Black)],
+        Variant::Bughouse => &[(0, Color::White), (0, Color::Black), (1, Color::Black), (1, Color::White)],
+    }
+}
+
+fn start_match(variant: Variant, waiting: Vec<WaitingPlayer>) {
+    let (inbox, mut messages) = mpsc::unbounded_channel();
+
+    let mut senders = Vec::new();
+    for (index, player) in waiting.into_iter().enumerate() {
+        let _ = player.seat.send(Seat { index, inbox: inbox.clone() });
+        senders.push(player.sender);
+    }
+    let mut game_match = Match::new(variant, senders);
+
+    tokio::spawn(async move {
+        while let Some((seat, message)) = messages.recv().await {
+            if game_match.handle_message(seat, message) {
+                break;
+            }
+        }
+    });
+}
+
+/// One or two linked boards (bughouse) and the players seated at them
+struct Match {
+    boards: Vec<Game>,
+    draw_offers: Vec<Option<Color>>,
+    players: Vec<Player>,
+}
+
+impl Match {
+    /// Seats the players in the order they joined and tells them their color
+    fn new(variant: Variant, senders: Vec<mpsc::UnboundedSender<ServerMessage>>) -> Match {
+        let mut players = Vec::new();
+        for (sender, &(board, color)) in senders.into_iter().zip(seats(variant)) {
+            let player = Player { board, color, sender };
+            player.send(ServerMessage::GameStarted(color, variant));
+            players.push(player);
+        }
+
+        let boards = match variant {
+            Variant::Bughouse => 2,
+            _ => 1,
+        };
+        Match {
+            boards: (0..boards).map(|_| Game::with_variant(variant)).collect(),
+            draw_offers: vec![None; boards],
+            players,
+        }
+    }
+
+    /// Returns true when the match is over
+    fn handle_message(&mut self, seat: usize, message: ClientMessage) -> bool {
+        let (board, color) = (self.players[seat].board, self.players[seat].color);
+
+        match message {
+            ClientMessage::JoinGame(_) => false,
+            ClientMessage::SendMove(start, end, promotion) => {
+                match self.play_move(board, color, start, end, promotion) {
+                    Ok(captured) => {
+                        self.players[seat].send(ServerMessage::MoveAccepted);
+                        self.send_to_opponent(seat, ServerMessage::OpponentMove(start, end, promotion));
+                        if let Some(piece) = captured {
+                            self.send_to_partner_board(board, piece);
+                        }
+                        self.check_endgame(board)
+                    }
+                    Err(reason) => {
+                        self.reject_move(seat, reason);
+                        false
+                    }
+                }
+            }
+            ClientMessage::SendDrop(kind, square) => {
+                let game = &mut self.boards[board];
+                let dropped = if game.turn() != color { Err(MoveError::NotYourTurn) } else { game.drop_piece(kind, square) };
+                if let Err(reason) = dropped {
+                    self.reject_move(seat, reason);
+                    return false;
+                }
+                self.players[seat].send(ServerMessage::MoveAccepted);
+                self.send_to_opponent(seat, ServerMessage::OpponentDrop(kind, square));
+                self.check_endgame(board)
+            }
+            ClientMessage::Resign => {
+                self.finish(board, EndgameStatus::Win(color.opposite(), WinReason::Resignation));
+                true
+            }
+            ClientMessage::OfferDraw => {
+                self.draw_offers[board] = Some(color);
+                self.send_to_opponent(seat, ServerMessage::OfferDraw);
+                false
+            }
+            ClientMessage::AcceptDraw => {
+                if self.draw_offers[board] != Some(color.opposite()) {
+                    return false;
+                }
+                self.finish(board, EndgameStatus::Draw(DrawReason::Agreement));
+                true
+            }
+            ClientMessage::DeclineDraw => {
+                if self.draw_offers[board].take() == Some(color.opposite()) {
+                    self.send_to_opponent(seat, ServerMessage::DeclineDraw);
+                }
+                false
+            }
+            ClientMessage::ClaimDraw => {
+                // A draw is claimed on one's own turn, like a move
+                let game = &self.boards[board];
+                let claim = if game.turn() != color { Err(MoveError::NotYourTurn) } else { game.can_claim_draw().ok_or(MoveError::NoDrawToClaim) };
+                match claim {
+                    Ok(reason) => {
+                        self.finish(board, EndgameStatus::Draw(reason));
+                        true
+                    }
+                    Err(reason) => {
+                        self.reject_move(seat, reason);
+                        false
+                    }
+                }
+            }
+        }
+    }
+
+    fn play_move(&mut self, board: usize, color: Color, start: Square, end: Square, promotion: Option<PromotionPiece>) -> Result<Option<Piece>, MoveError> {
+        let game = &mut self.boards[board];
+        if game.turn() != color {
+            return Err(MoveError::NotYourTurn);
+        }
+
+        let captured = game.play(Move::Normal { start, end, promotion })?;
+        self.draw_offers[board] = None;
+        Ok(captured)
+    }
+
+    /// The player's game went out of step with the server's, the position is sent back
+    fn reject_move(&self, seat: usize, reason: MoveError) {
+        let player = &self.players[seat];
+        player.send(ServerMessage::MoveRejected(reason));
+        player.send(ServerMessage::Resync(self.boards[player.board].to_fen()));
+    }
+
+    fn send_to_opponent(&self, seat: usize, message: ServerMessage) {
+        let (board, color) = (self.players[seat].board, self.players[seat].color);
+        if let Some(opponent) = self.players.iter().find(|player| player.board == board && player.color != color) {
+            opponent.send(message);
+        }
+    }
+
+    /// In bughouse, a piece captured on one board goes to the pocket of the same color on the other board
+    fn send_to_partner_board(&mut self, board: usize, piece: Piece) {
+        if self.boards.len() < 2 {
+            return;
+        }
+        let partner_board = 1 - board;
+        if self.boards[partner_board].add_to_pocket(piece).is_ok() {
+            for player in self.players.iter().filter(|player| player.board == partner_board) {
+                player.send(ServerMessage::PieceReceived(piece.color(), piece.kind()));
+            }
+        }
+    }
+
+    fn check_endgame(&mut self, board: usize) -> bool {
+        let endgame_status = self.boards[board].evaluate_endgame();
+        if endgame_status.is_ongoing() {
+            return false;
+        }
+        self.finish(board, endgame_status);
+        true
+    }
+
+    /// Ends every board of the match. In bughouse the team that wins on one board wins on the other
+    fn finish(&mut self, board: usize, endgame_status: EndgameStatus) {
+        for (index, game) in self.boards.iter_mut().enumerate() {
+            let status = match endgame_status {
+                EndgameStatus::Win(color, reason) if index != board => EndgameStatus::Win(color.opposite(), reason),
+                status => status,
+            };
+            game.end_game(status);
+        }
+        for player in &self.players {
+            player.send(ServerMessage::GameOver(self.boards[player.board].endgame_status()));
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_game_manager.rs"]
+mod test_game_manager;
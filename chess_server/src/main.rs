@@ -3,31 +3,24 @@ mod players;
 mod server;
 mod constants;
 
+use std::sync::Arc;
+
 use crate::constants::SERVER_ADDRESS;
+use crate::game_manager::GameManager;
 
 use tokio::net::TcpListener;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 #[tokio::main]
 async fn main() -> tokio::io::Result<()> {
     let listener = TcpListener::bind(SERVER_ADDRESS).await?;
     println!("Server listening on {SERVER_ADDRESS}...");
-    
+
+    let manager = Arc::new(GameManager::default());
+
     loop {
-        let (mut socket, addr) = listener.accept().await?;
+        let (socket, addr) = listener.accept().await?;
         println!("New connection from {}", addr);
 
-        tokio::spawn(async move {
-            let mut buffer = [0; 1024];
-
-            match socket.read(&mut buffer).await {
-                Ok(n) if n > 0 => {
-                    println!("Received: {:?}", &buffer[..n]);
-                    socket.write_all(b"Message received").await.unwrap();
-                }
-                Ok(_) => println!("Connection closed by client"),
-                Err(e) => eprintln!("Error reading from socket: {}", e),
-            }
-        });
+        tokio::spawn(server::handle_connection(socket, manager.clone()));
     }
 }
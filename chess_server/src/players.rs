@@ -0,0 +1,30 @@
+use chess_core::Color;
+use chess_network::{ClientMessage, ServerMessage};
+use tokio::sync::{mpsc, oneshot};
+
+/// A connected player waiting for enough opponents to start a game
+pub struct WaitingPlayer {
+    pub id: u64,
+    pub sender: mpsc::UnboundedSender<ServerMessage>,
+    pub seat: oneshot::Sender<Seat>,
+}
+
+/// What the connection needs to forward the player's messages to their game
+pub struct Seat {
+    pub index: usize,
+    pub inbox: mpsc::UnboundedSender<(usize, ClientMessage)>,
+}
+
+/// A player seated at a board
+pub struct Player {
+    pub board: usize,
+    pub color: Color,
+    pub sender: mpsc::UnboundedSender<ServerMessage>,
+}
+
+impl Player {
+    /// The player may have disconnected, in which case the message is lost
+    pub fn send(&self, message: ServerMessage) {
+        let _ = self.sender.send(message);
+    }
+}
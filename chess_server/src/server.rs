@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use chess_network::{decode, encode, ClientMessage, ServerMessage};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::{tcp::OwnedReadHalf, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::game_manager::GameManager;
+
+pub async fn handle_connection(socket: TcpStream, manager: Arc<GameManager>) {
+    let (reader, mut writer) = socket.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    // Everything sent to the player goes through this channel, so that the game never waits on a socket
+    let (sender, mut receiver) = mpsc::unbounded_channel::<ServerMessage>();
+    tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if writer.write_all(encode(&message).as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // The first message says which variant the player wants to play
+    let variant = match next_message(&mut lines).await {
+        Some(ClientMessage::JoinGame(variant)) => variant,
+        _ => return,
+    };
+
+    // The player is taken out of the queue if they leave before their game starts
+    let (id, mut seat_receiver) = manager.join(variant, sender);
+    let seat = loop {
+        tokio::select! {
+            seat = &mut seat_receiver => match seat {
+                Ok(seat) => break seat,
+                Err(_) => return,
+            },
+            message = next_message(&mut lines) => {
+                if message.is_some() {
+                    continue; // Nothing to do before the game starts
+                }
+                if !manager.leave(variant, id) {
+                    // The game started meanwhile, leaving it counts as a resignation
+                    if let Ok(seat) = seat_receiver.await {
+                        let _ = seat.inbox.send((seat.index, ClientMessage::Resign));
+                    }
+                }
+                return;
+            }
+        }
+    };
+
+    while let Some(message) = next_message(&mut lines).await {
+        if seat.inbox.send((seat.index, message)).is_err() {
+            return; // The game is over
+        }
+    }
+
+    // Leaving a game counts as a resignation
+    let _ = seat.inbox.send((seat.index, ClientMessage::Resign));
+}
+
+async fn next_message(lines: &mut Lines<BufReader<OwnedReadHalf>>) -> Option<ClientMessage> {
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) => match decode(&line) {
+                Ok(message) => return Some(message),
+                Err(e) => eprintln!("Invalid message {:?}: {}", line, e),
+            },
+            Ok(None) => return None,
+            Err(e) => {
+                eprintln!("Error reading from socket: {}", e);
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use chess_core::{Color, DrawReason, EndgameStatus, Game, MoveError, PieceKind, PromotionPiece, Setup, Square, Variant, WinReason};
+use chess_network::{ClientMessage, ServerMessage};
+use tokio::sync::mpsc;
+
+use super::{GameManager, Match};
+
+fn new_match(variant: Variant, players: usize) -> (Match, Vec<mpsc::UnboundedReceiver<ServerMessage>>) {
+    let (senders, receivers) = (0..players).map(|_| mpsc::unbounded_channel()).unzip();
+    (Match::new(variant, senders), receivers)
+}
+
+fn messages(receiver: &mut mpsc::UnboundedReceiver<ServerMessage>) -> Vec<ServerMessage> {
+    std::iter::from_fn(|| receiver.try_recv().ok()).collect()
+}
+
+#[tokio::test]
+async fn test_players_leaving_the_queue() {
+    let manager = GameManager::default();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let (id, _) = manager.join(Variant::Standard, sender);
+    assert!(manager.leave(Variant::Standard, id), "A waiting player leaves the queue");
+    assert!(!manager.leave(Variant::Standard, id));
+
+    let (sender, mut white) = mpsc::unbounded_channel();
+    let (white_id, white_seat) = manager.join(Variant::Standard, sender);
+    let (sender, mut black) = mpsc::unbounded_channel();
+    let (_, black_seat) = manager.join(Variant::Standard, sender);
+    assert_eq!(white_seat.await.unwrap().index, 0);
+    assert_eq!(black_seat.await.unwrap().index, 1);
+    assert!(!manager.leave(Variant::Standard, white_id), "The game has started");
+
+    assert!(messages(&mut receiver).is_empty(), "The player who left isn't paired");
+    assert!(matches!(messages(&mut white)[..], [ServerMessage::GameStarted(Color::White, Variant::Standard)]));
+    assert!(matches!(messages(&mut black)[..], [ServerMessage::GameStarted(Color::Black, Variant::Standard)]));
+}
+
+#[test]
+fn test_bughouse_seats_and_captures() {
+    let (mut game_match, mut receivers) = new_match(Variant::Bughouse, 4);
+    let seats: Vec<(usize, Color)> = game_match.players.iter().map(|player| (player.board, player.color)).collect();
+    assert_eq!(seats, vec![(0, Color::White), (0, Color::Black), (1, Color::Black), (1, Color::White)]);
+    for (receiver, (_, color)) in receivers.iter_mut().zip(seats) {
+        assert!(matches!(messages(receiver)[..], [ServerMessage::GameStarted(started, Variant::Bughouse)] if started == color));
+    }
+
+    assert!(!game_match.handle_message(0, ClientMessage::SendMove(Square::E2, Square::E4, None)));
+    assert!(!game_match.handle_message(1, ClientMessage::SendMove(Square::D7, Square::D5, None)));
+    assert!(!game_match.handle_message(0, ClientMessage::SendMove(Square::E4, Square::D5, None)));
+    assert!(game_match.boards[0].pocket(Color::White).is_empty(), "The pawn taken leaves the board");
+    assert_eq!(game_match.boards[1].pocket(Color::Black).count(PieceKind::Pawn), 1, "It goes to the partner of the player who took it");
+    for receiver in &mut receivers[2..] {
+        assert!(matches!(messages(receiver)[..], [ServerMessage::PieceReceived(Color::Black, PieceKind::Pawn)]));
+    }
+    assert!(matches!(messages(&mut receivers[1])[..], [ServerMessage::OpponentMove(..), ServerMessage::MoveAccepted, ServerMessage::OpponentMove(..)]));
+
+    assert!(!game_match.handle_message(2, ClientMessage::SendDrop(PieceKind::Pawn, Square::E5)));
+    assert!(matches!(messages(&mut receivers[2])[..], [ServerMessage::MoveRejected(MoveError::NotYourTurn), ServerMessage::Resync(_)]));
+    assert!(!game_match.handle_message(3, ClientMessage::SendMove(Square::E2, Square::E4, None)));
+    assert!(!game_match.handle_message(2, ClientMessage::SendDrop(PieceKind::Pawn, Square::E5)));
+    assert!(matches!(messages(&mut receivers[3])[..], [ServerMessage::MoveAccepted, ServerMessage::OpponentDrop(PieceKind::Pawn, Square::E5)]));
+}
+
+#[test]
+fn test_pending_promotion() {
+    let (mut game_match, mut receivers) = new_match(Variant::Standard, 2);
+    game_match.boards[0] = Game::from_setup(&Setup::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap()).unwrap();
+    messages(&mut receivers[0]);
+
+    assert!(!game_match.handle_message(0, ClientMessage::SendMove(Square::A7, Square::B8, None)));
+    assert!(matches!(messages(&mut receivers[0])[..], [ServerMessage::MoveRejected(MoveError::MissingPromotionPiece), ServerMessage::Resync(_)]));
+    assert!(game_match.boards[0].history().is_empty(), "A rejected promotion leaves the game untouched");
+
+    assert!(!game_match.handle_message(0, ClientMessage::SendMove(Square::A7, Square::B8, Some(PromotionPiece::Queen))));
+    assert_eq!(game_match.boards[0].is_promotion_pending(), None);
+    assert_eq!(game_match.boards[0].turn(), Color::Black);
+    assert!(matches!(
+        messages(&mut receivers[1])[..],
+        [ServerMessage::GameStarted(..), ServerMessage::OpponentMove(Square::A7, Square::B8, Some(PromotionPiece::Queen))]
+    ));
+}
+
+#[test]
+fn test_bughouse_ends_both_boards() {
+    let (mut game_match, mut receivers) = new_match(Variant::Bughouse, 4);
+    assert!(game_match.handle_message(1, ClientMessage::Resign));
+
+    assert_eq!(game_match.boards[0].endgame_status(), EndgameStatus::Win(Color::White, WinReason::Resignation));
+    assert_eq!(game_match.boards[1].endgame_status(), EndgameStatus::Win(Color::Black, WinReason::Resignation), "The partner of the winner wins too");
+    for (receiver, player) in receivers.iter_mut().zip(&game_match.players) {
+        let status = game_match.boards[player.board].endgame_status();
+        assert!(matches!(messages(receiver)[..], [ServerMessage::GameStarted(..), ServerMessage::GameOver(over)] if over == status));
+    }
+}
+
+#[test]
+fn test_draw_claimed_on_own_turn() {
+    let (mut game_match, mut receivers) = new_match(Variant::Standard, 2);
+    let knights = [(0, Square::G1, Square::F3), (1, Square::G8, Square::F6), (0, Square::F3, Square::G1), (1, Square::F6, Square::G8)];
+    for (seat, start, end) in knights.iter().chain(&knights) {
+        assert!(!game_match.handle_message(*seat, ClientMessage::SendMove(*start, *end, None)));
+    }
+    messages(&mut receivers[1]);
+
+    assert!(!game_match.handle_message(1, ClientMessage::ClaimDraw), "White is to play");
+    assert!(matches!(messages(&mut receivers[1])[..], [ServerMessage::MoveRejected(MoveError::NotYourTurn), ServerMessage::Resync(_)]));
+    assert!(game_match.boards[0].endgame_status().is_ongoing());
+
+    assert!(game_match.handle_message(0, ClientMessage::ClaimDraw));
+    assert_eq!(game_match.boards[0].endgame_status(), EndgameStatus::Draw(DrawReason::ThreefoldRepetition));
+}
@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use chess_core::Color;
+
+/// Time of each player, counting down from the time control, or up from zero without one
+pub struct Clock {
+    used: [Duration; 2], // White then black, without the turn in progress
+    limit: Option<Duration>,
+    running: Option<(Color, Instant)>,
+}
+
+fn index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+impl Clock {
+    pub fn new(limit: Option<Duration>) -> Clock {
+        Clock { used: [Duration::ZERO; 2], limit, running: None }
+    }
+
+    /// Runs the time of that player, the other one's is stopped
+    pub fn start(&mut self, color: Color) {
+        self.stop();
+        self.running = Some((color, Instant::now()));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some((color, since)) = self.running.take() {
+            self.used[index(color)] += since.elapsed();
+        }
+    }
+
+    /// The player whose time is running
+    pub fn running(&self) -> Option<Color> {
+        self.running.map(|(color, _)| color)
+    }
+
+    fn used(&self, color: Color) -> Duration {
+        match self.running {
+            Some((running, since)) if running == color => self.used[index(color)] + since.elapsed(),
+            _ => self.used[index(color)],
+        }
+    }
+
+    /// Time left with a time control, time used otherwise
+    pub fn shown(&self, color: Color) -> Duration {
+        match self.limit {
+            Some(limit) => limit.saturating_sub(self.used(color)),
+            None => self.used(color),
+        }
+    }
+
+    /// The player whose time ran out, if any
+    pub fn flagged(&self) -> Option<Color> {
+        let limit = self.limit?;
+        self.running().filter(|&color| self.used(color) >= limit)
+    }
+}
+
+/// Minutes and seconds, 4:05
+pub fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
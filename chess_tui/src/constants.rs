@@ -0,0 +1,29 @@
+use crossterm::style::Color;
+
+pub const SERVER_ADDRESS: &str = "127.0.0.1:3855";
+
+pub const TICK_MILLIS: u64 = 100; // How long to wait for a key before drawing again, for the clocks, the network and the analysis
+pub const ANALYSIS_LINES: usize = 3; // Best lines shown in analysis mode
+pub const ANALYSIS_MOVES: usize = 8; // Moves shown of each line
+
+// Rows and columns of the terminal
+pub const TOP_PLAYER_ROW: u16 = 0; // Clock and pocket of the player at the top of the board
+pub const BOARD_TOP: u16 = 1; // Row of the eighth rank, or of the first one when the board is flipped
+pub const BOARD_LEFT: u16 = 3; // Column of the a file, after the rank labels
+pub const SQUARE_WIDTH: u16 = 3; // Columns of a square, the piece in the middle
+pub const EVAL_BAR_LEFT: u16 = 29; // Column of the evaluation bar, beside the board
+pub const PANEL_LEFT: u16 = 34; // Column of the move list
+pub const MOVE_ROWS: usize = 8; // Full moves shown in the move list, the last ones
+pub const STATUS_ROW: u16 = 12; // Side to move or result of the game
+pub const ANALYSIS_ROW: u16 = 13; // First of the analysis lines
+pub const PROMPT_ROW: u16 = 17; // Where moves and commands are typed
+pub const HELP_ROW: u16 = 19; // Keys and commands
+
+pub const LIGHT_SQUARE: Color = Color::Rgb { r: 240, g: 217, b: 181 };
+pub const DARK_SQUARE: Color = Color::Rgb { r: 181, g: 136, b: 99 };
+pub const LAST_MOVE_COLOR: Color = Color::Rgb { r: 205, g: 210, b: 106 }; // Squares of the last move
+pub const CHECK_COLOR: Color = Color::Rgb { r: 230, g: 90, b: 80 }; // King in check
+pub const CURSOR_COLOR: Color = Color::Rgb { r: 110, g: 160, b: 230 }; // Square under the keyboard cursor
+pub const SELECTED_COLOR: Color = Color::Rgb { r: 120, g: 190, b: 120 }; // Piece picked with the cursor
+pub const WHITE_PIECE_COLOR: Color = Color::Rgb { r: 255, g: 255, b: 255 };
+pub const BLACK_PIECE_COLOR: Color = Color::Rgb { r: 0, g: 0, b: 0 };
@@ -0,0 +1,402 @@
+mod clock;
+mod constants;
+mod ui;
+mod utils;
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use chess_core::search::Analyzer;
+use chess_core::{Color, DrawReason, EndgameStatus, Game, Move, Piece, PromotionPiece, Square, Variant, WinReason};
+use chess_network::client::{Connection, NetworkEvent};
+use chess_network::{resync, ClientMessage, ServerMessage};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+
+use crate::clock::Clock;
+use crate::constants::{ANALYSIS_LINES, SERVER_ADDRESS, TICK_MILLIS};
+
+/// Everything the terminal shows, changed by the keys and the server
+struct Tui {
+    variant: Variant,
+    game: Game,
+    connection: Option<Connection>, // Online only
+    player_color: Option<Color>, // Known once the server has found an opponent, both sides are played locally otherwise
+    clock: Clock,
+    analyzer: Option<Analyzer>, // Restarted whenever the position changes, in analysis mode
+    sans: Vec<String>, // Notation of the moves played, updated when the moves change
+    san_moves: Vec<Move>, // The moves sans was written for
+    bottom: Color, // Color whose first rank is at the bottom of the board
+    cursor: Square,
+    selected: Option<Square>, // Piece picked with the cursor
+    pending_promotion: Option<(Square, Square)>, // Move sent once the piece is chosen
+    draw_offered: bool, // The opponent offered a draw
+    input: String,
+    message: String,
+    should_quit: bool,
+}
+
+impl Tui {
+    fn new(variant: Variant) -> Tui {
+        let mut tui = Tui {
+            variant,
+            game: Game::with_variant(variant),
+            connection: None,
+            player_color: None,
+            clock: Clock::new(utils::clock_from_args()),
+            analyzer: None,
+            sans: Vec::new(),
+            san_moves: Vec::new(),
+            bottom: Color::White,
+            cursor: Square::E2,
+            selected: None,
+            pending_promotion: None,
+            draw_offered: false,
+            input: String::new(),
+            message: String::new(),
+            should_quit: false,
+        };
+        tui.new_game();
+        tui
+    }
+
+    fn new_game(&mut self) {
+        self.game = Game::with_variant(self.variant);
+        self.clock = Clock::new(utils::clock_from_args());
+        self.selected = None;
+        self.pending_promotion = None;
+        self.draw_offered = false;
+        if utils::online_from_args() {
+            self.connection = Some(Connection::connect(SERVER_ADDRESS, self.variant));
+            self.player_color = None;
+            self.message = "Waiting for an opponent...".to_string();
+        } else {
+            self.message.clear();
+        }
+    }
+
+    /// Whether the player at this terminal can move now
+    fn can_play(&self) -> bool {
+        let is_my_turn = match (&self.connection, self.player_color) {
+            (None, _) => true,
+            (Some(_), color) => color == Some(self.game.turn()),
+        };
+        is_my_turn && self.game.endgame_status().is_ongoing() && self.game.is_promotion_pending().is_none()
+    }
+
+    /// Plays a move of the player, the piece of a promotion is asked for when the move doesn't say
+    fn play(&mut self, mv: Move) {
+        let result = match mv {
+            Move::Normal { start, end, promotion: None } => self.game.play_move(start, end).map(|_| ()),
+            mv => self.game.play(mv).map(|_| ()),
+        };
+        match result {
+            Ok(()) => {
+                self.message.clear();
+                match mv {
+                    Move::Normal { start, end, .. } if self.game.is_promotion_pending().is_some() => {
+                        self.pending_promotion = Some((start, end));
+                    }
+                    mv => self.send_move(mv),
+                }
+            }
+            Err(e) => self.message = e.to_string(),
+        }
+    }
+
+    /// Tells the server about a finished move, and checks whether the game is over
+    fn send_move(&mut self, mv: Move) {
+        if let Some(connection) = &self.connection {
+            match mv {
+                Move::Normal { start, end, promotion } => connection.send(ClientMessage::SendMove(start, end, promotion)),
+                Move::Drop { kind, square } => connection.send(ClientMessage::SendDrop(kind, square)),
+            }
+        }
+        self.game.evaluate_endgame();
+    }
+
+    fn promote(&mut self, promotion: PromotionPiece) {
+        if self.game.promote_pawn(promotion).is_ok() {
+            if let Some((start, end)) = self.pending_promotion.take() {
+                self.send_move(Move::Normal { start, end, promotion: Some(promotion) });
+            }
+        }
+    }
+
+    /// Space or Enter on an empty line: picks the piece under the cursor, or moves the picked piece there
+    fn use_cursor(&mut self) {
+        if !self.can_play() {
+            return;
+        }
+        let piece = self.game.piece_at(self.cursor);
+        let is_own_piece = piece.is_some_and(|piece| piece.color() == self.game.turn());
+        match self.selected {
+            Some(selected) if selected == self.cursor => self.selected = None,
+            _ if is_own_piece => self.selected = Some(self.cursor),
+            Some(selected) => {
+                self.selected = None;
+                self.play(Move::new(selected, self.cursor));
+            }
+            None => {}
+        }
+    }
+
+    /// A line typed and entered: a command or a move
+    fn submit(&mut self) {
+        let input = std::mem::take(&mut self.input);
+        let online = self.connection.is_some();
+        match input.trim() {
+            "quit" | "exit" => self.should_quit = true,
+            "flip" => self.bottom = self.bottom.opposite(),
+            "new" => self.new_game(),
+            "undo" if online => self.message = "Moves can't be taken back online".to_string(),
+            "undo" => {
+                self.game.undo();
+                self.pending_promotion = None;
+                self.message.clear();
+            }
+            "resign" | "draw" | "claim" if !self.game.endgame_status().is_ongoing() => self.message = "The game is over".to_string(),
+            "resign" => match (&self.connection, self.player_color) {
+                (Some(connection), Some(_)) => connection.send(ClientMessage::Resign),
+                (Some(_), None) => {}
+                (None, _) => self.game.end_game(EndgameStatus::Win(self.game.turn().opposite(), WinReason::Resignation)),
+            },
+            "draw" => match &self.connection {
+                Some(connection) => {
+                    connection.send(ClientMessage::OfferDraw);
+                    self.message = "Draw offered".to_string();
+                }
+                None => self.game.end_game(EndgameStatus::Draw(DrawReason::Agreement)),
+            },
+            "claim" => match (&self.connection, self.player_color) {
+                (Some(connection), Some(_)) => connection.send(ClientMessage::ClaimDraw),
+                (Some(_), None) => {}
+                (None, _) => {
+                    if let Err(e) = self.game.claim_draw() {
+                        self.message = e.to_string();
+                    }
+                }
+            },
+            "accept" | "decline" if !self.draw_offered => self.message = "No draw was offered".to_string(),
+            "accept" | "decline" => {
+                if let Some(connection) = &self.connection {
+                    let accepted = input.trim() == "accept";
+                    connection.send(if accepted { ClientMessage::AcceptDraw } else { ClientMessage::DeclineDraw });
+                }
+                self.draw_offered = false;
+                self.message.clear();
+            }
+            "" => self.use_cursor(),
+            text if !self.can_play() => self.message = format!("{}: not your turn", text),
+            text => match utils::parse_input(&self.game, text) {
+                Ok(mv) => {
+                    self.selected = None;
+                    self.play(mv);
+                }
+                Err(e) => self.message = format!("{}: {}", text, e),
+            },
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            if matches!(code, KeyCode::Char('c') | KeyCode::Char('q')) {
+                self.should_quit = true;
+            }
+            return;
+        }
+
+        // The piece of a promotion is chosen with a single key
+        if self.pending_promotion.is_some() || self.game.is_promotion_pending().is_some() {
+            let promotion = match code {
+                KeyCode::Char('q') => Some(PromotionPiece::Queen),
+                KeyCode::Char('r') => Some(PromotionPiece::Rook),
+                KeyCode::Char('b') => Some(PromotionPiece::Bishop),
+                KeyCode::Char('n') => Some(PromotionPiece::Knight),
+                _ => None,
+            };
+            if let Some(promotion) = promotion {
+                self.promote(promotion);
+                return;
+            }
+        }
+
+        // The arrows move on the screen, whichever way the board is turned
+        let step = if self.bottom == Color::White { 1 } else { -1 };
+        let moved = match code {
+            KeyCode::Up => Some((0, step)),
+            KeyCode::Down => Some((0, -step)),
+            KeyCode::Left => Some((-step, 0)),
+            KeyCode::Right => Some((step, 0)),
+            _ => None,
+        };
+        if let Some((files, ranks)) = moved {
+            // The cursor stays on the edge of the board
+            self.cursor = self.cursor.offset(files, ranks).unwrap_or(self.cursor);
+            return;
+        }
+
+        match code {
+            KeyCode::Char(' ') if self.input.is_empty() => self.use_cursor(),
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Tab => self.bottom = self.bottom.opposite(),
+            KeyCode::Esc => {
+                self.input.clear();
+                self.selected = None;
+                self.message.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply what the server sent since the last tick
+    fn receive(&mut self) {
+        let Some(connection) = &self.connection else {
+            return;
+        };
+        let mut events = Vec::new();
+        while let Some(event) = connection.try_recv() {
+            events.push(event);
+        }
+
+        for event in events {
+            match event {
+                NetworkEvent::Message(ServerMessage::GameStarted(color, variant)) => {
+                    self.game = Game::with_variant(variant);
+                    self.clock = Clock::new(utils::clock_from_args());
+                    self.player_color = Some(color);
+                    self.bottom = color; // The player's pieces are at the bottom
+                    self.cursor = if color == Color::White { Square::E2 } else { Square::E7 };
+                    self.message = format!("You play {}", color);
+                }
+                NetworkEvent::Message(ServerMessage::OpponentMove(start, end, promotion)) => {
+                    if self.game.play_move(start, end).is_ok() {
+                        if let Some(promotion_piece) = promotion {
+                            let _ = self.game.promote_pawn(promotion_piece);
+                        }
+                        self.game.evaluate_endgame();
+                    }
+                }
+                NetworkEvent::Message(ServerMessage::OpponentDrop(kind, square)) => {
+                    if self.game.drop_piece(kind, square).is_ok() {
+                        self.game.evaluate_endgame();
+                    }
+                }
+                NetworkEvent::Message(ServerMessage::PieceReceived(color, kind)) => {
+                    let _ = self.game.add_to_pocket(Piece::new(kind, color));
+                }
+                NetworkEvent::Message(ServerMessage::GameOver(endgame_status)) => {
+                    self.game.end_game(endgame_status);
+                    self.draw_offered = false;
+                }
+                NetworkEvent::Message(ServerMessage::MoveRejected(reason)) => {
+                    self.message = format!("The server rejected the last move: {}", reason);
+                }
+                NetworkEvent::Message(ServerMessage::Resync(fen)) => {
+                    if let Some(game) = resync(&self.game, &fen) {
+                        self.game = game;
+                    }
+                    self.selected = None;
+                    self.pending_promotion = None;
+                }
+                NetworkEvent::Message(ServerMessage::OfferDraw) => {
+                    self.draw_offered = true;
+                    self.message = "Your opponent offers a draw: accept or decline".to_string();
+                }
+                NetworkEvent::Message(ServerMessage::DeclineDraw) => {
+                    self.message = "Your opponent declined the draw".to_string();
+                }
+                NetworkEvent::Message(_) => {}
+                NetworkEvent::Disconnected(reason) => {
+                    if self.game.endgame_status().is_ongoing() {
+                        self.message = format!("Disconnected: {}", reason);
+                        self.player_color = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs the clock of the side to move while the game goes on, a local game is lost on time
+    fn update_clock(&mut self) {
+        let started = self.connection.is_none() || self.player_color.is_some();
+        if !started || !self.game.endgame_status().is_ongoing() {
+            self.clock.stop();
+            return;
+        }
+        if self.clock.running() != Some(self.game.turn()) {
+            self.clock.start(self.game.turn());
+        }
+        if let Some(color) = self.clock.flagged() {
+            // The server keeps no time, online the clocks are only shown
+            if self.connection.is_none() {
+                self.game.end_game(EndgameStatus::Win(color.opposite(), WinReason::Timeout));
+                self.clock.stop();
+            }
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.san_moves != self.game.history() {
+            self.sans = self.game.history_to_san();
+            self.san_moves = self.game.history().to_vec();
+        }
+        let targets = match self.selected {
+            Some(square) => self.game.get_legal_moves(square),
+            None => Vec::new(),
+        };
+        let (width, _) = terminal::size()?;
+
+        queue!(out, terminal::Clear(ClearType::All))?;
+        ui::draw_board(out, &self.game, self.bottom, self.cursor, self.selected, &targets)?;
+        ui::draw_players(out, &self.game, self.bottom, &self.clock)?;
+        ui::draw_moves(out, &self.sans)?;
+        if utils::analysis_from_args() {
+            if self.analyzer.as_ref().is_none_or(|analyzer| !analyzer.is_analyzing(&self.game)) {
+                self.analyzer = Some(Analyzer::start(&self.game, ANALYSIS_LINES));
+            }
+            let lines = self.analyzer.as_mut().map_or(&[][..], |analyzer| analyzer.lines());
+            ui::draw_eval_bar(out, lines.first().map(|line| line.score), self.bottom)?;
+            ui::draw_analysis(out, lines, &self.game, width)?;
+        }
+        ui::draw_prompt(out, &ui::status(&self.game), &self.input, &self.message, self.connection.is_some())?;
+        out.flush()
+    }
+}
+
+fn run(out: &mut impl Write) -> io::Result<()> {
+    let mut tui = Tui::new(utils::variant_from_args());
+    while !tui.should_quit {
+        tui.receive();
+        tui.update_clock();
+        tui.draw(out)?;
+
+        // Wait for a key at most until the next tick, the clocks and the analysis change meanwhile
+        if event::poll(Duration::from_millis(TICK_MILLIS))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    tui.handle_key(key.code, key.modifiers);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, cursor::Hide)?;
+
+    let result = run(&mut out);
+
+    // The terminal is given back as it was, even after an error
+    execute!(out, LeaveAlternateScreen, cursor::Show)?;
+    terminal::disable_raw_mode()?;
+    result
+}
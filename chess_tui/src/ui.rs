@@ -0,0 +1,211 @@
+use std::io::{self, Write};
+
+use chess_core::search::{Line, LineScore};
+use chess_core::{Color, DrawReason, EndgameStatus, Game, Move, Piece, PieceKind, Pocket, Square, WinReason};
+use crossterm::cursor::MoveTo;
+use crossterm::style::{Attribute, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use crossterm::{queue, style};
+
+use crate::clock::{format_time, Clock};
+use crate::constants::{
+    ANALYSIS_MOVES, ANALYSIS_ROW, BLACK_PIECE_COLOR, BOARD_LEFT, BOARD_TOP, CHECK_COLOR, CURSOR_COLOR, DARK_SQUARE,
+    EVAL_BAR_LEFT, HELP_ROW, LAST_MOVE_COLOR, LIGHT_SQUARE, MOVE_ROWS, PANEL_LEFT, PROMPT_ROW, SELECTED_COLOR,
+    SQUARE_WIDTH, STATUS_ROW, TOP_PLAYER_ROW, WHITE_PIECE_COLOR,
+};
+use crate::utils::screen_square;
+
+/// The same glyph is used for both colors, the pieces are told apart by their color
+fn glyph(kind: PieceKind) -> char {
+    match kind {
+        PieceKind::Pawn => '♟',
+        PieceKind::Knight => '♞',
+        PieceKind::Bishop => '♝',
+        PieceKind::Rook => '♜',
+        PieceKind::Queen => '♛',
+        PieceKind::King => '♚',
+    }
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
+
+/// Draw the board with its rank and file labels, bottom is the color whose first rank is at the bottom.
+/// The cursor, the selected piece and the squares it can go to, the last move and a king in check are highlighted
+pub fn draw_board(
+    out: &mut impl Write,
+    game: &Game,
+    bottom: Color,
+    cursor: Square,
+    selected: Option<Square>,
+    targets: &[Square],
+) -> io::Result<()> {
+    let last_move: Vec<Square> = match game.history().last() {
+        Some(Move::Normal { start, end, .. }) => vec![*start, *end],
+        Some(Move::Drop { square, .. }) => vec![*square],
+        None => Vec::new(),
+    };
+    let checked_king = Piece::new(PieceKind::King, game.turn());
+
+    for row in 0..8 {
+        let rank = screen_square((row, 0), bottom).rank();
+        queue!(out, MoveTo(0, BOARD_TOP + row as u16), ResetColor, Print(format!("{} ", rank)))?;
+        for column in 0..8 {
+            let square = screen_square((row, column), bottom);
+            let piece = game.piece_at(square);
+            let background = if square == cursor {
+                CURSOR_COLOR
+            } else if Some(square) == selected {
+                SELECTED_COLOR
+            } else if piece == Some(checked_king) && game.is_in_check() {
+                CHECK_COLOR
+            } else if last_move.contains(&square) {
+                LAST_MOVE_COLOR
+            } else if square.color() == Color::Black {
+                DARK_SQUARE
+            } else {
+                LIGHT_SQUARE
+            };
+            let (symbol, foreground) = match piece {
+                Some(piece) if piece.color() == Color::White => (glyph(piece.kind()), WHITE_PIECE_COLOR),
+                Some(piece) => (glyph(piece.kind()), BLACK_PIECE_COLOR),
+                None if targets.contains(&square) => ('·', BLACK_PIECE_COLOR),
+                None => (' ', BLACK_PIECE_COLOR),
+            };
+            queue!(
+                out,
+                MoveTo(BOARD_LEFT + column as u16 * SQUARE_WIDTH, BOARD_TOP + row as u16),
+                SetBackgroundColor(background),
+                SetForegroundColor(foreground),
+                SetAttribute(Attribute::Bold),
+                Print(format!(" {} ", symbol)),
+                SetAttribute(Attribute::Reset),
+                ResetColor,
+            )?;
+        }
+    }
+
+    for column in 0..8 {
+        let file = screen_square((0, column), bottom).file();
+        queue!(out, MoveTo(BOARD_LEFT + column as u16 * SQUARE_WIDTH + 1, BOARD_TOP + 8), Print(file))?;
+    }
+    Ok(())
+}
+
+/// Name, clock and pocket of the players, the one at the bottom of the board below it
+pub fn draw_players(out: &mut impl Write, game: &Game, bottom: Color, clock: &Clock) -> io::Result<()> {
+    for (row, color) in [(TOP_PLAYER_ROW, bottom.opposite()), (BOARD_TOP + 9, bottom)] {
+        let mut text = format!("{:<6}{:>7}", color_name(color), format_time(clock.shown(color)));
+        if game.variant().has_drops() {
+            let pocket = game.pocket(color);
+            for kind in Pocket::KINDS.into_iter().filter(|&kind| pocket.count(kind) > 0) {
+                text.push_str(&format!("  {}{}", glyph(kind), pocket.count(kind)));
+            }
+        }
+        let attribute = if clock.running() == Some(color) { Attribute::Bold } else { Attribute::Dim };
+        queue!(out, MoveTo(BOARD_LEFT, row), SetAttribute(attribute), Print(text), SetAttribute(Attribute::Reset))?;
+    }
+    Ok(())
+}
+
+/// The last full moves in SAN with their numbers, the last move in reverse video
+pub fn draw_moves(out: &mut impl Write, sans: &[String]) -> io::Result<()> {
+    queue!(out, MoveTo(PANEL_LEFT, TOP_PLAYER_ROW), SetAttribute(Attribute::Bold), Print("Moves"), SetAttribute(Attribute::Reset))?;
+    let rows = sans.len().div_ceil(2);
+    let first_row = rows.saturating_sub(MOVE_ROWS);
+    for (index, row) in sans.chunks(2).enumerate().skip(first_row) {
+        let y = BOARD_TOP + (index - first_row) as u16;
+        queue!(out, MoveTo(PANEL_LEFT, y), Print(format!("{:>3}.", index + 1)))?;
+        for (column, san) in row.iter().enumerate() {
+            let is_last = 2 * index + column + 1 == sans.len();
+            let attribute = if is_last { Attribute::Reverse } else { Attribute::NormalIntensity };
+            queue!(
+                out,
+                MoveTo(PANEL_LEFT + 5 + column as u16 * 9, y),
+                SetAttribute(attribute),
+                Print(san),
+                SetAttribute(Attribute::Reset),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Bar beside the board filled with white from the bottom for white's advantage, the other way when the board is flipped
+pub fn draw_eval_bar(out: &mut impl Write, score: Option<LineScore>, bottom: Color) -> io::Result<()> {
+    let white_share = match score {
+        Some(LineScore::Centipawns(score)) => (0.5 + score as f32 / 2000.0).clamp(0.05, 0.95),
+        Some(LineScore::Mate(moves)) if moves > 0 => 1.0,
+        Some(LineScore::Mate(_)) => 0.0,
+        None => 0.5,
+    };
+    let white_rows = (white_share * 8.0).round() as u16;
+    for row in 0..8 {
+        let is_white = match bottom {
+            Color::White => row >= 8 - white_rows,
+            Color::Black => row < white_rows,
+        };
+        let color = if is_white { WHITE_PIECE_COLOR } else { BLACK_PIECE_COLOR };
+        queue!(out, MoveTo(EVAL_BAR_LEFT, BOARD_TOP + row), SetBackgroundColor(color), Print("  "), ResetColor)?;
+    }
+    Ok(())
+}
+
+/// The best lines of the analysis with their score and depth, cut to the width of the terminal
+pub fn draw_analysis(out: &mut impl Write, lines: &[Line], game: &Game, width: u16) -> io::Result<()> {
+    for (index, line) in lines.iter().enumerate() {
+        let moves = game.numbered_san(&line.san).into_iter().take(ANALYSIS_MOVES).collect::<Vec<_>>().join(" ");
+        let text: String = format!("{:>6}  d{:<3} {}", line.score.to_string(), line.depth, moves).chars().take(width as usize).collect();
+        queue!(out, MoveTo(0, ANALYSIS_ROW + index as u16), Print(text))?;
+    }
+    Ok(())
+}
+
+/// What the game is waiting for, or how it ended
+pub fn status(game: &Game) -> String {
+    match game.endgame_status() {
+        EndgameStatus::Ongoing if game.is_promotion_pending().is_some() => "Promote to: q, r, b or n".to_string(),
+        EndgameStatus::Ongoing if game.is_in_check() => format!("{} to move, check", color_name(game.turn())),
+        EndgameStatus::Ongoing => format!("{} to move", color_name(game.turn())),
+        EndgameStatus::Win(color, WinReason::Checkmate) => format!("{} wins by checkmate", color_name(color)),
+        EndgameStatus::Win(color, WinReason::Resignation) => format!("{} wins by resignation", color_name(color)),
+        EndgameStatus::Win(color, WinReason::Timeout) => format!("{} wins on time", color_name(color)),
+        EndgameStatus::Draw(DrawReason::Agreement) => "Draw by agreement".to_string(),
+        EndgameStatus::Draw(DrawReason::Stalemate) => "Draw by stalemate".to_string(),
+        EndgameStatus::Draw(DrawReason::InsufficientMaterial) => "Draw by insufficient material".to_string(),
+        EndgameStatus::Draw(DrawReason::ThreefoldRepetition) => "Draw by threefold repetition".to_string(),
+        EndgameStatus::Draw(DrawReason::FiftyMoveRule) => "Draw by the fifty-move rule".to_string(),
+        EndgameStatus::Draw(DrawReason::FivefoldRepetition) => "Draw by fivefold repetition".to_string(),
+        EndgameStatus::Draw(DrawReason::SeventyFiveMoveRule) => "Draw by the seventy-five-move rule".to_string(),
+    }
+}
+
+/// The status of the game, the line being typed with the message below it, and the keys
+pub fn draw_prompt(out: &mut impl Write, status: &str, input: &str, message: &str, online: bool) -> io::Result<()> {
+    let commands = if online { "new draw accept decline claim resign quit" } else { "new undo draw claim resign quit" };
+    queue!(
+        out,
+        MoveTo(0, STATUS_ROW),
+        SetAttribute(Attribute::Bold),
+        Print(status),
+        SetAttribute(Attribute::Reset),
+        MoveTo(0, PROMPT_ROW),
+        Print(format!("> {}", input)),
+        SetAttribute(Attribute::Reverse),
+        Print(' '),
+        SetAttribute(Attribute::Reset),
+        MoveTo(0, PROMPT_ROW + 1),
+        style::PrintStyledContent(style::Stylize::italic(message)),
+        MoveTo(0, HELP_ROW),
+        SetAttribute(Attribute::Dim),
+        Print("Arrows move the cursor, Space picks and drops a piece, Tab flips the board, Esc cancels."),
+        MoveTo(0, HELP_ROW + 1),
+        Print("Type e4, Nxf3, O-O, e7e8q, N@f3 or e2 e4 and Enter to play. Commands: "),
+        Print(commands),
+        SetAttribute(Attribute::Reset),
+    )?;
+    Ok(())
+}
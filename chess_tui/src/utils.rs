@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use chess_core::{parse_move, Color, File, Game, Move, Rank, Square, Variant};
+
+/// The variant is given as the first command line argument, standard chess by default
+pub fn variant_from_args() -> Variant {
+    match std::env::args().nth(1).as_deref() {
+        Some("crazyhouse") => Variant::Crazyhouse,
+        Some("bughouse") => Variant::Bughouse,
+        _ => Variant::Standard,
+    }
+}
+
+/// Play against someone connected to chess_server instead of both sides on this terminal, bughouse is only played online
+pub fn online_from_args() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "online") || variant_from_args() == Variant::Bughouse
+}
+
+/// Analyze the position on a background thread and show the best lines
+pub fn analysis_from_args() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "analysis")
+}
+
+/// Time of each player in minutes, given after the "clock" argument
+pub fn clock_from_args() -> Option<Duration> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == "clock")?;
+    let minutes: f64 = args.next()?.parse().ok()?;
+    Duration::try_from_secs_f64(minutes * 60.0).ok()
+}
+
+/// Reads a typed move: two squares as in "e2 e4", coordinate notation as in "e2e4" or "N@f3", or SAN as in "Nf3"
+pub fn parse_input(game: &Game, input: &str) -> Result<Move, &'static str> {
+    if let Ok((start, end)) = parse_move(input) {
+        return Ok(Move::new(start, end));
+    }
+    if let Ok(mv) = input.trim().parse::<Move>() {
+        return Ok(mv);
+    }
+    game.parse_san(input).map_err(|_| "Unknown move or command")
+}
+
+/// Square shown at a row and column of the screen, with the bottom color's first rank at the bottom
+pub fn screen_square((row, column): (usize, usize), bottom: Color) -> Square {
+    let (rank, file) = match bottom {
+        Color::White => (7 - row, column),
+        Color::Black => (row, 7 - column),
+    };
+    Square::new(File::ALL[file], Rank::ALL[rank])
+}